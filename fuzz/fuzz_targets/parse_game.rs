@@ -0,0 +1,9 @@
+#![no_main]
+
+use howlongtobeat_scraper::parse::{self, ParseMode};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|html: &str| {
+    let _ = parse::game_with_mode(html, 1, ParseMode::Strict);
+    let _ = parse::game_with_mode(html, 1, ParseMode::Lenient);
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use howlongtobeat_scraper::parse;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|html: &str| {
+    let _ = parse::search_result(html);
+    let _ = parse::search_results(html);
+    let _ = parse::next_data(html);
+});
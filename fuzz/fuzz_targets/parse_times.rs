@@ -0,0 +1,12 @@
+#![no_main]
+
+use howlongtobeat_scraper::parse::{self, ParseMode};
+use howlongtobeat_scraper::repr::parse_duration;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|text: &str| {
+    let _ = parse::time_cell(text);
+    let _ = parse::time_row(text, ParseMode::Strict);
+    let _ = parse::time_row(text, ParseMode::Lenient);
+    let _ = parse_duration(text);
+});
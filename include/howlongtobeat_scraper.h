@@ -0,0 +1,44 @@
+#ifndef HOWLONGTOBEAT_SCRAPER_H
+#define HOWLONGTOBEAT_SCRAPER_H
+
+/* Generated with cbindgen. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Searches for a game by name and returns it as JSON
+ *
+ * Returns NULL if `name` is NULL or not valid UTF-8.
+ *
+ * # Safety
+ *
+ * `name` must be NULL or point to a NUL-terminated string that stays valid for the duration of the call.
+ */
+char *hltb_search_by_name(const char *name, bool sandbox);
+
+/**
+ * Fetches a game by its How Long to Beat ID and returns it as JSON
+ */
+char *hltb_search_by_id(uint32_t hltb_id, bool sandbox);
+
+/**
+ * Releases a string returned by this library
+ *
+ * # Safety
+ *
+ * `s` must be NULL or a pointer returned by this library that hasn't been freed yet.
+ */
+void hltb_string_free(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* HOWLONGTOBEAT_SCRAPER_H */
@@ -0,0 +1,268 @@
+//! Authenticated sessions.
+//!
+//! A session is either created by logging in with a username and password, or
+//! from a `Cookie` header copied from a logged-in browser. Sessions can be saved
+//! to disk (readable by the current user only) and reused across runs.
+
+#[cfg(feature = "browser")]
+use crate::client::host_slot_blocking;
+#[cfg(feature = "browser")]
+use crate::{launch_tab, BASE_URL};
+#[cfg(feature = "browser")]
+use headless_chrome::Tab;
+use serde::Deserialize;
+use serde::Serialize;
+#[cfg(feature = "browser")]
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+pub struct SessionCookie {
+    pub name: String,
+    pub value: String,
+    /// The expiry of the cookie in seconds since the unix epoch, `None` for session cookies
+    pub expires: Option<f64>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone, Default)]
+pub struct Session {
+    pub cookies: Vec<SessionCookie>,
+}
+
+impl Session {
+    /// Creates a session from a `Cookie` header copied from a logged-in browser
+    ///
+    /// # Arguments
+    ///
+    /// * `header`:  &str - The header value (e.g. "hltb_alias=...; OptanonConsent=...")
+    ///
+    /// returns: Result<Session, Box<dyn Error, Global>>
+    pub fn from_cookie_header(header: &str) -> Result<Session, Box<dyn Error>> {
+        let header = header.trim();
+        let header = header.strip_prefix("Cookie:").unwrap_or(header);
+        let cookies: Vec<SessionCookie> = header
+            .split(';')
+            .filter_map(|pair| {
+                let (name, value) = pair.split_once('=')?;
+                let name = name.trim();
+                if name.is_empty() {
+                    return None;
+                }
+                Some(SessionCookie {
+                    name: name.to_string(),
+                    value: value.trim().to_string(),
+                    expires: None,
+                })
+            })
+            .collect();
+        if cookies.is_empty() {
+            return Err("No cookies found in the header".into());
+        }
+        Ok(Session { cookies })
+    }
+
+    /// Formats the cookies of the session as a `Cookie` header value
+    ///
+    /// returns: String
+    pub fn cookie_header(&self) -> String {
+        self.cookies
+            .iter()
+            .map(|c| format!("{}={}", c.name, c.value))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// Sends the cookies of the session with every request of a tab
+    ///
+    /// # Arguments
+    ///
+    /// * `tab`:  &Tab - The tab to log in
+    ///
+    /// returns: Result<(), Box<dyn Error, Global>>
+    #[cfg(feature = "browser")]
+    pub(crate) fn apply(&self, tab: &Tab) -> Result<(), Box<dyn Error>> {
+        let cookie = self.cookie_header();
+        tab.set_extra_http_headers(HashMap::from([("Cookie", cookie.as_str())]))?;
+        Ok(())
+    }
+
+    /// Checks whether any cookie of the session has expired, in which case a new login is needed
+    ///
+    /// returns: bool
+    pub fn is_expired(&self) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs_f64())
+            .unwrap_or(0.0);
+        self.cookies
+            .iter()
+            .any(|c| c.expires.is_some_and(|expires| expires <= now))
+    }
+
+    /// Returns the default location of the saved session
+    ///
+    /// This is `howlongtobeat/session.json` in `%APPDATA%` on Windows, and in
+    /// `$XDG_CONFIG_HOME` (or `~/.config`) elsewhere.
+    ///
+    /// returns: Option<PathBuf>
+    pub fn default_path() -> Option<PathBuf> {
+        let config_dir = if cfg!(windows) {
+            PathBuf::from(std::env::var_os("APPDATA")?)
+        } else {
+            match std::env::var_os("XDG_CONFIG_HOME") {
+                Some(dir) => PathBuf::from(dir),
+                None => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+            }
+        };
+        Some(config_dir.join("howlongtobeat").join("session.json"))
+    }
+
+    /// Saves the session, only readable and writable by the current user on unix systems
+    ///
+    /// # Arguments
+    ///
+    /// * `path`:  &Path - Where to save the session
+    ///
+    /// returns: Result<(), Box<dyn Error, Global>>
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let file = options.open(path)?;
+        serde_json::to_writer(file, self)?;
+        Ok(())
+    }
+
+    /// Loads a previously saved session
+    ///
+    /// # Arguments
+    ///
+    /// * `path`:  &Path - Where the session was saved
+    ///
+    /// returns: Result<Session, Box<dyn Error, Global>>
+    pub fn load(path: &Path) -> Result<Session, Box<dyn Error>> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+}
+
+/// Logs in with a username and password and returns the resulting session
+///
+/// # Arguments
+///
+/// * `username`:  &str - The name of the user on How Long to Beat
+/// * `password`:  &str - The password of the user
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+///
+/// returns: Result<Session, Box<dyn Error, Global>>
+#[cfg(feature = "browser")]
+pub async fn login(
+    username: &str,
+    password: &str,
+    sandbox: bool,
+) -> Result<Session, Box<dyn Error>> {
+    let tab = launch_tab(sandbox)?;
+    let _slot = host_slot_blocking(BASE_URL, None)?;
+    tab.navigate_to(&(BASE_URL.to_owned() + "login"))?;
+    tab.wait_until_navigated()?;
+    tab.wait_for_element("input[name='username']")?
+        .click()?
+        .type_into(username)?;
+    tab.wait_for_element("input[type='password']")?
+        .click()?
+        .type_into(password)?;
+    tab.wait_for_element("button[type='submit']")?.click()?;
+    tab.wait_until_navigated()?;
+
+    if tab.get_url().contains("/login") {
+        return Err("Login failed, check the username and password".into());
+    }
+    let cookies: Vec<SessionCookie> = tab
+        .get_cookies()?
+        .into_iter()
+        .filter(|c| c.domain.contains("howlongtobeat.com"))
+        .map(|c| SessionCookie {
+            name: c.name,
+            value: c.value,
+            expires: if c.session { None } else { Some(c.expires) },
+        })
+        .collect();
+    if cookies.is_empty() {
+        return Err("Login failed, no session cookie was set".into());
+    }
+    Ok(Session { cookies })
+}
+
+/// Loads the session saved at a path if it is still valid, or logs in and saves the new session
+///
+/// # Arguments
+///
+/// * `path`:  &Path - Where the session is saved
+/// * `username`:  &str - The name of the user on How Long to Beat
+/// * `password`:  &str - The password of the user
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+///
+/// returns: Result<Session, Box<dyn Error, Global>>
+#[cfg(feature = "browser")]
+pub async fn load_or_login(
+    path: &Path,
+    username: &str,
+    password: &str,
+    sandbox: bool,
+) -> Result<Session, Box<dyn Error>> {
+    if let Ok(session) = Session::load(path) {
+        if !session.is_expired() {
+            return Ok(session);
+        }
+    }
+    let session = login(username, password, sandbox).await?;
+    session.save(path)?;
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_cookie_header() {
+        let session = Session::from_cookie_header("Cookie: hltb_alias=abc123; theme=dark").unwrap();
+        assert_eq!(session.cookies.len(), 2);
+        assert_eq!(session.cookies[0].name, "hltb_alias");
+        assert_eq!(session.cookie_header(), "hltb_alias=abc123; theme=dark");
+        assert!(Session::from_cookie_header(" ; ").is_err());
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let mut session = Session::from_cookie_header("hltb_alias=abc123").unwrap();
+        assert!(!session.is_expired());
+        session.cookies[0].expires = Some(1.0);
+        assert!(session.is_expired());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join("hltb_session_test/session.json");
+        let session = Session::from_cookie_header("hltb_alias=abc123").unwrap();
+        session.save(&path).unwrap();
+        let loaded = Session::load(&path).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        assert_eq!(loaded, session);
+    }
+}
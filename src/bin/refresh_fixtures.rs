@@ -0,0 +1,70 @@
+//! Re-downloads the pages the offline tests replay.
+//!
+//! The tests of the crate fetch their pages from the site, or from a cassette when
+//! `HLTB_REPLAY` is set. This records a fresh cassette from the live site, so a change
+//! of the site's markup can be captured and the tests updated against it in one step:
+//!
+//! ```text
+//! cargo run --bin refresh-fixtures [cassette]
+//! HLTB_REPLAY=fixtures/hltb.json cargo test
+//! ```
+
+use howlongtobeat_scraper::HltbClient;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::{env, fs, process};
+
+/// Where the cassette is written unless another path is given
+const DEFAULT_CASSETTE: &str = "fixtures/hltb.json";
+
+/// The names searched by the tests
+const SEARCHES: &[&str] = &["Metal Gear", "Helldivers 2"];
+
+/// The IDs of the details pages fetched by the tests
+const DETAILS: &[u32] = &[5900, 129232];
+
+#[tokio::main]
+async fn main() {
+    let path = env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_CASSETTE));
+    if let Err(e) = refresh(&path).await {
+        eprintln!("Failed to refresh {}: {}", path.display(), e);
+        process::exit(1);
+    }
+    println!(
+        "Recorded {} pages to {}",
+        SEARCHES.len() + DETAILS.len(),
+        path.display()
+    );
+}
+
+/// Records every fixture to a new cassette, so pages the tests no longer use are dropped
+///
+/// The pages are recorded next to the cassette, which is only replaced once they all are.
+///
+/// # Arguments
+///
+/// * `path`:  &Path - The cassette file to write
+///
+/// returns: Result<(), Box<dyn Error, Global>>
+async fn refresh(path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut recording = path.as_os_str().to_owned();
+    recording.push(".recording");
+    let recording = PathBuf::from(recording);
+    if recording.exists() {
+        fs::remove_file(&recording)?;
+    }
+    let client = HltbClient::builder().record(&recording).build()?;
+    for name in SEARCHES {
+        println!("Searching {}", name);
+        client.search_html(name).await?;
+    }
+    for hltb_id in DETAILS {
+        println!("Fetching game {}", hltb_id);
+        client.detail_html(*hltb_id).await?;
+    }
+    fs::rename(&recording, path)?;
+    Ok(())
+}
@@ -0,0 +1,155 @@
+//! Per-platform browse listings.
+
+use crate::parse::{game_cards, SearchResult, GAME_LINK_SELECTOR};
+use crate::{fetch_page_content, BASE_URL};
+use scraper::{Html, Selector};
+use std::error::Error;
+use urlencoding::encode;
+
+/// The maximum number of pages read from a platform listing, as a guard against endless pagination
+const MAX_PLATFORM_PAGES: u32 = 2000;
+
+/// Returns the URL of a page of the listing of a platform
+///
+/// # Arguments
+///
+/// * `platform`:  &str - The HLTB name of the platform (e.g. "PlayStation 4")
+/// * `page`:  u32 - The page of the listing, starting at 1
+///
+/// returns: String
+pub fn platform_url(platform: &str, page: u32) -> String {
+    format!("{}platform/{}/{}", BASE_URL, encode(platform.trim()), page)
+}
+
+/// Fetches a single page of the games of a platform, in the order of the site
+///
+/// # Arguments
+///
+/// * `platform`:  &str - The HLTB name of the platform (e.g. "PlayStation 4")
+/// * `page`:  u32 - The page of the listing, starting at 1
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+///
+/// returns: Result<Vec<SearchResult>, Box<dyn Error, Global>>
+pub async fn get_platform_page_with_sandbox(
+    platform: &str,
+    page: u32,
+    sandbox: bool,
+) -> Result<Vec<SearchResult>, Box<dyn Error>> {
+    let url = platform_url(platform, page.max(1));
+    let content = fetch_page_content(&url, "main a[href*='/game/']", sandbox)?;
+    Ok(parse_platform_page(&content, &url))
+}
+
+/// Fetches a single page of the games of a platform (with sandbox enabled by default)
+///
+/// # Arguments
+///
+/// * `platform`:  &str - The HLTB name of the platform (e.g. "PlayStation 4")
+/// * `page`:  u32 - The page of the listing, starting at 1
+///
+/// returns: Result<Vec<SearchResult>, Box<dyn Error, Global>>
+pub async fn get_platform_page(
+    platform: &str,
+    page: u32,
+) -> Result<Vec<SearchResult>, Box<dyn Error>> {
+    get_platform_page_with_sandbox(platform, page, true).await
+}
+
+/// Fetches every game of a platform, following pagination
+///
+/// Large platforms span hundreds of pages, each rendered in the browser, and a page that
+/// can't be fetched fails the whole listing rather than truncating it: prefer
+/// [`get_platform_page_with_sandbox`] to build a dataset incrementally.
+///
+/// # Arguments
+///
+/// * `platform`:  &str - The HLTB name of the platform (e.g. "PlayStation 4")
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+///
+/// returns: Result<Vec<SearchResult>, Box<dyn Error, Global>>
+pub async fn get_platform_games_with_sandbox(
+    platform: &str,
+    sandbox: bool,
+) -> Result<Vec<SearchResult>, Box<dyn Error>> {
+    let mut games: Vec<SearchResult> = Vec::new();
+    for page in 1..=MAX_PLATFORM_PAGES {
+        let page_games = get_platform_page_with_sandbox(platform, page, sandbox).await?;
+        // Past the last page the site keeps serving the last one
+        let last_ids = games[games.len().saturating_sub(page_games.len())..]
+            .iter()
+            .map(|game| game.hltb_id);
+        if page_games.is_empty() || last_ids.eq(page_games.iter().map(|game| game.hltb_id)) {
+            break;
+        }
+        games.extend(page_games);
+    }
+    Ok(games)
+}
+
+/// Fetches every game of a platform, following pagination (with sandbox enabled by default)
+///
+/// # Arguments
+///
+/// * `platform`:  &str - The HLTB name of the platform (e.g. "PlayStation 4")
+///
+/// returns: Result<Vec<SearchResult>, Box<dyn Error, Global>>
+pub async fn get_platform_games(platform: &str) -> Result<Vec<SearchResult>, Box<dyn Error>> {
+    get_platform_games_with_sandbox(platform, true).await
+}
+
+/// Parses the games of a page of a platform listing
+///
+/// The games are the links to details pages of the main content, each game being listed once.
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the listing page
+/// * `url`:  &str - The URL of the listing page, recorded on the results
+///
+/// returns: Vec<SearchResult>
+fn parse_platform_page(content: &str, url: &str) -> Vec<SearchResult> {
+    let document = Html::parse_document(content);
+    let link_selector = Selector::parse(&format!("main {}", GAME_LINK_SELECTOR)).unwrap();
+    game_cards(document.root_element(), &link_selector)
+        .into_iter()
+        .map(|card| SearchResult {
+            hltb_id: card.hltb_id,
+            title: card.title,
+            search_url: Some(url.to_string()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_platform_url() {
+        assert_eq!(
+            platform_url("PlayStation 4", 2),
+            "https://howlongtobeat.com/platform/PlayStation%204/2"
+        );
+    }
+
+    #[test]
+    fn test_parse_platform_page() {
+        let url = platform_url("NES", 1);
+        let content = r#"<html><body>
+            <nav><a href="/game/1">Header link</a></nav>
+            <main><ul>
+                <li><a href="/game/5900"><img></a><h3><a href="/game/5900">Metal Gear</a></h3></li>
+                <li><a href="/game/4102" title="Contra"><img></a></li>
+            </ul></main>
+        </body></html>"#;
+        let games = parse_platform_page(content, &url);
+        assert_eq!(
+            games
+                .iter()
+                .map(|game| (game.hltb_id, game.title.as_str()))
+                .collect::<Vec<_>>(),
+            [(5900, "Metal Gear"), (4102, "Contra")]
+        );
+        assert_eq!(games[0].search_url.as_deref(), Some(url.as_str()));
+    }
+}
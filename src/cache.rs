@@ -0,0 +1,473 @@
+//! On-disk cache of resolved games.
+//!
+//! Every entry is stored as `{"version": N, "game": {...}}` so that entries written
+//! by older versions of the crate keep loading after `Game` changes: fields added
+//! since then take their serde defaults, and [`MIGRATIONS`] rewrites anything that
+//! was renamed or reshaped. Entries from before the envelope existed (a bare
+//! `Game` object) are read as version 0.
+//!
+//! Searches by name are cached too, as the ID their name resolved to along with the
+//! results it was picked from, keyed by the name once normalized, so "Hollow Knight",
+//! "hollow knight" and "Hollow  Knight " share an entry and a single search.
+//!
+//! [`DiskCache::warm_up`] fills a cache from a JSON Lines export, so a new deployment
+//! starts with the games another one already fetched.
+//!
+//! A [`CachePolicy`] tells how long an entry is served as is: forever, or until a
+//! soft TTL after which it is still served but refreshed in the background.
+
+use crate::parse::{normalize_title, SearchResult};
+use crate::Game;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// The version of the entries written by this version of the crate
+pub const CACHE_VERSION: u32 = 1;
+
+/// A migration rewrites the JSON of a cached game from one version to the next
+pub type Migration = fn(&mut Value);
+
+/// The built-in migrations: `MIGRATIONS[n]` upgrades an entry from version `n + 1` to `n + 2`
+/// (version 0 entries are version 1 entries without the envelope)
+const MIGRATIONS: &[Migration] = &[];
+
+#[derive(Serialize)]
+struct Entry<'a> {
+    version: u32,
+    game: &'a Game,
+}
+
+/// Encodes a game as a versioned cache entry
+///
+/// # Arguments
+///
+/// * `game`:  &Game - The game to cache
+///
+/// returns: Result<String, Box<dyn Error, Global>>
+pub fn encode_entry(game: &Game) -> Result<String, Box<dyn Error>> {
+    Ok(serde_json::to_string(&Entry {
+        version: CACHE_VERSION,
+        game,
+    })?)
+}
+
+/// Decodes a cache entry written by any version of the crate
+///
+/// # Arguments
+///
+/// * `content`:  &str - The cache entry
+///
+/// returns: Result<Game, Box<dyn Error, Global>>
+pub fn decode_entry(content: &str) -> Result<Game, Box<dyn Error>> {
+    decode_entry_with(content, |_, _| {})
+}
+
+/// Decodes a cache entry written by any version of the crate, with a hook to migrate custom data
+///
+/// The hook runs after the built-in migrations, with the version the entry was written in.
+///
+/// # Arguments
+///
+/// * `content`:  &str - The cache entry
+/// * `hook`:  F - Called with the original version and the migrated JSON of the game
+///
+/// returns: Result<Game, Box<dyn Error, Global>>
+pub fn decode_entry_with<F>(content: &str, hook: F) -> Result<Game, Box<dyn Error>>
+where
+    F: FnOnce(u32, &mut Value),
+{
+    let value: Value = serde_json::from_str(content)?;
+    let (version, mut game) = match value {
+        Value::Object(mut entry) if entry.contains_key("version") && entry.contains_key("game") => {
+            let version = entry["version"]
+                .as_u64()
+                .ok_or("Invalid cache entry version")? as u32;
+            (version, entry.remove("game").unwrap())
+        }
+        game => (0, game),
+    };
+    if version > CACHE_VERSION {
+        return Err(format!(
+            "Cache entry version {} is newer than the supported version {}",
+            version, CACHE_VERSION
+        )
+        .into());
+    }
+    for migration in MIGRATIONS.iter().skip(version.saturating_sub(1) as usize) {
+        migration(&mut game);
+    }
+    hook(version, &mut game);
+    Ok(serde_json::from_value(game)?)
+}
+
+/// How long cached games are served as they are
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CachePolicy {
+    /// Cached games are served for as long as they are cached
+    #[default]
+    Forever,
+    /// Cached games fetched at least `soft_ttl` ago are still served at once, and queued to
+    /// be refreshed in the background, so responses are instant and eventually fresh
+    StaleWhileRevalidate { soft_ttl: Duration },
+}
+
+impl CachePolicy {
+    /// Checks whether a cached game should be refreshed
+    ///
+    /// Games without a fetch date, cached by older versions of the crate, are stale.
+    ///
+    /// # Arguments
+    ///
+    /// * `game`:  &Game - The cached game
+    /// * `now`:  SystemTime - The current time
+    ///
+    /// returns: bool
+    pub fn is_stale(&self, game: &Game, now: SystemTime) -> bool {
+        self.is_stale_since(game.fetched_at, now)
+    }
+
+    /// Checks whether a cached search should be made again
+    ///
+    /// # Arguments
+    ///
+    /// * `query`:  &CachedQuery - The cached search
+    /// * `now`:  SystemTime - The current time
+    ///
+    /// returns: bool
+    pub fn is_query_stale(&self, query: &CachedQuery, now: SystemTime) -> bool {
+        self.is_stale_since(query.fetched_at, now)
+    }
+
+    fn is_stale_since(&self, fetched_at: Option<SystemTime>, now: SystemTime) -> bool {
+        match self {
+            CachePolicy::Forever => false,
+            CachePolicy::StaleWhileRevalidate { soft_ttl } => match fetched_at {
+                Some(fetched_at) => now
+                    .duration_since(fetched_at)
+                    .is_ok_and(|age| age >= *soft_ttl),
+                None => true,
+            },
+        }
+    }
+}
+
+/// The cached outcome of a search by name
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CachedQuery {
+    /// The ID of the game the name resolved to
+    pub hltb_id: u32,
+    /// Every result of the search page the game was picked from
+    #[serde(default)]
+    pub results: Vec<SearchResult>,
+    /// The spelling that found the game, when the searched name had no results
+    #[serde(default)]
+    pub variant: Option<String>,
+    /// When the search was made
+    #[serde(default, with = "crate::repr::timestamp_string")]
+    pub fetched_at: Option<SystemTime>,
+}
+
+impl CachedQuery {
+    /// Describes a search made now
+    ///
+    /// # Arguments
+    ///
+    /// * `hltb_id`:  u32 - The ID of the game the name resolved to
+    /// * `results`:  Vec<SearchResult> - Every result of the search page
+    /// * `variant`:  Option<String> - The spelling that found the game, if not the name
+    ///
+    /// returns: CachedQuery
+    pub fn new(hltb_id: u32, results: Vec<SearchResult>, variant: Option<String>) -> CachedQuery {
+        CachedQuery {
+            hltb_id,
+            results,
+            variant,
+            fetched_at: Some(SystemTime::now()),
+        }
+    }
+}
+
+/// A cached search under the normalized name it was made for
+#[derive(Serialize, Deserialize)]
+struct QueryEntry {
+    query: String,
+    #[serde(flatten)]
+    cached: CachedQuery,
+}
+
+/// Hashes a normalized name into the file name of its entry (64-bit FNV-1a, stable across
+/// versions and platforms)
+fn query_key(query: &str) -> String {
+    let hash = query.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+impl DiskCache {
+    /// Creates a cache storing one file per game in a directory
+    ///
+    /// # Arguments
+    ///
+    /// * `dir`:  impl Into<PathBuf> - The directory of the cache, created on the first write
+    ///
+    /// returns: DiskCache
+    pub fn new(dir: impl Into<PathBuf>) -> DiskCache {
+        DiskCache { dir: dir.into() }
+    }
+
+    fn path(&self, hltb_id: u32) -> PathBuf {
+        self.dir.join(format!("{}.json", hltb_id))
+    }
+
+    /// Returns the cached game with an ID, if any
+    ///
+    /// Entries that can't be read or decoded are treated as missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+    ///
+    /// returns: Option<Game>
+    pub fn get(&self, hltb_id: u32) -> Option<Game> {
+        let content = fs::read_to_string(self.path(hltb_id)).ok()?;
+        decode_entry(&content).ok()
+    }
+
+    /// Stores a game in the cache, replacing any previous entry
+    ///
+    /// A game the site redirected to from another ID is also stored under that ID, so
+    /// lookups of the old ID are served the canonical game.
+    ///
+    /// # Arguments
+    ///
+    /// * `game`:  &Game - The game to cache
+    ///
+    /// returns: Result<(), Box<dyn Error, Global>>
+    pub fn put(&self, game: &Game) -> Result<(), Box<dyn Error>> {
+        fs::create_dir_all(&self.dir)?;
+        let entry = encode_entry(game)?;
+        fs::write(self.path(game.hltb_id), &entry)?;
+        if let Some(from) = game.redirected_from {
+            fs::write(self.path(from), &entry)?;
+        }
+        Ok(())
+    }
+
+    fn query_path(&self, query: &str) -> PathBuf {
+        self.dir
+            .join("queries")
+            .join(format!("{}.json", query_key(query)))
+    }
+
+    /// Returns the last search of a name, in any spelling [`normalize_title`] folds, if
+    /// cached
+    ///
+    /// # Arguments
+    ///
+    /// * `name`:  &str - The searched name
+    ///
+    /// returns: Option<CachedQuery>
+    pub fn get_query(&self, name: &str) -> Option<CachedQuery> {
+        let query = normalize_title(name);
+        let content = fs::read_to_string(self.query_path(&query)).ok()?;
+        let entry: QueryEntry = serde_json::from_str(&content).ok()?;
+        // Different names whose keys collide don't share an entry
+        (entry.query == query).then_some(entry.cached)
+    }
+
+    /// Stores the search of a name, replacing any previous entry of the name
+    ///
+    /// # Arguments
+    ///
+    /// * `name`:  &str - The searched name
+    /// * `cached`:  &CachedQuery - The outcome of the search
+    ///
+    /// returns: Result<(), Box<dyn Error, Global>>
+    pub fn put_query(&self, name: &str, cached: &CachedQuery) -> Result<(), Box<dyn Error>> {
+        let query = normalize_title(name);
+        if query.is_empty() {
+            return Ok(());
+        }
+        let path = self.query_path(&query);
+        let entry = QueryEntry {
+            query,
+            cached: cached.clone(),
+        };
+        fs::create_dir_all(self.dir.join("queries"))?;
+        fs::write(path, serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+
+    /// Fills the cache with the games of a JSON Lines export, e.g. written by
+    /// [`JsonlExporter`](crate::export::jsonl::JsonlExporter)
+    ///
+    /// Lines can be games or cache entries. Lines that don't parse are skipped, and so are the
+    /// games already cached from a fetch at least as recent.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`:  &Path - The exported file
+    ///
+    /// returns: Result<usize, Box<dyn Error, Global>> - The number of games imported
+    pub fn warm_up(&self, path: &Path) -> Result<usize, Box<dyn Error>> {
+        let mut imported = 0;
+        for line in BufReader::new(File::open(path)?).lines() {
+            let Ok(game) = decode_entry(&line?) else {
+                continue;
+            };
+            let cached = self.get(game.hltb_id);
+            if cached.is_some_and(|cached| cached.fetched_at >= game.fetched_at) {
+                continue;
+            }
+            self.put(&game)?;
+            imported += 1;
+        }
+        Ok(imported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_legacy_entry() {
+        let content = r#"{"hltb_id": 5900, "title": "Metal Gear", "main_story": null,
+            "main_extra": null, "completionist": null, "all_styles": null, "co_op": null, "vs": null}"#;
+        let mut seen_version = None;
+        let game = decode_entry_with(content, |version, _| seen_version = Some(version)).unwrap();
+        assert_eq!(seen_version, Some(0));
+        assert_eq!(game.title, "Metal Gear");
+        assert!(game.platforms.is_empty());
+        assert_eq!(game.release_year, None);
+    }
+
+    #[test]
+    fn test_disk_cache_round_trip() {
+        let dir = std::env::temp_dir().join("hltb_cache_test");
+        let cache = DiskCache::new(&dir);
+        let game = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        cache.put(&game).unwrap();
+        let cached = cache.get(5900);
+        let missing = cache.get(1);
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(cached.is_some_and(|cached| cached.data_eq(&game)));
+        assert!(missing.is_none());
+
+        let mut merged = game.clone();
+        merged.hltb_id = 5901;
+        merged.redirected_from = Some(5900);
+        cache.put(&merged).unwrap();
+        let redirected = (cache.get(5900), cache.get(5901));
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(redirected.0.is_some_and(|game| game.data_eq(&merged)));
+        assert!(redirected.1.is_some_and(|game| game.data_eq(&merged)));
+
+        let newer = r#"{"version": 99, "game": {}}"#;
+        assert!(decode_entry(newer).is_err());
+    }
+
+    #[test]
+    fn test_query_cache() {
+        let dir = std::env::temp_dir().join("hltb_query_cache_test");
+        let cache = DiskCache::new(&dir);
+        let result = SearchResult {
+            hltb_id: 26286,
+            title: "Hollow Knight".to_string(),
+            search_url: None,
+        };
+        let cached = CachedQuery::new(26286, vec![result], Some("Hollow Knight".to_string()));
+        cache.put_query("Hollow Knigth", &cached).unwrap();
+        let hits = [
+            cache.get_query("hollow knigth"),
+            cache.get_query("  Hollow   Knigth "),
+            cache.get_query("Hollow Knight: Silksong"),
+        ];
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(hits, [Some(cached.clone()), Some(cached.clone()), None]);
+        let policy = CachePolicy::StaleWhileRevalidate {
+            soft_ttl: Duration::from_secs(3600),
+        };
+        let later = SystemTime::now() + Duration::from_secs(7200);
+        assert!(!policy.is_query_stale(&cached, SystemTime::now()));
+        assert!(policy.is_query_stale(&cached, later));
+    }
+
+    #[test]
+    fn test_warm_up() {
+        let dir = std::env::temp_dir().join("hltb_warm_up_test");
+        let export = std::env::temp_dir().join("hltb_warm_up_test.jsonl");
+        let game = |title: &str, hltb_id: u32, age: u64| {
+            let mut game = Game::new(
+                title.to_string(),
+                hltb_id,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            );
+            game.fetched_at = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 - age));
+            game
+        };
+        let cache = DiskCache::new(&dir);
+        cache.put(&game("Contra (cached)", 2065, 0)).unwrap();
+        let lines = [
+            serde_json::to_string(&game("Metal Gear", 5900, 10)).unwrap(),
+            "{\"hltb_id\": 1, \"tit".to_string(),
+            serde_json::to_string(&game("Contra", 2065, 10)).unwrap(),
+        ];
+        fs::write(&export, lines.join("\n")).unwrap();
+        let imported = cache.warm_up(&export).unwrap();
+        let cached = (cache.get(5900), cache.get(2065));
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_file(&export).unwrap();
+        assert_eq!(imported, 1);
+        assert!(cached.0.is_some_and(|game| game.title == "Metal Gear"));
+        assert!(cached.1.is_some_and(|game| game.title == "Contra (cached)"));
+    }
+
+    #[test]
+    fn test_cache_policy() {
+        let now = SystemTime::now();
+        let mut game = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let policy = CachePolicy::StaleWhileRevalidate {
+            soft_ttl: Duration::from_secs(3600),
+        };
+        assert!(policy.is_stale(&game, now));
+        game.fetched_at = Some(now - Duration::from_secs(60));
+        assert!(!policy.is_stale(&game, now));
+        game.fetched_at = Some(now - Duration::from_secs(7200));
+        assert!(policy.is_stale(&game, now));
+        assert!(!CachePolicy::Forever.is_stale(&game, now));
+    }
+}
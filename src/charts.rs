@@ -0,0 +1,221 @@
+//! Monthly and annual charts of the most played and most completed games.
+
+use crate::parse::{game_id_from_href, GAME_LINK_SELECTOR};
+use crate::{fetch_page_content, BASE_URL};
+use scraper::{Html, Selector};
+use serde::Deserialize;
+use serde::Serialize;
+use std::error::Error;
+
+/// What a chart ranks games by
+#[derive(Deserialize, Debug, PartialEq, Eq, Serialize, Clone, Copy)]
+pub enum ChartKind {
+    /// The games the most users started playing
+    MostPlayed,
+    /// The games the most users completed
+    MostCompleted,
+}
+
+impl ChartKind {
+    /// Returns the value of the chart kind in the URL of the report page
+    ///
+    /// returns: &str
+    fn param(&self) -> &'static str {
+        match self {
+            ChartKind::MostPlayed => "playing",
+            ChartKind::MostCompleted => "completed",
+        }
+    }
+}
+
+/// The period a chart covers
+#[derive(Deserialize, Debug, PartialEq, Eq, Serialize, Clone, Copy)]
+pub enum ChartPeriod {
+    /// A month of a year, the month being from 1 to 12
+    Month {
+        year: u16,
+        month: u8,
+    },
+    Year(u16),
+}
+
+/// A game of a chart
+#[derive(Deserialize, Debug, PartialEq, Eq, Serialize, Clone)]
+pub struct ChartEntry {
+    /// The position of the game in the chart, starting at 1
+    pub rank: usize,
+    pub hltb_id: u32,
+    pub title: String,
+    /// How many users played or completed the game over the period
+    pub count: Option<u32>,
+}
+
+/// Returns the URL of the report page of a chart
+///
+/// # Arguments
+///
+/// * `kind`:  ChartKind - What the chart ranks games by
+/// * `period`:  ChartPeriod - The period of the chart
+///
+/// returns: String
+pub fn chart_url(kind: ChartKind, period: ChartPeriod) -> String {
+    match period {
+        ChartPeriod::Month { year, month } => format!(
+            "{}stats?type={}&year={}&month={}",
+            BASE_URL,
+            kind.param(),
+            year,
+            month
+        ),
+        ChartPeriod::Year(year) => {
+            format!("{}stats?type={}&year={}", BASE_URL, kind.param(), year)
+        }
+    }
+}
+
+/// Fetches a chart of the most played or most completed games
+///
+/// # Arguments
+///
+/// * `kind`:  ChartKind - What the chart ranks games by
+/// * `period`:  ChartPeriod - The period of the chart
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+///
+/// returns: Result<Vec<ChartEntry>, Box<dyn Error, Global>>
+pub async fn get_chart_with_sandbox(
+    kind: ChartKind,
+    period: ChartPeriod,
+    sandbox: bool,
+) -> Result<Vec<ChartEntry>, Box<dyn Error>> {
+    if let ChartPeriod::Month { month, .. } = period {
+        if !(1..=12).contains(&month) {
+            return Err(format!("Invalid month: {}", month).into());
+        }
+    }
+    let content = fetch_page_content(&chart_url(kind, period), "main table", sandbox)?;
+    Ok(parse_chart(&content))
+}
+
+/// Fetches a chart of the most played or most completed games (with sandbox enabled by default)
+///
+/// # Arguments
+///
+/// * `kind`:  ChartKind - What the chart ranks games by
+/// * `period`:  ChartPeriod - The period of the chart
+///
+/// returns: Result<Vec<ChartEntry>, Box<dyn Error, Global>>
+pub async fn get_chart(
+    kind: ChartKind,
+    period: ChartPeriod,
+) -> Result<Vec<ChartEntry>, Box<dyn Error>> {
+    get_chart_with_sandbox(kind, period, true).await
+}
+
+/// Parses a number as written on the site, e.g. "#1" or "1,024"
+fn parse_number(text: &str) -> Option<u32> {
+    text.trim()
+        .trim_start_matches('#')
+        .trim_end_matches('.')
+        .replace(',', "")
+        .parse()
+        .ok()
+}
+
+/// Parses the games of a chart report page
+///
+/// Each row of the table is a game: its link gives the game, a leading number its rank
+/// (its position otherwise) and the last number of the row its count.
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the report page
+///
+/// returns: Vec<ChartEntry>
+fn parse_chart(content: &str) -> Vec<ChartEntry> {
+    let document = Html::parse_document(content);
+    let row_selector = Selector::parse("main table tbody tr").unwrap();
+    let cell_selector = Selector::parse("td").unwrap();
+    let link_selector = Selector::parse(GAME_LINK_SELECTOR).unwrap();
+
+    let mut entries = Vec::new();
+    for row in document.select(&row_selector) {
+        let Some(link) = row.select(&link_selector).next() else {
+            continue;
+        };
+        let Some(hltb_id) = link.value().attr("href").and_then(game_id_from_href) else {
+            continue;
+        };
+        let cells: Vec<String> = row
+            .select(&cell_selector)
+            .map(|cell| cell.text().collect::<String>())
+            .collect();
+        let rank = cells
+            .first()
+            .and_then(|cell| parse_number(cell))
+            .map_or(entries.len() + 1, |rank| rank as usize);
+        let count = cells
+            .iter()
+            .skip(1)
+            .rev()
+            .find_map(|cell| parse_number(cell));
+        entries.push(ChartEntry {
+            rank,
+            hltb_id,
+            title: link.text().collect::<String>().trim().to_string(),
+            count,
+        });
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chart_url() {
+        assert_eq!(
+            chart_url(
+                ChartKind::MostPlayed,
+                ChartPeriod::Month {
+                    year: 2024,
+                    month: 2
+                }
+            ),
+            "https://howlongtobeat.com/stats?type=playing&year=2024&month=2"
+        );
+        assert_eq!(
+            chart_url(ChartKind::MostCompleted, ChartPeriod::Year(2023)),
+            "https://howlongtobeat.com/stats?type=completed&year=2023"
+        );
+    }
+
+    #[test]
+    fn test_parse_chart() {
+        let content = r#"<html><body><main><table>
+            <thead><tr><th>#</th><th>Game</th><th>Players</th></tr></thead>
+            <tbody>
+                <tr><td>#1</td><td><a href="/game/129232">Helldivers 2</a></td><td>12,408</td></tr>
+                <tr><td>#2</td><td><a href="/game/5900">Metal Gear</a></td><td>--</td></tr>
+                <tr><td colspan="3">Advertisement</td></tr>
+            </tbody>
+        </table></main></body></html>"#;
+        assert_eq!(
+            parse_chart(content),
+            vec![
+                ChartEntry {
+                    rank: 1,
+                    hltb_id: 129232,
+                    title: "Helldivers 2".to_string(),
+                    count: Some(12408),
+                },
+                ChartEntry {
+                    rank: 2,
+                    hltb_id: 5900,
+                    title: "Metal Gear".to_string(),
+                    count: None,
+                },
+            ]
+        );
+    }
+}
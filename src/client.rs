@@ -0,0 +1,2552 @@
+//! Configurable client.
+//!
+//! [`HltbClient`] gathers every setting of the scraper (backend, timeouts,
+//! retries, cache, rate limit, user agent, proxy, base URL) in one place. The
+//! free functions of the crate are thin wrappers around a default client.
+//!
+//! Lookups can be aborted with a [`CancellationToken`] (see
+//! [`HltbClient::search_by_name_with_cancel`]) or by dropping their future: either
+//! way the browser is closed as soon as the pending wait notices.
+//!
+//! Settings are layered: the defaults, then the `HLTB_*` environment variables
+//! (see [`ClientConfig::from_env`]), then the calls made on the builder.
+
+use crate::cache::{CachePolicy, CachedQuery, DiskCache};
+use crate::distribution::{self, Distribution};
+#[cfg(feature = "browser")]
+use crate::error::sendable;
+#[cfg(feature = "browser")]
+use crate::fetch_page_content_in_tab;
+#[cfg(feature = "browser")]
+use crate::guard::BrowserGuard;
+use crate::history::HistoryStore;
+use crate::layout::{self, LayoutFingerprint};
+use crate::overrides::TitleOverrides;
+use crate::parse::{
+    self, ParseMode, SearchResult, TitleCleaning, DETAILS_TABLE_SELECTOR, SEARCH_HEADER_SELECTOR,
+};
+use crate::query::SearchQuery;
+use crate::release::ReleaseDates;
+#[cfg(feature = "http")]
+use crate::report::{self, Cover};
+use crate::timings::Timings;
+#[cfg(all(feature = "browser", feature = "http"))]
+use crate::BackendAttempt;
+use crate::{CancellationToken, Game, HltbError, PlayStyle, BASE_URL, USER_AGENT};
+#[cfg(feature = "http")]
+use scraper::{Html, Selector};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "browser")]
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
+#[cfg(feature = "browser")]
+use tokio::sync::TryAcquireError;
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+use urlencoding::encode;
+
+mod audit;
+mod backlog;
+mod batch;
+mod dry_run;
+mod local;
+#[cfg(feature = "test-util")]
+pub mod mock;
+mod observer;
+mod request;
+mod revalidate;
+#[cfg(feature = "browser")]
+mod session;
+mod vcr;
+
+#[cfg(feature = "audit")]
+pub use audit::read_audit_log;
+#[cfg(feature = "audit")]
+use audit::AuditLog;
+pub use audit::{AuditEntry, AuditOutcome};
+pub use backlog::{BacklogEntry, BacklogItem, BacklogReport};
+pub use batch::{AmbiguousTitle, BatchProgress, BatchReport, Candidate, UnmatchedTitle};
+pub use dry_run::PlannedRequest;
+#[cfg(feature = "test-util")]
+pub use mock::MockBackend;
+pub use observer::Observer;
+use request::Query;
+pub use request::SearchRequest;
+#[cfg(feature = "browser")]
+pub use session::HltbSession;
+use vcr::Cassette;
+pub use vcr::VcrMode;
+
+/// The delay before the first retry of a failed request, doubled on every retry
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// How many times the retry delay is doubled at most, i.e. 32 s between later retries
+const MAX_RETRY_DOUBLINGS: u32 = 6;
+
+/// How long an idle connection of the HTTP backend is kept open for the next requests
+#[cfg(feature = "http")]
+const HTTP_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// The interval of the HTTP/2 pings that keep the connection of the HTTP backend alive
+#[cfg(feature = "http")]
+const HTTP2_KEEP_ALIVE: Duration = Duration::from_secs(30);
+
+/// The default maximum size of a page body, once decompressed
+const MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// The maximum number of requests in flight to a host, shared by every client of the process
+/// whatever their configuration
+pub const MAX_CONCURRENT_REQUESTS: usize = 4;
+
+/// The request slots of every host requested by the process, see [`MAX_CONCURRENT_REQUESTS`]
+static HOST_SLOTS: OnceLock<Mutex<HashMap<String, Arc<Semaphore>>>> = OnceLock::new();
+
+/// How often a blocking thread checks whether a request slot was released
+#[cfg(feature = "browser")]
+const HOST_SLOT_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// How pages are fetched, each backend being available with the feature of the same name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// Renders pages in headless Chrome, the default when the `browser` feature is enabled
+    #[cfg(feature = "browser")]
+    #[default]
+    Browser,
+    /// Fetches pages with plain HTTP requests, without launching a browser
+    ///
+    /// Only pages rendered by the server can be read this way, which excludes the search
+    /// page: lookups by name need the browser backend.
+    #[cfg(feature = "http")]
+    #[cfg_attr(not(feature = "browser"), default)]
+    Http,
+    /// Serves canned pages from a [`MockBackend`], set with [`HltbClientBuilder::mock`]
+    #[cfg(feature = "test-util")]
+    Mock,
+}
+
+/// The pages the client fetches, which can each be waited for differently
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageKind {
+    Search,
+    Details,
+}
+
+impl PageKind {
+    /// Returns the selector that only matches once the page is rendered
+    ///
+    /// The search page is rendered once its results header is, so that a search without
+    /// results is read, for the spelling the site suggests, rather than waited for.
+    ///
+    /// returns: &str
+    fn selector(&self) -> &'static str {
+        match self {
+            PageKind::Search => SEARCH_HEADER_SELECTOR,
+            PageKind::Details => DETAILS_TABLE_SELECTOR,
+        }
+    }
+
+    /// Returns the selectors of the parts of the page the parsers read
+    ///
+    /// returns: &[&str]
+    #[cfg(feature = "browser")]
+    fn subtrees(&self) -> &'static [&'static str] {
+        match self {
+            PageKind::Search => &[SEARCH_HEADER_SELECTOR, parse::SEARCH_SUGGESTION_SELECTOR],
+            PageKind::Details => &[
+                parse::HEADER_SELECTOR,
+                "main div[class*='_profile_info']",
+                "main table",
+                parse::SUBMIT_LINK_SELECTOR,
+                "script#__NEXT_DATA__",
+            ],
+        }
+    }
+}
+
+/// How the browser backend decides that a page is rendered
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum WaitStrategy {
+    /// Until the element the parser reads appears, the most reliable
+    #[default]
+    Selector,
+    /// Until the page has loaded and no resource started loading for a while, which also
+    /// waits for late content such as images
+    NetworkIdle { quiet: Duration },
+    /// Until a JavaScript expression evaluates to a truthy value in the page, e.g.
+    /// `"document.querySelectorAll('li').length > 3"`
+    Function(String),
+}
+
+/// A preset of the options Chrome is launched with by the browser backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LaunchProfile {
+    /// The options of Chrome, with the sandbox set by [`ClientConfig::sandbox`]
+    #[default]
+    Default,
+    /// Options that let headless Chrome run in Docker and CI containers: no sandbox, no
+    /// shared memory (`/dev/shm` is tiny there), no GPU and no zygote process
+    Container,
+}
+
+impl LaunchProfile {
+    /// Returns the extra command line flags of Chrome
+    ///
+    /// returns: &[&str]
+    pub fn args(&self) -> &'static [&'static str] {
+        match self {
+            LaunchProfile::Default => &[],
+            LaunchProfile::Container => &[
+                "--no-sandbox",
+                "--disable-dev-shm-usage",
+                "--disable-gpu",
+                "--no-zygote",
+                "--disable-setuid-sandbox",
+            ],
+        }
+    }
+
+    /// Returns whether the sandbox is enabled, given the configured setting
+    ///
+    /// # Arguments
+    ///
+    /// * `sandbox`:  bool - The sandbox setting of the configuration
+    ///
+    /// returns: bool
+    pub fn sandbox(&self, sandbox: bool) -> bool {
+        match self {
+            LaunchProfile::Default => sandbox,
+            LaunchProfile::Container => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    pub backend: Backend,
+    /// Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+    pub sandbox: bool,
+    /// The preset of the options Chrome is launched with
+    pub launch_profile: LaunchProfile,
+    /// How long the browser gets to exit after a lookup before its process is killed
+    pub shutdown_timeout: Duration,
+    /// How long to wait for a page before giving up
+    pub timeout: Duration,
+    /// How long to wait for a page to be rendered in the browser, the timeout if not set
+    pub wait_timeout: Option<Duration>,
+    /// The interval at which the browser backend checks whether a page is rendered
+    pub poll_interval: Duration,
+    /// How the browser backend waits for search pages
+    pub search_wait: WaitStrategy,
+    /// How the browser backend waits for details pages
+    pub details_wait: WaitStrategy,
+    /// Whether the browser backend keeps the whole rendered pages, rather than only the
+    /// parts the parsers read
+    pub full_pages: bool,
+    /// How many times a failed request is retried
+    pub retries: u32,
+    /// Where games are cached on disk, if anywhere
+    pub cache_dir: Option<PathBuf>,
+    /// How long cached games are served as they are
+    pub cache_policy: CachePolicy,
+    /// Where every fetched snapshot of the games is kept on disk, if anywhere
+    pub history_dir: Option<PathBuf>,
+    /// The JSON file mapping titles to the IDs of their games, see [`TitleOverrides`]
+    pub overrides_file: Option<PathBuf>,
+    /// The minimum delay between two requests, if any
+    pub rate_limit: Option<Duration>,
+    pub user_agent: String,
+    /// Who sends the requests, appended to the user agent, e.g. "my-app (+https://example.com)"
+    pub identity: Option<String>,
+    /// The proxy requests go through, e.g. "http://127.0.0.1:8080"
+    pub proxy: Option<String>,
+    /// The URL of the site, ending with a slash
+    pub base_url: String,
+    /// The Chrome or Chromium executable to launch, found automatically if not set
+    pub chrome_path: Option<PathBuf>,
+    /// How strictly the pages are parsed
+    pub parse_mode: ParseMode,
+    /// How the titles of games are cleaned
+    pub title_cleaning: TitleCleaning,
+    /// Whether the fetched pages are recorded to or replayed from a cassette file
+    pub vcr: VcrMode,
+    /// The file every fetched page is logged to, if any
+    #[cfg(feature = "audit")]
+    pub audit_log: Option<PathBuf>,
+    /// The maximum size in bytes of a page body fetched by the HTTP backend, once
+    /// decompressed, if any
+    pub max_body_size: Option<usize>,
+    /// Whether the requests to the site are only planned, not made, see
+    /// [`HltbClient::planned_requests`]
+    pub dry_run: bool,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        ClientConfig {
+            backend: Backend::default(),
+            sandbox: true,
+            launch_profile: LaunchProfile::default(),
+            shutdown_timeout: Duration::from_secs(5),
+            timeout: Duration::from_secs(20),
+            wait_timeout: None,
+            poll_interval: Duration::from_millis(100),
+            search_wait: WaitStrategy::default(),
+            details_wait: WaitStrategy::default(),
+            full_pages: false,
+            retries: 0,
+            cache_dir: None,
+            cache_policy: CachePolicy::default(),
+            history_dir: None,
+            overrides_file: None,
+            rate_limit: None,
+            user_agent: USER_AGENT.to_string(),
+            identity: None,
+            proxy: None,
+            base_url: BASE_URL.to_string(),
+            chrome_path: None,
+            parse_mode: ParseMode::default(),
+            title_cleaning: TitleCleaning::default(),
+            vcr: VcrMode::default(),
+            #[cfg(feature = "audit")]
+            audit_log: None,
+            max_body_size: Some(MAX_BODY_SIZE),
+            dry_run: false,
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Returns how the browser backend waits for a kind of page
+    ///
+    /// # Arguments
+    ///
+    /// * `page`:  PageKind - The kind of page
+    ///
+    /// returns: &WaitStrategy
+    pub fn wait_strategy(&self, page: PageKind) -> &WaitStrategy {
+        match page {
+            PageKind::Search => &self.search_wait,
+            PageKind::Details => &self.details_wait,
+        }
+    }
+
+    /// Returns the user agent requests are sent with, followed by the identity, if any
+    ///
+    /// returns: String
+    pub fn full_user_agent(&self) -> String {
+        match &self.identity {
+            Some(identity) => format!("{} {}", self.user_agent, identity),
+            None => self.user_agent.clone(),
+        }
+    }
+
+    /// Returns the selectors of the parts of a kind of page the browser backend extracts,
+    /// none for the whole page
+    ///
+    /// # Arguments
+    ///
+    /// * `page`:  PageKind - The kind of page
+    ///
+    /// returns: &[&str]
+    #[cfg(feature = "browser")]
+    fn subtrees(&self, page: PageKind) -> &'static [&'static str] {
+        if self.full_pages {
+            &[]
+        } else {
+            page.subtrees()
+        }
+    }
+
+    /// Creates a configuration from the defaults overridden by environment variables
+    ///
+    /// The variables are `HLTB_BACKEND` ("browser" or "http"), `HLTB_SANDBOX` ("true" or
+    /// "false"), `HLTB_LAUNCH_PROFILE` ("default" or "container"), `HLTB_SHUTDOWN_TIMEOUT`,
+    /// `HLTB_TIMEOUT`, `HLTB_RATE_LIMIT` and `HLTB_CACHE_SOFT_TTL` (in seconds, see
+    /// [`CachePolicy::StaleWhileRevalidate`]), `HLTB_RETRIES`, `HLTB_CACHE_DIR`,
+    /// `HLTB_HISTORY_DIR`, `HLTB_OVERRIDES`, `HLTB_USER_AGENT`, `HLTB_IDENTITY`, `HLTB_PROXY`,
+    /// `HLTB_BASE_URL`, `HLTB_CHROME_PATH`, `HLTB_PARSE_MODE` ("lenient" or "strict"),
+    /// `HLTB_RECORD` or `HLTB_REPLAY` (the path of a cassette file), `HLTB_DRY_RUN` ("true" or
+    /// "false"), `HLTB_MAX_BODY_SIZE` (in bytes, "none" for no limit) and `HLTB_AUDIT_LOG`
+    /// (the path of an audit log, `audit` feature).
+    ///
+    /// returns: Result<ClientConfig, Box<dyn Error, Global>>
+    pub fn from_env() -> Result<ClientConfig, Box<dyn Error>> {
+        ClientConfig::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Creates a configuration from the defaults overridden by variables
+    ///
+    /// # Arguments
+    ///
+    /// * `var`:  F - Returns the value of a variable, if set
+    ///
+    /// returns: Result<ClientConfig, Box<dyn Error, Global>>
+    fn from_vars<F>(var: F) -> Result<ClientConfig, Box<dyn Error>>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let var = |name: &str| var(name).filter(|value| !value.trim().is_empty());
+        let seconds = |name: &str, value: String| {
+            value
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|s| s.is_finite() && *s >= 0.0)
+                .map(Duration::from_secs_f64)
+                .ok_or_else(|| HltbError::invalid_config(name, &value))
+        };
+
+        let mut config = ClientConfig::default();
+        if let Some(value) = var("HLTB_BACKEND") {
+            config.backend = match value.trim().to_lowercase().as_str() {
+                #[cfg(feature = "browser")]
+                "browser" => Backend::Browser,
+                #[cfg(feature = "http")]
+                "http" => Backend::Http,
+                _ => return Err(HltbError::invalid_config("HLTB_BACKEND", &value).into()),
+            };
+        }
+        if let Some(value) = var("HLTB_SANDBOX") {
+            config.sandbox = match value.trim().to_lowercase().as_str() {
+                "true" | "1" | "yes" => true,
+                "false" | "0" | "no" => false,
+                _ => return Err(HltbError::invalid_config("HLTB_SANDBOX", &value).into()),
+            };
+        }
+        if let Some(value) = var("HLTB_LAUNCH_PROFILE") {
+            config.launch_profile = match value.trim().to_lowercase().as_str() {
+                "default" => LaunchProfile::Default,
+                "container" => LaunchProfile::Container,
+                _ => return Err(HltbError::invalid_config("HLTB_LAUNCH_PROFILE", &value).into()),
+            };
+        }
+        if let Some(value) = var("HLTB_SHUTDOWN_TIMEOUT") {
+            config.shutdown_timeout = seconds("HLTB_SHUTDOWN_TIMEOUT", value)?;
+        }
+        if let Some(value) = var("HLTB_TIMEOUT") {
+            config.timeout = seconds("HLTB_TIMEOUT", value)?;
+        }
+        if let Some(value) = var("HLTB_RATE_LIMIT") {
+            config.rate_limit = Some(seconds("HLTB_RATE_LIMIT", value)?);
+        }
+        if let Some(value) = var("HLTB_RETRIES") {
+            config.retries = value
+                .trim()
+                .parse()
+                .map_err(|_| HltbError::invalid_config("HLTB_RETRIES", &value))?;
+        }
+        if let Some(value) = var("HLTB_CACHE_DIR") {
+            config.cache_dir = Some(PathBuf::from(value));
+        }
+        if let Some(value) = var("HLTB_CACHE_SOFT_TTL") {
+            config.cache_policy = CachePolicy::StaleWhileRevalidate {
+                soft_ttl: seconds("HLTB_CACHE_SOFT_TTL", value)?,
+            };
+        }
+        if let Some(value) = var("HLTB_HISTORY_DIR") {
+            config.history_dir = Some(PathBuf::from(value));
+        }
+        if let Some(value) = var("HLTB_OVERRIDES") {
+            config.overrides_file = Some(PathBuf::from(value));
+        }
+        if let Some(value) = var("HLTB_USER_AGENT") {
+            config.user_agent = value;
+        }
+        if let Some(value) = var("HLTB_IDENTITY") {
+            config.identity = Some(value);
+        }
+        if let Some(value) = var("HLTB_PROXY") {
+            config.proxy = Some(value);
+        }
+        if let Some(value) = var("HLTB_BASE_URL") {
+            config.base_url = value;
+        }
+        if let Some(value) = var("HLTB_CHROME_PATH") {
+            config.chrome_path = Some(PathBuf::from(value));
+        }
+        if let Some(value) = var("HLTB_RECORD") {
+            config.vcr = VcrMode::Record(PathBuf::from(value));
+        }
+        if let Some(value) = var("HLTB_REPLAY") {
+            config.vcr = VcrMode::Replay(PathBuf::from(value));
+        }
+        #[cfg(feature = "audit")]
+        if let Some(value) = var("HLTB_AUDIT_LOG") {
+            config.audit_log = Some(PathBuf::from(value));
+        }
+        if let Some(value) = var("HLTB_DRY_RUN") {
+            config.dry_run = match value.trim().to_lowercase().as_str() {
+                "true" | "1" | "yes" => true,
+                "false" | "0" | "no" => false,
+                _ => return Err(HltbError::invalid_config("HLTB_DRY_RUN", &value).into()),
+            };
+        }
+        if let Some(value) = var("HLTB_MAX_BODY_SIZE") {
+            config.max_body_size = match value.trim().to_lowercase().as_str() {
+                "none" => None,
+                size => Some(
+                    size.parse()
+                        .map_err(|_| HltbError::invalid_config("HLTB_MAX_BODY_SIZE", &value))?,
+                ),
+            };
+        }
+        if let Some(value) = var("HLTB_PARSE_MODE") {
+            config.parse_mode = match value.trim().to_lowercase().as_str() {
+                "lenient" => ParseMode::Lenient,
+                "strict" => ParseMode::Strict,
+                _ => return Err(HltbError::invalid_config("HLTB_PARSE_MODE", &value).into()),
+            };
+        }
+        Ok(config)
+    }
+}
+
+pub struct HltbClientBuilder {
+    config: ClientConfig,
+    #[cfg(feature = "test-util")]
+    mock: Option<MockBackend>,
+    #[cfg(feature = "http")]
+    http: Option<reqwest::Client>,
+    observers: Vec<Arc<dyn Observer>>,
+    /// The titles mapped to their games in code, over the entries of the overrides file
+    overrides: TitleOverrides,
+    /// The error of the environment variables, reported when building
+    env_error: Option<String>,
+    /// How long a browser kept open between lookups stays open without lookups
+    #[cfg(feature = "browser")]
+    keep_browser: Option<Duration>,
+}
+
+impl HltbClientBuilder {
+    /// Sets how pages are fetched
+    pub fn backend(mut self, backend: Backend) -> HltbClientBuilder {
+        self.config.backend = backend;
+        self
+    }
+
+    /// Sets whether to enable sandbox mode for the browser
+    pub fn sandbox(mut self, sandbox: bool) -> HltbClientBuilder {
+        self.config.sandbox = sandbox;
+        self
+    }
+
+    /// Sets the preset of the options Chrome is launched with, e.g.
+    /// [`LaunchProfile::Container`] to run in Docker or CI
+    pub fn launch_profile(mut self, launch_profile: LaunchProfile) -> HltbClientBuilder {
+        self.config.launch_profile = launch_profile;
+        self
+    }
+
+    /// Sets how long the browser gets to exit after a lookup before its process is killed
+    pub fn shutdown_timeout(mut self, timeout: Duration) -> HltbClientBuilder {
+        self.config.shutdown_timeout = timeout;
+        self
+    }
+
+    /// Sets how long to wait for a page before giving up
+    pub fn timeout(mut self, timeout: Duration) -> HltbClientBuilder {
+        self.config.timeout = timeout;
+        self
+    }
+
+    /// Sets how long to wait for a page to be rendered in the browser, instead of the timeout
+    pub fn wait_timeout(mut self, timeout: Duration) -> HltbClientBuilder {
+        self.config.wait_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the interval at which the browser backend checks whether a page is rendered
+    pub fn poll_interval(mut self, interval: Duration) -> HltbClientBuilder {
+        self.config.poll_interval = interval;
+        self
+    }
+
+    /// Sets how the browser backend waits for a kind of page
+    pub fn wait_strategy(mut self, page: PageKind, strategy: WaitStrategy) -> HltbClientBuilder {
+        match page {
+            PageKind::Search => self.config.search_wait = strategy,
+            PageKind::Details => self.config.details_wait = strategy,
+        }
+        self
+    }
+
+    /// Sets whether the browser backend keeps the whole rendered pages, e.g. to read fields
+    /// the crate doesn't parse from [`GamePage::html`]
+    ///
+    /// By default only the parts of the pages the parsers read are extracted, which is
+    /// faster on large pages.
+    pub fn full_pages(mut self, full_pages: bool) -> HltbClientBuilder {
+        self.config.full_pages = full_pages;
+        self
+    }
+
+    /// Sets how many times a failed request is retried
+    pub fn retries(mut self, retries: u32) -> HltbClientBuilder {
+        self.config.retries = retries;
+        self
+    }
+
+    /// Caches the fetched games in a directory
+    pub fn cache_dir(mut self, dir: impl Into<PathBuf>) -> HltbClientBuilder {
+        self.config.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Sets how long cached games are served as they are, see [`HltbClient::run_revalidation`]
+    pub fn cache_policy(mut self, policy: CachePolicy) -> HltbClientBuilder {
+        self.config.cache_policy = policy;
+        self
+    }
+
+    /// Keeps every fetched snapshot of the games in a directory, see [`HistoryStore`]
+    pub fn history_dir(mut self, dir: impl Into<PathBuf>) -> HltbClientBuilder {
+        self.config.history_dir = Some(dir.into());
+        self
+    }
+
+    /// Loads the titles mapped to their games from a JSON file when the client is built,
+    /// see [`TitleOverrides`]
+    pub fn overrides_file(mut self, path: impl Into<PathBuf>) -> HltbClientBuilder {
+        self.config.overrides_file = Some(path.into());
+        self
+    }
+
+    /// Maps titles to their games, skipping their searches, over the entries of the
+    /// overrides file
+    pub fn overrides(mut self, overrides: TitleOverrides) -> HltbClientBuilder {
+        self.overrides.merge(overrides);
+        self
+    }
+
+    /// Sets the minimum delay between two requests
+    pub fn rate_limit(mut self, interval: Duration) -> HltbClientBuilder {
+        self.config.rate_limit = Some(interval);
+        self
+    }
+
+    /// Sets the user agent requests are sent with
+    pub fn user_agent(mut self, user_agent: &str) -> HltbClientBuilder {
+        self.config.user_agent = user_agent.to_string();
+        self
+    }
+
+    /// Identifies the application sending the requests, with a user agent suffix such as
+    /// "my-app (+https://example.com/contact)", so the site knows whom to reach
+    ///
+    /// # Arguments
+    ///
+    /// * `app`:  &str - The name of the application, e.g. "my-app/1.0"
+    /// * `contact`:  &str - Where to reach its maintainers, e.g. a URL or an email address
+    ///
+    /// returns: HltbClientBuilder
+    pub fn identity(mut self, app: &str, contact: &str) -> HltbClientBuilder {
+        self.config.identity = Some(format!("{} (+{})", app.trim(), contact.trim()));
+        self
+    }
+
+    /// Sets the maximum size in bytes of a page body fetched by the HTTP backend, once
+    /// decompressed, None for no limit
+    pub fn max_body_size(mut self, max_body_size: Option<usize>) -> HltbClientBuilder {
+        self.config.max_body_size = max_body_size;
+        self
+    }
+
+    /// Plans the requests to the site instead of making them: lookups are served from the
+    /// cache or fail, see [`HltbClient::planned_requests`]
+    pub fn dry_run(mut self, dry_run: bool) -> HltbClientBuilder {
+        self.config.dry_run = dry_run;
+        self
+    }
+
+    /// Sends the requests through a proxy, e.g. "http://127.0.0.1:8080"
+    pub fn proxy(mut self, proxy: &str) -> HltbClientBuilder {
+        self.config.proxy = Some(proxy.to_string());
+        self
+    }
+
+    /// Sets the URL of the site, e.g. to point at a mirror or a local test server
+    pub fn base_url(mut self, base_url: &str) -> HltbClientBuilder {
+        self.config.base_url = base_url.to_string();
+        self
+    }
+
+    /// Sets the Chrome or Chromium executable to launch
+    pub fn chrome_path(mut self, path: impl Into<PathBuf>) -> HltbClientBuilder {
+        self.config.chrome_path = Some(path.into());
+        self
+    }
+
+    /// Sets how strictly the pages are parsed
+    pub fn parse_mode(mut self, parse_mode: ParseMode) -> HltbClientBuilder {
+        self.config.parse_mode = parse_mode;
+        self
+    }
+
+    /// Sets how the titles of games are cleaned
+    pub fn title_cleaning(mut self, title_cleaning: TitleCleaning) -> HltbClientBuilder {
+        self.config.title_cleaning = title_cleaning;
+        self
+    }
+
+    /// Saves every fetched page to a cassette file, to replay them later
+    pub fn record(mut self, cassette: impl Into<PathBuf>) -> HltbClientBuilder {
+        self.config.vcr = VcrMode::Record(cassette.into());
+        self
+    }
+
+    /// Serves the pages recorded in a cassette file instead of fetching them
+    pub fn replay(mut self, cassette: impl Into<PathBuf>) -> HltbClientBuilder {
+        self.config.vcr = VcrMode::Replay(cassette.into());
+        self
+    }
+
+    /// Sends the requests of the HTTP backend through an existing client, so several
+    /// clients share its connection pool
+    ///
+    /// The timeout of the requests still comes from this builder, but the user agent and
+    /// the proxy are those of the given client.
+    #[cfg(feature = "http")]
+    pub fn http_client(mut self, http: reqwest::Client) -> HltbClientBuilder {
+        self.http = Some(http);
+        self
+    }
+
+    /// Appends the URL, time, content hash and parse outcome of every fetched page to a log
+    /// file, to trace where the data comes from
+    #[cfg(feature = "audit")]
+    pub fn audit_log(mut self, path: impl Into<PathBuf>) -> HltbClientBuilder {
+        self.config.audit_log = Some(path.into());
+        self
+    }
+
+    /// Keeps the browser open between the lookups of the client instead of launching one for
+    /// every lookup, closing it once no lookup used it for a duration
+    #[cfg(feature = "browser")]
+    pub fn keep_browser(mut self, idle: Duration) -> HltbClientBuilder {
+        self.keep_browser = Some(idle);
+        self
+    }
+
+    /// Adds an observer of the requests of the client, e.g. to collect metrics
+    pub fn observer(mut self, observer: impl Observer + 'static) -> HltbClientBuilder {
+        self.observers.push(Arc::new(observer));
+        self
+    }
+
+    /// Serves the pages from a mock backend instead of the site, for tests
+    #[cfg(feature = "test-util")]
+    pub fn mock(mut self, mock: MockBackend) -> HltbClientBuilder {
+        self.config.backend = Backend::Mock;
+        self.mock = Some(mock);
+        self
+    }
+
+    /// Builds the client
+    ///
+    /// returns: Result<HltbClient, Box<dyn Error, Global>>
+    pub fn build(self) -> Result<HltbClient, Box<dyn Error>> {
+        if let Some(error) = self.env_error {
+            return Err(error.into());
+        }
+        let mut config = self.config;
+        if !config.base_url.ends_with('/') {
+            config.base_url.push('/');
+        }
+        #[cfg(feature = "http")]
+        let http = match config.backend {
+            Backend::Http => match self.http {
+                Some(http) => Some(http),
+                None => {
+                    // One client for every request, so its connections are reused
+                    // instead of negotiating TLS for each page
+                    let mut builder = reqwest::Client::builder()
+                        .user_agent(config.full_user_agent())
+                        .timeout(config.timeout)
+                        .pool_idle_timeout(HTTP_IDLE_TIMEOUT)
+                        .tcp_keepalive(HTTP_IDLE_TIMEOUT)
+                        .http2_keep_alive_interval(HTTP2_KEEP_ALIVE)
+                        .http2_keep_alive_while_idle(true)
+                        .http2_adaptive_window(true)
+                        .gzip(true)
+                        .brotli(true);
+                    if let Some(proxy) = &config.proxy {
+                        builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+                    }
+                    Some(builder.build()?)
+                }
+            },
+            #[cfg(feature = "browser")]
+            Backend::Browser => None,
+            #[cfg(feature = "test-util")]
+            Backend::Mock => None,
+        };
+        let mut overrides = match &config.overrides_file {
+            Some(path) => TitleOverrides::load(path)?,
+            None => TitleOverrides::new(),
+        };
+        overrides.merge(self.overrides);
+        Ok(HltbClient {
+            cache: config.cache_dir.as_ref().map(DiskCache::new),
+            history: config.history_dir.as_ref().map(HistoryStore::new),
+            overrides,
+            cassette: Cassette::open(&config.vcr)?,
+            #[cfg(feature = "audit")]
+            audit: match &config.audit_log {
+                Some(path) => Some(AuditLog::open(path)?),
+                None => None,
+            },
+            config,
+            #[cfg(feature = "http")]
+            http,
+            #[cfg(feature = "test-util")]
+            mock: self.mock,
+            observers: self.observers,
+            #[cfg(feature = "browser")]
+            kept: self.keep_browser.map(|idle| {
+                Arc::new(KeptBrowser {
+                    tab: TabSlot::default(),
+                    idle,
+                    uses: AtomicU64::new(0),
+                })
+            }),
+            next_request: Arc::new(Mutex::new(None)),
+            stale: Mutex::new(Vec::new()),
+            stale_queued: Notify::new(),
+            planned: Mutex::new(Vec::new()),
+        })
+    }
+}
+
+pub struct HltbClient {
+    config: ClientConfig,
+    #[cfg(feature = "http")]
+    http: Option<reqwest::Client>,
+    #[cfg(feature = "test-util")]
+    mock: Option<MockBackend>,
+    cache: Option<DiskCache>,
+    history: Option<HistoryStore>,
+    overrides: TitleOverrides,
+    cassette: Option<Cassette>,
+    #[cfg(feature = "audit")]
+    audit: Option<AuditLog>,
+    observers: Vec<Arc<dyn Observer>>,
+    /// The browser kept open between lookups, if the client keeps one
+    #[cfg(feature = "browser")]
+    kept: Option<Arc<KeptBrowser>>,
+    /// The earliest time the next request may start, when rate limited, shared with the
+    /// sessions of the client
+    next_request: Arc<Mutex<Option<Instant>>>,
+    /// The IDs of the stale games served from the cache, waiting to be refreshed
+    stale: Mutex<Vec<u32>>,
+    /// Notified when a stale game is queued
+    stale_queued: Notify,
+    /// The requests a dry run would have made
+    planned: Mutex<Vec<PlannedRequest>>,
+}
+
+impl HltbClient {
+    /// Creates a builder starting from the default configuration and the environment variables
+    ///
+    /// returns: HltbClientBuilder
+    pub fn builder() -> HltbClientBuilder {
+        let (config, env_error) = match ClientConfig::from_env() {
+            Ok(config) => (config, None),
+            Err(e) => (ClientConfig::default(), Some(e.to_string())),
+        };
+        HltbClientBuilder {
+            config,
+            #[cfg(feature = "test-util")]
+            mock: None,
+            #[cfg(feature = "http")]
+            http: None,
+            observers: Vec::new(),
+            overrides: TitleOverrides::new(),
+            env_error,
+            #[cfg(feature = "browser")]
+            keep_browser: None,
+        }
+    }
+
+    /// Returns the configuration of the client
+    ///
+    /// returns: &ClientConfig
+    pub fn config(&self) -> &ClientConfig {
+        &self.config
+    }
+
+    /// Closes the browser the client keeps open between lookups, if any, e.g. before the
+    /// process exits
+    ///
+    /// A lookup running meanwhile keeps its tab, which is closed once idle.
+    #[cfg(feature = "browser")]
+    pub async fn close_browser(&self) {
+        let tab = self
+            .kept
+            .as_ref()
+            .and_then(|kept| kept.tab.0.lock().unwrap().take());
+        if let Some(tab) = tab {
+            let _ = tokio::task::spawn_blocking(move || drop(tab)).await;
+        }
+    }
+
+    /// Returns the history of the fetched games, if a history directory is configured
+    ///
+    /// returns: Option<&HistoryStore>
+    pub fn history(&self) -> Option<&HistoryStore> {
+        self.history.as_ref()
+    }
+
+    /// Fills the cache of the client with the games of a JSON Lines export, see
+    /// [`DiskCache::warm_up`]
+    ///
+    /// # Arguments
+    ///
+    /// * `path`:  impl AsRef<Path> - The exported file
+    ///
+    /// returns: Result<usize, Box<dyn Error, Global>> - The number of games imported, an error
+    /// if no cache directory is configured
+    pub fn warm_cache(&self, path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+        match &self.cache {
+            Some(cache) => cache.warm_up(path.as_ref()),
+            None => Err(HltbError::Unsupported("No cache directory is configured").into()),
+        }
+    }
+
+    /// Returns the titles mapped to their games, whose searches are skipped
+    ///
+    /// returns: &TitleOverrides
+    pub fn overrides(&self) -> &TitleOverrides {
+        &self.overrides
+    }
+
+    /// Returns the client the HTTP backend sends its requests with, None with another backend
+    ///
+    /// Cloning it shares its connection pool, e.g. to build other clients with
+    /// [`HltbClientBuilder::http_client`].
+    ///
+    /// returns: Option<&Client>
+    #[cfg(feature = "http")]
+    pub fn http_client(&self) -> Option<&reqwest::Client> {
+        self.http.as_ref()
+    }
+
+    /// Starts a search for a game, whose options can be changed for this call only
+    ///
+    /// The search runs when the returned request is awaited, e.g.
+    /// `client.search("Metal Gear").timeout(Duration::from_secs(5)).platform("NES").await`.
+    ///
+    /// # Arguments
+    ///
+    /// * `query`:  impl Into<SearchQuery> - The query, or just the name of the game
+    ///
+    /// returns: SearchRequest
+    pub fn search(&self, query: impl Into<SearchQuery>) -> SearchRequest<'_> {
+        SearchRequest::new(self, Query::Search(query.into()))
+    }
+
+    /// Lists the results of a search, sorted and windowed as the query asks
+    ///
+    /// # Arguments
+    ///
+    /// * `query`:  impl Into<SearchQuery> - The query, or just the name of the game
+    ///
+    /// returns: Result<Vec<SearchResult>, Box<dyn Error, Global>>
+    pub async fn search_results(
+        &self,
+        query: impl Into<SearchQuery>,
+    ) -> Result<Vec<SearchResult>, Box<dyn Error>> {
+        let query = query.into();
+        let cancel = CancellationToken::new();
+        let call = Call::new(&self.config, &cancel);
+        let content = self.search_html_with(&query.name, call).await?;
+        let search_url = self.search_url(&query.name);
+        self.audit(&search_url, Some(&content), AuditOutcome::Parsed)?;
+        let mut results =
+            parse::search_results_with_cleaning(&content, &self.config.title_cleaning);
+        for result in &mut results {
+            result.search_url = Some(search_url.clone());
+        }
+        Ok(query.arrange(results))
+    }
+
+    /// Returns the total number of results of a search, as the header of the search page
+    /// states it, without parsing the results themselves
+    ///
+    /// A search without results counts none, as soon as its page is rendered.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`:  &str - The name of the game to search for
+    ///
+    /// returns: Result<u32, Box<dyn Error, Global>>
+    pub async fn count_results(&self, name: &str) -> Result<u32, Box<dyn Error>> {
+        let cancel = CancellationToken::new();
+        let call = Call::new(&self.config, &cancel);
+        let content = self.search_html_with(name, call).await?;
+        self.parsed(
+            &self.search_url(name),
+            &content,
+            parse::result_count(&content),
+        )
+    }
+
+    /// Returns the client the HTTP backend sends its requests with, or a new client using the
+    /// same user agent and proxy with another backend
+    ///
+    /// returns: Result<Client, Box<dyn Error, Global>>
+    #[cfg(feature = "http")]
+    fn http_or_new_client(&self) -> Result<reqwest::Client, Box<dyn Error>> {
+        if let Some(http) = &self.http {
+            return Ok(http.clone());
+        }
+        let mut builder = reqwest::Client::builder()
+            .user_agent(self.config.full_user_agent())
+            .timeout(self.config.timeout);
+        if let Some(proxy) = &self.config.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Fetches the cover image of a game, to embed it in a report
+    ///
+    /// The image is downloaded with the client of the HTTP backend, see
+    /// [`HltbClient::http_or_new_client`].
+    ///
+    /// # Arguments
+    ///
+    /// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+    ///
+    /// returns: Result<Option<Cover>, Box<dyn Error, Global>> - None if the game has no cover
+    #[cfg(feature = "http")]
+    pub async fn fetch_cover(&self, hltb_id: u32) -> Result<Option<Cover>, Box<dyn Error>> {
+        let page = self.game(hltb_id).send_with_html().await?;
+        let Some(url) = page.cover_url() else {
+            return Ok(None);
+        };
+        let (headers, bytes) = self.download(&url, self.config.timeout).await?;
+        // Without its parameters, and only if it is an image type
+        let mime = headers
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_ascii_lowercase())
+            .filter(|value| report::is_image_type(value))
+            .unwrap_or_else(|| "image/jpeg".to_string());
+        Ok(Some(Cover { mime, bytes }))
+    }
+
+    /// Starts a lookup of a game by its How Long to Beat ID, whose options can be changed for
+    /// this call only
+    ///
+    /// # Arguments
+    ///
+    /// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+    ///
+    /// returns: SearchRequest
+    pub fn game(&self, hltb_id: u32) -> SearchRequest<'_> {
+        SearchRequest::new(self, Query::Id(hltb_id))
+    }
+
+    /// Searches for a game by name
+    ///
+    /// # Arguments
+    ///
+    /// * `name`:  &str - The name of the game to search for
+    ///
+    /// returns: Result<Game, Box<dyn Error, Global>>
+    pub async fn search_by_name(&self, name: &str) -> Result<Game, Box<dyn Error>> {
+        self.search_by_name_with_cancel(name, &CancellationToken::new())
+            .await
+    }
+
+    /// Searches for a game by name, until the token is cancelled
+    ///
+    /// # Arguments
+    ///
+    /// * `name`:  &str - The name of the game to search for
+    /// * `cancel`:  &CancellationToken - Cancels the search, e.g. when the user types a new query
+    ///
+    /// returns: Result<Game, Box<dyn Error, Global>>
+    pub async fn search_by_name_with_cancel(
+        &self,
+        name: &str,
+        cancel: &CancellationToken,
+    ) -> Result<Game, Box<dyn Error>> {
+        self.search_by_name_with(name, Call::new(&self.config, cancel))
+            .await
+    }
+
+    async fn search_by_name_with(
+        &self,
+        name: &str,
+        call: Call<'_>,
+    ) -> Result<Game, Box<dyn Error>> {
+        self.search_by_query_with(&SearchQuery::new(name), call)
+            .await
+    }
+
+    async fn search_by_query_with(
+        &self,
+        query: &SearchQuery,
+        call: Call<'_>,
+    ) -> Result<Game, Box<dyn Error>> {
+        let tab = TabSlot::default();
+        let call = call.sharing_tab(&tab);
+        let found = self.find_with(query, call).await?;
+        let mut game = self.search_by_id_with(found.hltb_id, call).await?;
+        game.search_url = found.search_url;
+        Ok(game)
+    }
+
+    /// Searches for a game by its How Long to Beat ID, from the cache when possible
+    ///
+    /// # Arguments
+    ///
+    /// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+    ///
+    /// returns: Result<Game, Box<dyn Error, Global>>
+    pub async fn search_by_id(&self, hltb_id: u32) -> Result<Game, Box<dyn Error>> {
+        self.search_by_id_with_cancel(hltb_id, &CancellationToken::new())
+            .await
+    }
+
+    /// Searches for a game by its How Long to Beat ID, until the token is cancelled
+    ///
+    /// # Arguments
+    ///
+    /// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+    /// * `cancel`:  &CancellationToken - Cancels the search
+    ///
+    /// returns: Result<Game, Box<dyn Error, Global>>
+    pub async fn search_by_id_with_cancel(
+        &self,
+        hltb_id: u32,
+        cancel: &CancellationToken,
+    ) -> Result<Game, Box<dyn Error>> {
+        self.search_by_id_with(hltb_id, Call::new(&self.config, cancel))
+            .await
+    }
+
+    async fn search_by_id_with(
+        &self,
+        hltb_id: u32,
+        call: Call<'_>,
+    ) -> Result<Game, Box<dyn Error>> {
+        if let Some(game) = self.cache.as_ref().and_then(|c| c.get(hltb_id)) {
+            self.notify(|observer| observer.on_cache_hit(hltb_id));
+            if self.config.cache_policy.is_stale(&game, SystemTime::now()) {
+                self.queue_stale(hltb_id);
+            }
+            return Ok(game);
+        }
+        let game = self.fetch_details_with(hltb_id, call).await?;
+        self.store(&game)?;
+        Ok(game)
+    }
+
+    /// Stores a fetched game in the cache and the history, if configured
+    ///
+    /// # Arguments
+    ///
+    /// * `game`:  &Game - The fetched game
+    ///
+    /// returns: Result<(), Box<dyn Error, Global>>
+    fn store(&self, game: &Game) -> Result<(), Box<dyn Error>> {
+        if let Some(cache) = &self.cache {
+            cache.put(game)?;
+        }
+        if let Some(history) = &self.history {
+            history.record(game)?;
+        }
+        Ok(())
+    }
+
+    /// Fetches the rendered search page of a name, without parsing it
+    ///
+    /// Parse it with [`parse::search_result`].
+    ///
+    /// # Arguments
+    ///
+    /// * `name`:  &str - The name of the game to search for
+    ///
+    /// returns: Result<String, Box<dyn Error, Global>>
+    pub async fn search_html(&self, name: &str) -> Result<String, Box<dyn Error>> {
+        let cancel = CancellationToken::new();
+        let content = self
+            .search_html_with(name, Call::new(&self.config, &cancel))
+            .await?;
+        self.audit(
+            &self.search_url(name),
+            Some(&content),
+            AuditOutcome::Unparsed,
+        )?;
+        Ok(content)
+    }
+
+    async fn search_html_with(&self, name: &str, call: Call<'_>) -> Result<String, Box<dyn Error>> {
+        #[cfg(feature = "http")]
+        if self.config.backend == Backend::Http {
+            return Err(
+                HltbError::Unsupported("Searching by name requires the browser backend").into(),
+            );
+        }
+        self.fetch(&self.search_url(name), PageKind::Search, call)
+            .await
+    }
+
+    /// Returns the URL of the search page of a name on the configured base URL
+    ///
+    /// # Arguments
+    ///
+    /// * `name`:  &str - The name of the game to search for
+    ///
+    /// returns: String
+    pub fn search_url(&self, name: &str) -> String {
+        format!("{}?q={}", self.config.base_url, encode(name.trim()))
+    }
+
+    /// Fetches the details page of a game, without parsing it and bypassing the cache
+    ///
+    /// Parse it with [`parse::game`].
+    ///
+    /// # Arguments
+    ///
+    /// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+    ///
+    /// returns: Result<String, Box<dyn Error, Global>>
+    pub async fn detail_html(&self, hltb_id: u32) -> Result<String, Box<dyn Error>> {
+        let cancel = CancellationToken::new();
+        let content = self
+            .detail_html_with(hltb_id, Call::new(&self.config, &cancel))
+            .await?;
+        let url = format!("{}game/{}", self.config.base_url, hltb_id);
+        self.audit(&url, Some(&content), AuditOutcome::Unparsed)?;
+        Ok(content)
+    }
+
+    async fn detail_html_with(
+        &self,
+        hltb_id: u32,
+        call: Call<'_>,
+    ) -> Result<String, Box<dyn Error>> {
+        let url = format!("{}game/{}", self.config.base_url, hltb_id);
+        self.fetch(&url, PageKind::Details, call).await
+    }
+
+    /// Finds the ID of the first search result for a name
+    ///
+    /// # Arguments
+    ///
+    /// * `name`:  &str - The name of the game to search for
+    ///
+    /// returns: Result<u32, Box<dyn Error, Global>>
+    #[cfg(test)]
+    pub(crate) async fn find_id(&self, name: &str) -> Result<u32, Box<dyn Error>> {
+        let cancel = CancellationToken::new();
+        let call = Call::new(&self.config, &cancel);
+        Ok(self.find_with(&SearchQuery::new(name), call).await?.hltb_id)
+    }
+
+    /// Finds the ID of the game a query resolves to, along with every result of its search
+    ///
+    /// A name mapped to a game by the overrides resolves to it without searching, and so does
+    /// a plain search cached under the same normalized name. When the name has no results,
+    /// the search is retried with the spelling the site suggests, then with
+    /// [`SearchQuery::spelling_variants`], until one has.
+    ///
+    /// # Arguments
+    ///
+    /// * `query`:  &SearchQuery - The query
+    /// * `call`:  Call - The configuration, cancellation and retry hook of the call
+    ///
+    /// returns: Result<Found, Box<dyn Error, Global>>
+    async fn find_with(
+        &self,
+        query: &SearchQuery,
+        call: Call<'_>,
+    ) -> Result<Found, Box<dyn Error>> {
+        if let Some(hltb_id) = self.overrides.get(&query.name) {
+            return Ok(Found {
+                hltb_id,
+                results: Vec::new(),
+                variant: None,
+                overridden: true,
+                search_url: None,
+            });
+        }
+        // Options change the pick, so only plain searches share their name's entry
+        let cache = self.cache.as_ref().filter(|_| query.is_plain());
+        // A stale search is made again, as the site may resolve the name differently by now
+        let cached = cache
+            .and_then(|cache| cache.get_query(&query.name))
+            .filter(|cached| {
+                !self
+                    .config
+                    .cache_policy
+                    .is_query_stale(cached, SystemTime::now())
+            });
+        if let Some(cached) = cached {
+            self.notify(|observer| observer.on_cache_hit(cached.hltb_id));
+            let name = cached.variant.as_deref().unwrap_or(&query.name);
+            return Ok(Found {
+                hltb_id: cached.hltb_id,
+                search_url: Some(self.search_url(name)),
+                results: cached.results,
+                variant: cached.variant,
+                overridden: false,
+            });
+        }
+        let content = self.search_html_with(&query.name, call).await?;
+        let error = match self.pick(query, &content, call) {
+            Ok((hltb_id, results)) => {
+                if let Some(cache) = cache {
+                    cache.put_query(
+                        &query.name,
+                        &CachedQuery::new(hltb_id, results.clone(), None),
+                    )?;
+                }
+                return Ok(Found {
+                    hltb_id,
+                    results,
+                    variant: None,
+                    overridden: false,
+                    search_url: Some(self.search_url(&query.name)),
+                });
+            }
+            Err(e) => e,
+        };
+        if !is_no_results(error.as_ref()) {
+            return Err(error);
+        }
+        let mut variants: Vec<String> = parse::suggestion(&content).into_iter().collect();
+        for variant in query.spelling_variants() {
+            if !variants.contains(&variant) {
+                variants.push(variant);
+            }
+        }
+        for variant in variants {
+            let retry = SearchQuery {
+                name: variant.clone(),
+                ..query.clone()
+            };
+            let content = self.search_html_with(&retry.name, call).await?;
+            if let Ok((hltb_id, results)) = self.pick(&retry, &content, call) {
+                if let Some(cache) = cache {
+                    let cached = CachedQuery::new(hltb_id, results.clone(), Some(variant.clone()));
+                    cache.put_query(&query.name, &cached)?;
+                }
+                return Ok(Found {
+                    hltb_id,
+                    results,
+                    search_url: Some(self.search_url(&variant)),
+                    variant: Some(variant),
+                    overridden: false,
+                });
+            }
+        }
+        Err(error)
+    }
+
+    /// Picks the game a query resolves to on its search page
+    ///
+    /// # Arguments
+    ///
+    /// * `query`:  &SearchQuery - The query
+    /// * `content`:  &str - The HTML of the search page of the query
+    /// * `call`:  Call - The configuration, cancellation and retry hook of the call
+    ///
+    /// returns: Result<(u32, Vec<SearchResult>), Box<dyn Error, Global>>
+    fn pick(
+        &self,
+        query: &SearchQuery,
+        content: &str,
+        call: Call<'_>,
+    ) -> Result<(u32, Vec<SearchResult>), Box<dyn Error>> {
+        let started = Instant::now();
+        let results = parse::search_results_with_cleaning(content, &call.config.title_cleaning);
+        call.time(|timings| timings.parse += started.elapsed());
+        if query.is_plain() {
+            // The plain first result, as before queries had options
+            let url = self.search_url(&query.name);
+            let hltb_id = self.parsed(&url, content, parse::search_result(content))?;
+            return Ok((hltb_id, results));
+        }
+        self.audit(
+            &self.search_url(&query.name),
+            Some(content),
+            AuditOutcome::Parsed,
+        )?;
+        let hltb_id = query
+            .select(results.clone())
+            .map(|result| result.hltb_id)
+            .ok_or_else(|| HltbError::NoMatch(query.name.clone()))?;
+        Ok((hltb_id, results))
+    }
+
+    /// Fetches and parses the details page of a game, bypassing the cache
+    async fn fetch_details_with(
+        &self,
+        hltb_id: u32,
+        call: Call<'_>,
+    ) -> Result<Game, Box<dyn Error>> {
+        Ok(self.fetch_page_with(hltb_id, call).await?.game)
+    }
+
+    async fn fetch_page_with(
+        &self,
+        hltb_id: u32,
+        call: Call<'_>,
+    ) -> Result<GamePage, Box<dyn Error>> {
+        let html = self.detail_html_with(hltb_id, call).await?;
+        let config = call.config;
+        let url = format!("{}game/{}", self.config.base_url, hltb_id);
+        let started = Instant::now();
+        let game =
+            parse::game_with_cleaning(&html, hltb_id, config.parse_mode, &config.title_cleaning);
+        call.time(|timings| timings.parse += started.elapsed());
+        let mut game = self.parsed(&url, &html, game)?;
+        game.fetched_at = Some(SystemTime::now());
+        if let Some((from, to)) = game.redirect() {
+            self.notify(|observer| observer.on_redirect(from, to));
+        }
+        Ok(GamePage { game, html })
+    }
+
+    /// Fetches a page, or replays it from the cassette, recording it when the client records
+    ///
+    /// # Arguments
+    ///
+    /// * `url`:  &str - The URL of the page
+    /// * `page`:  PageKind - The kind of the page, which sets how it is waited for
+    /// * `call`:  Call - The configuration, cancellation and retry hook of the call
+    ///
+    /// returns: Result<String, Box<dyn Error, Global>>
+    async fn fetch(
+        &self,
+        url: &str,
+        page: PageKind,
+        call: Call<'_>,
+    ) -> Result<String, Box<dyn Error>> {
+        let key = url.strip_prefix(&self.config.base_url).unwrap_or(url);
+        if let Some(cassette) = self.cassette.as_ref().filter(|c| c.is_replaying()) {
+            return cassette.replay(key);
+        }
+        if call.config.dry_run {
+            self.plan(url, page);
+            return Err(HltbError::DryRun(url.to_string()).into());
+        }
+        let content = match self.fetch_live(url, page, call).await {
+            Ok(content) => content,
+            Err(e) => {
+                self.audit(url, None, AuditOutcome::RequestError(e.to_string()))?;
+                return Err(e);
+            }
+        };
+        if let Some(cassette) = &self.cassette {
+            cassette.record(key, &content)?;
+        }
+        Ok(content)
+    }
+
+    /// Fetches a page with the configured backend, honouring the rate limit and retries
+    ///
+    /// # Arguments
+    ///
+    /// * `url`:  &str - The URL of the page
+    /// * `page`:  PageKind - The kind of the page, which sets how it is waited for
+    /// * `call`:  Call - The configuration, cancellation and retry hook of the call
+    ///
+    /// returns: Result<String, Box<dyn Error, Global>>
+    async fn fetch_live(
+        &self,
+        url: &str,
+        page: PageKind,
+        call: Call<'_>,
+    ) -> Result<String, Box<dyn Error>> {
+        // Also cancelled when this future is dropped, so the browser doesn't outlive the lookup
+        let cancel = call.cancel.child_token();
+        let _drop_guard = cancel.clone().drop_guard();
+        let mut attempt = 0;
+        loop {
+            if cancel.is_cancelled() {
+                return Err(HltbError::Cancelled.into());
+            }
+            if let Some(wait) = self.reserve_request() {
+                cancellable(&cancel, async {
+                    tokio::time::sleep(wait).await;
+                    Ok(())
+                })
+                .await?;
+            }
+            self.notify(|observer| observer.on_request_start(url));
+            let started = Instant::now();
+            let result = match call.config.backend {
+                #[cfg(feature = "browser")]
+                Backend::Browser => {
+                    let result = self.fetch_browser(url, page, call, &cancel).await;
+                    self.fall_back(url, page, call, &cancel, result).await
+                }
+                #[cfg(feature = "http")]
+                Backend::Http => {
+                    let fetch = self.fetch_http(url, page.selector(), call.config.timeout);
+                    let result = cancellable(&cancel, fetch).await;
+                    call.time(|timings| timings.navigation += started.elapsed());
+                    result
+                }
+                #[cfg(feature = "test-util")]
+                Backend::Mock => {
+                    let result = self.fetch_mock(url);
+                    call.time(|timings| timings.navigation += started.elapsed());
+                    result
+                }
+            };
+            call.time(|timings| timings.requests += 1);
+            let error = result.as_ref().err().map(|e| e.to_string());
+            self.notify(|observer| {
+                observer.on_request_end(url, started.elapsed(), error.as_deref())
+            });
+            match result {
+                Ok(content) => return Ok(content),
+                Err(e) if attempt >= call.config.retries || cancel.is_cancelled() => return Err(e),
+                Err(e) => {
+                    let error = e.to_string();
+                    self.notify(|observer| observer.on_retry(url, attempt + 1, &error));
+                    if let Some(on_retry) = call.on_retry {
+                        on_retry(attempt + 1, &error);
+                    }
+                    cancellable(&cancel, async {
+                        tokio::time::sleep(retry_delay(attempt)).await;
+                        Ok(())
+                    })
+                    .await?;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// Fetches a details page with the HTTP backend when the browser backend is unavailable,
+    /// e.g. when Chrome isn't installed
+    ///
+    /// When the HTTP backend fails too, or can't fetch the page, the error lists both attempts.
+    ///
+    /// # Arguments
+    ///
+    /// * `url`:  &str - The URL of the page
+    /// * `page`:  PageKind - The kind of the page
+    /// * `call`:  Call - The configuration of the call
+    /// * `cancel`:  &CancellationToken - Cancels the fetch
+    /// * `result`:  Result<String, Box<dyn Error>> - The result of the browser backend
+    ///
+    /// returns: Result<String, Box<dyn Error, Global>>
+    #[cfg(feature = "browser")]
+    async fn fall_back(
+        &self,
+        url: &str,
+        page: PageKind,
+        call: Call<'_>,
+        cancel: &CancellationToken,
+        result: Result<String, Box<dyn Error>>,
+    ) -> Result<String, Box<dyn Error>> {
+        let attempts = match result {
+            Err(e) => match e.downcast::<HltbError>() {
+                Ok(error) => match *error {
+                    HltbError::BackendUnavailable(attempts) => attempts,
+                    error => return Err(error.into()),
+                },
+                Err(e) => return Err(e),
+            },
+            ok => return ok,
+        };
+        #[cfg(feature = "http")]
+        let attempts = {
+            let mut attempts = attempts;
+            let attempt = if page == PageKind::Details {
+                let started = Instant::now();
+                let fetch = self.fetch_http(url, page.selector(), call.config.timeout);
+                let result = cancellable(cancel, fetch).await;
+                call.time(|timings| timings.navigation += started.elapsed());
+                match result {
+                    Ok(content) => return Ok(content),
+                    Err(e) if cancel.is_cancelled() => return Err(e),
+                    Err(e) => BackendAttempt::new(
+                        "http",
+                        &e.to_string(),
+                        "check the network connection and HLTB_PROXY, the site may also block \
+                         this address",
+                    ),
+                }
+            } else {
+                BackendAttempt::new(
+                    "http",
+                    "it can't search by name",
+                    "make the browser backend usable, or look the game up by ID",
+                )
+            };
+            attempts.push(attempt);
+            attempts
+        };
+        #[cfg(not(feature = "http"))]
+        let _ = (url, page, call, cancel);
+        Err(HltbError::BackendUnavailable(attempts).into())
+    }
+
+    /// Fetches a page with a headless browser, on a blocking thread that stops waiting when
+    /// the token is cancelled
+    ///
+    /// # Arguments
+    ///
+    /// * `url`:  &str - The URL of the page
+    /// * `page`:  PageKind - The kind of the page, which sets how it is waited for
+    /// * `call`:  Call - The configuration of the browser and the timings of the call
+    /// * `cancel`:  &CancellationToken - Cancels the fetch and closes the browser
+    ///
+    /// returns: Result<String, Box<dyn Error, Global>>
+    #[cfg(feature = "browser")]
+    async fn fetch_browser(
+        &self,
+        url: &str,
+        page: PageKind,
+        call: Call<'_>,
+        cancel: &CancellationToken,
+    ) -> Result<String, Box<dyn Error>> {
+        let url = url.to_string();
+        let (config, cancel) = (call.config.clone(), cancel.clone());
+        // The tab kept by the client, or the one of the previous page of the call if it shares
+        // one, so it isn't relaunched
+        let slot = self.kept.as_ref().map(|kept| &kept.tab).or(call.tab);
+        let mut tab = slot.and_then(|slot| slot.0.lock().unwrap().take());
+        let shared = slot.is_some();
+        let (result, measured, tab) = tokio::task::spawn_blocking(move || {
+            let wait = config.wait_strategy(page);
+            let mut timings = Timings::default();
+            let result = fetch_page_content_in_tab(
+                &mut tab,
+                &url,
+                page.selector(),
+                wait,
+                config.subtrees(page),
+                &config,
+                None,
+                Some(&cancel),
+                Some(&mut timings),
+            )
+            .map_err(sendable);
+            // A tab nothing shares is closed here rather than on the async thread
+            (result, timings, tab.filter(|_| shared))
+        })
+        .await?;
+        call.time(|timings| timings.add(&measured));
+        if let Some(slot) = slot {
+            // Concurrent lookups of a client keeping its browser each launch one when the kept
+            // tab is in use, only one of them is kept
+            let spare = {
+                let mut kept = slot.0.lock().unwrap();
+                if kept.is_none() {
+                    *kept = tab;
+                    None
+                } else {
+                    tab
+                }
+            };
+            if let Some(spare) = spare {
+                drop(tokio::task::spawn_blocking(move || drop(spare)));
+            }
+        }
+        if let Some(kept) = &self.kept {
+            kept.close_when_idle();
+        }
+        result.map_err(|e| e as Box<dyn Error>)
+    }
+
+    /// Fetches a page from the mock backend
+    ///
+    /// # Arguments
+    ///
+    /// * `url`:  &str - The URL of the page
+    ///
+    /// returns: Result<String, Box<dyn Error, Global>>
+    #[cfg(feature = "test-util")]
+    fn fetch_mock(&self, url: &str) -> Result<String, Box<dyn Error>> {
+        let mock = self.mock.as_ref().ok_or("The mock backend is not set up")?;
+        mock.respond(url.strip_prefix(&self.config.base_url).unwrap_or(url))
+    }
+
+    /// Downloads a URL with the HTTP client, in a request slot of its host and within the
+    /// maximum body size
+    ///
+    /// # Arguments
+    ///
+    /// * `url`:  &str - The URL
+    /// * `timeout`:  Duration - How long to wait for the response
+    ///
+    /// returns: Result<(HeaderMap, Vec<u8, Global>), Box<dyn Error, Global>> - The headers and
+    /// the body of the response
+    #[cfg(feature = "http")]
+    async fn download(
+        &self,
+        url: &str,
+        timeout: Duration,
+    ) -> Result<(reqwest::header::HeaderMap, Vec<u8>), Box<dyn Error>> {
+        let _slot = host_slot(url).await?;
+        let response = self
+            .http_or_new_client()?
+            .get(url)
+            .timeout(timeout)
+            .send()
+            .await?
+            .error_for_status()?;
+        let headers = response.headers().clone();
+        let limit = self.config.max_body_size.unwrap_or(usize::MAX);
+        Ok((headers, read_body(response, url, limit).await?))
+    }
+
+    /// Fetches a page with a plain HTTP request
+    ///
+    /// # Arguments
+    ///
+    /// * `url`:  &str - The URL of the page
+    /// * `wait_selector`:  &str - A selector the page must match to be considered complete
+    /// * `timeout`:  Duration - How long to wait for the response
+    ///
+    /// returns: Result<String, Box<dyn Error, Global>>
+    #[cfg(feature = "http")]
+    async fn fetch_http(
+        &self,
+        url: &str,
+        wait_selector: &str,
+        timeout: Duration,
+    ) -> Result<String, Box<dyn Error>> {
+        let (_, body) = self.download(url, timeout).await?;
+        let content = String::from_utf8_lossy(&body).into_owned();
+        let selector = Selector::parse(wait_selector).map_err(|e| e.to_string())?;
+        if Html::parse_document(&content)
+            .select(&selector)
+            .next()
+            .is_none()
+        {
+            return Err(HltbError::ElementNotFound(wait_selector.to_string()).into());
+        }
+        Ok(content)
+    }
+
+    /// Reports the outcome of the parsing of a page to the observers and the audit log
+    ///
+    /// # Arguments
+    ///
+    /// * `url`:  &str - The URL of the page
+    /// * `content`:  &str - The content of the page
+    /// * `result`:  Result<T, Box<dyn Error>> - The result of the parsing
+    ///
+    /// returns: Result<T, Box<dyn Error, Global>> - The result of the parsing
+    fn parsed<T>(
+        &self,
+        url: &str,
+        content: &str,
+        result: Result<T, Box<dyn Error>>,
+    ) -> Result<T, Box<dyn Error>> {
+        let outcome = match &result {
+            Ok(_) => AuditOutcome::Parsed,
+            Err(e) => {
+                let error = e.to_string();
+                self.notify(|observer| observer.on_parse_error(url, &error));
+                AuditOutcome::ParseError(error)
+            }
+        };
+        self.audit(url, Some(content), outcome)?;
+        result
+    }
+
+    /// Appends the entry of a page to the audit log, if the client has one
+    ///
+    /// # Arguments
+    ///
+    /// * `url`:  &str - The URL of the page
+    /// * `content`:  Option<&str> - The content of the page, None if it couldn't be fetched
+    /// * `outcome`:  AuditOutcome - What became of the page
+    ///
+    /// returns: Result<(), Box<dyn Error, Global>>
+    #[cfg_attr(not(feature = "audit"), allow(unused_variables))]
+    fn audit(
+        &self,
+        url: &str,
+        content: Option<&str>,
+        outcome: AuditOutcome,
+    ) -> Result<(), Box<dyn Error>> {
+        #[cfg(feature = "audit")]
+        if let Some(audit) = &self.audit {
+            audit.record(url, content, outcome)?;
+        }
+        Ok(())
+    }
+
+    /// Passes an event to every observer of the client
+    ///
+    /// # Arguments
+    ///
+    /// * `event`:  impl Fn(&dyn Observer) - Calls the method of the event on an observer
+    fn notify(&self, event: impl Fn(&dyn Observer)) {
+        for observer in &self.observers {
+            event(observer.as_ref());
+        }
+    }
+
+    /// Reserves the next request slot of the rate limit and returns how long to wait for it
+    ///
+    /// returns: Option<Duration>
+    fn reserve_request(&self) -> Option<Duration> {
+        next_request_wait(self.config.rate_limit, &self.next_request)
+    }
+}
+
+/// Reserves the next request slot of a rate limit and returns how long to wait for it
+///
+/// # Arguments
+///
+/// * `rate_limit`:  Option<Duration> - The minimum interval between requests, if any
+/// * `next_request`:  &Mutex<Option<Instant>> - The earliest time the next request may start
+///
+/// returns: Option<Duration>
+fn next_request_wait(
+    rate_limit: Option<Duration>,
+    next_request: &Mutex<Option<Instant>>,
+) -> Option<Duration> {
+    let interval = rate_limit?;
+    let mut next_request = next_request.lock().unwrap();
+    let now = Instant::now();
+    let start = next_request.map_or(now, |next| next.max(now));
+    *next_request = Some(start + interval);
+    Some(start - now)
+}
+
+/// The client returned by [`global`]
+static GLOBAL: OnceLock<HltbClient> = OnceLock::new();
+
+/// How long the browser of the [`global`] client stays open without lookups
+#[cfg(feature = "browser")]
+const GLOBAL_BROWSER_IDLE: Duration = Duration::from_secs(30);
+
+/// Returns the client shared by the whole process, built on first use from the default
+/// configuration and the environment variables
+///
+/// Lookups through it share its rate limit, cache, HTTP connections and browser, without
+/// passing a client around. The browser is launched by the first lookup and kept open until
+/// no lookup used it for 30 seconds, see [`HltbClientBuilder::keep_browser`]. Since a static
+/// is never dropped, applications exiting sooner should call
+/// [`close_browser`](HltbClient::close_browser) first. Applications needing another
+/// configuration should build their own client.
+///
+/// returns: Result<&HltbClient, Box<dyn Error, Global>>
+pub fn global() -> Result<&'static HltbClient, Box<dyn Error>> {
+    if let Some(client) = GLOBAL.get() {
+        return Ok(client);
+    }
+    let builder = HltbClient::builder();
+    #[cfg(feature = "browser")]
+    let builder = builder.keep_browser(GLOBAL_BROWSER_IDLE);
+    // Two threads may both build a client on first use, only one of them is kept
+    let client = builder.build()?;
+    Ok(GLOBAL.get_or_init(|| client))
+}
+
+/// A game along with the HTML of its details page, to extract fields the crate doesn't parse
+#[derive(Debug, Clone)]
+pub struct GamePage {
+    pub game: Game,
+    pub html: String,
+}
+
+impl GamePage {
+    /// Returns the `__NEXT_DATA__` JSON embedded in the page, if any
+    ///
+    /// returns: Option<Value>
+    pub fn next_data(&self) -> Option<serde_json::Value> {
+        parse::next_data(&self.html)
+    }
+
+    /// Returns the release dates of the game by platform and by region
+    ///
+    /// returns: ReleaseDates
+    pub fn release_dates(&self) -> ReleaseDates {
+        parse::release_dates(&self.html)
+    }
+
+    /// Returns the distributions of the submitted times of the game, for the play styles the
+    /// page has one for
+    ///
+    /// returns: BTreeMap<PlayStyle, Distribution>
+    pub fn distributions(&self) -> BTreeMap<PlayStyle, Distribution> {
+        distribution::distributions(&self.html)
+    }
+
+    /// Returns the URL of the cover image of the game, if the page has one
+    ///
+    /// returns: Option<String>
+    pub fn cover_url(&self) -> Option<String> {
+        parse::cover_url(&self.html)
+    }
+
+    /// Returns the layout fingerprint of the page, to store along with the game and compare later
+    ///
+    /// returns: LayoutFingerprint
+    pub fn layout(&self) -> LayoutFingerprint {
+        layout::fingerprint(&self.html)
+    }
+}
+
+/// The game a search resolved to
+struct Found {
+    hltb_id: u32,
+    /// Every result of the search page the game was picked from
+    results: Vec<SearchResult>,
+    /// The spelling that found the game, when the searched name had no results
+    variant: Option<String>,
+    /// Whether the name is mapped to the game by the overrides, without searching
+    overridden: bool,
+    /// The URL of the search page the game was picked from, None if it was overridden
+    search_url: Option<String>,
+}
+
+/// Checks whether an error means that a search had no results to pick from
+///
+/// # Arguments
+///
+/// * `error`:  &dyn Error - The error of the search
+///
+/// returns: bool
+pub(crate) fn is_no_results(error: &(dyn Error + 'static)) -> bool {
+    matches!(
+        error.downcast_ref::<HltbError>(),
+        Some(HltbError::ElementNotFound(_) | HltbError::NoMatch(_))
+    )
+}
+
+/// Called before a retry with the number of the retry and the error of the failed attempt
+type RetryHook<'a> = &'a (dyn Fn(u32, &str) + Sync);
+
+/// The per-call state of a lookup, passed down to every fetch it makes
+#[derive(Clone, Copy)]
+struct Call<'a> {
+    /// The configuration of the client, with the overrides of the call
+    config: &'a ClientConfig,
+    cancel: &'a CancellationToken,
+    on_retry: Option<RetryHook<'a>>,
+    /// Where the timings of the fetches of the call are added, if anywhere
+    timings: Option<&'a Mutex<Timings>>,
+    /// The browser tab the pages of the call are loaded in one after the other, if they share
+    /// one
+    #[cfg(feature = "browser")]
+    tab: Option<&'a TabSlot>,
+}
+
+/// The browser tab shared by the pages of a call, empty until the first page is loaded
+///
+/// Closing the tab blocks until its browser exits, so it is closed on the blocking thread
+/// pool when the slot is dropped at the end of the call.
+#[cfg(feature = "browser")]
+#[derive(Default)]
+struct TabSlot(Mutex<Option<Arc<BrowserGuard>>>);
+
+#[cfg(feature = "browser")]
+impl Drop for TabSlot {
+    fn drop(&mut self) {
+        let Some(tab) = self.0.get_mut().unwrap().take() else {
+            return;
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => drop(runtime.spawn_blocking(move || drop(tab))),
+            Err(_) => drop(tab),
+        }
+    }
+}
+
+/// A browser a client keeps open between its lookups, see [`HltbClientBuilder::keep_browser`]
+#[cfg(feature = "browser")]
+struct KeptBrowser {
+    tab: TabSlot,
+    /// How long the browser stays open without lookups
+    idle: Duration,
+    /// The number of lookups that used the browser, so a timer can tell whether it stayed idle
+    uses: AtomicU64,
+}
+
+#[cfg(feature = "browser")]
+impl KeptBrowser {
+    /// Closes the browser if no other lookup uses it before it has been idle for long enough
+    fn close_when_idle(self: &Arc<KeptBrowser>) {
+        let uses = self.uses.fetch_add(1, Ordering::SeqCst) + 1;
+        // The timer doesn't keep the browser of a dropped client open
+        let kept = Arc::downgrade(self);
+        let idle = self.idle;
+        tokio::spawn(async move {
+            tokio::time::sleep(idle).await;
+            let Some(kept) = kept.upgrade() else {
+                return;
+            };
+            if kept.uses.load(Ordering::SeqCst) == uses {
+                let tab = kept.tab.0.lock().unwrap().take();
+                if let Some(tab) = tab {
+                    drop(tokio::task::spawn_blocking(move || drop(tab)));
+                }
+            }
+        });
+    }
+}
+
+/// A stand-in for the tab of a call without the browser backend
+#[cfg(not(feature = "browser"))]
+type TabSlot = Mutex<()>;
+
+impl<'a> Call<'a> {
+    fn new(config: &'a ClientConfig, cancel: &'a CancellationToken) -> Call<'a> {
+        Call {
+            config,
+            cancel,
+            on_retry: None,
+            timings: None,
+            #[cfg(feature = "browser")]
+            tab: None,
+        }
+    }
+
+    /// Loads the pages of the call in the same browser tab, so a lookup by name loads the
+    /// details page in the tab of the search page rather than launching another browser
+    ///
+    /// Each page is still a full navigation of the tab, which only saves the launch of the
+    /// browser. A call already sharing a tab keeps it.
+    ///
+    /// # Arguments
+    ///
+    /// * `tab`:  &TabSlot - The tab to share, closed when dropped
+    ///
+    /// returns: Call
+    #[cfg_attr(not(feature = "browser"), allow(unused_variables))]
+    fn sharing_tab(self, tab: &'a TabSlot) -> Call<'a> {
+        Call {
+            #[cfg(feature = "browser")]
+            tab: self.tab.or(Some(tab)),
+            ..self
+        }
+    }
+
+    /// Adds to the timings of the call, if they are measured
+    ///
+    /// # Arguments
+    ///
+    /// * `add`:  impl FnOnce(&mut Timings) - Adds the measured durations
+    fn time(&self, add: impl FnOnce(&mut Timings)) {
+        if let Some(timings) = self.timings {
+            add(&mut timings.lock().unwrap());
+        }
+    }
+}
+
+/// Waits for a request slot of the host of a URL, released when the permit is dropped
+///
+/// Every request to the site takes one, whichever backend or function sends it: the
+/// fetches of the clients and sessions, the download of covers, and the pages of the
+/// crawling functions of the crate.
+///
+/// # Arguments
+///
+/// * `url`:  &str - The URL about to be requested
+///
+/// returns: Result<OwnedSemaphorePermit, Box<dyn Error, Global>>
+pub(crate) async fn host_slot(url: &str) -> Result<OwnedSemaphorePermit, Box<dyn Error>> {
+    Ok(host_slots(url).acquire_owned().await?)
+}
+
+/// Waits for a request slot of the host of a URL on a blocking thread, see [`host_slot`]
+///
+/// # Arguments
+///
+/// * `url`:  &str - The URL about to be requested
+/// * `cancel`:  Option<&CancellationToken> - Gives up the wait when cancelled
+///
+/// returns: Result<OwnedSemaphorePermit, Box<dyn Error, Global>>
+#[cfg(feature = "browser")]
+pub(crate) fn host_slot_blocking(
+    url: &str,
+    cancel: Option<&CancellationToken>,
+) -> Result<OwnedSemaphorePermit, Box<dyn Error>> {
+    let slots = host_slots(url);
+    loop {
+        if cancel.is_some_and(CancellationToken::is_cancelled) {
+            return Err(HltbError::Cancelled.into());
+        }
+        match slots.clone().try_acquire_owned() {
+            Ok(permit) => return Ok(permit),
+            Err(TryAcquireError::NoPermits) => std::thread::sleep(HOST_SLOT_POLL_INTERVAL),
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Returns the request slots of the host of a URL
+///
+/// # Arguments
+///
+/// * `url`:  &str - The URL about to be requested
+///
+/// returns: Arc<Semaphore, Global>
+fn host_slots(url: &str) -> Arc<Semaphore> {
+    let host = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest)
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    HOST_SLOTS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .entry(host)
+        .or_insert_with(|| Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS)))
+        .clone()
+}
+
+/// Runs a future until it completes or the token is cancelled
+///
+/// # Arguments
+///
+/// * `cancel`:  &CancellationToken - The token cancelling the future
+/// * `future`:  F - The future to run
+///
+/// returns: Result<T, Box<dyn Error, Global>>
+async fn cancellable<T, F>(cancel: &CancellationToken, future: F) -> Result<T, Box<dyn Error>>
+where
+    F: std::future::Future<Output = Result<T, Box<dyn Error>>>,
+{
+    tokio::select! {
+        result = future => result,
+        _ = cancel.cancelled() => Err(HltbError::Cancelled.into()),
+    }
+}
+
+/// Reads the body of a response, in chunks so that a body over the limit is never held whole
+///
+/// # Arguments
+///
+/// * `response`:  Response - The response
+/// * `url`:  &str - The URL of the request, for the error
+/// * `limit`:  usize - The maximum size of the body, in bytes
+///
+/// returns: Result<Vec<u8, Global>, Box<dyn Error, Global>>
+#[cfg(feature = "http")]
+async fn read_body(
+    mut response: reqwest::Response,
+    url: &str,
+    limit: usize,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let too_large = || HltbError::BodyTooLarge {
+        url: url.to_string(),
+        limit,
+    };
+    // The announced length is that of the compressed body, only a first check
+    if response
+        .content_length()
+        .is_some_and(|length| length > limit as u64)
+    {
+        return Err(too_large().into());
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > limit {
+            return Err(too_large().into());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// Returns the delay before a retry of a failed request
+///
+/// # Arguments
+///
+/// * `attempt`:  u32 - The number of the failed attempt, starting at 0
+///
+/// returns: Duration
+fn retry_delay(attempt: u32) -> Duration {
+    RETRY_DELAY * 2u32.pow(attempt.min(MAX_RETRY_DOUBLINGS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[cfg(feature = "browser")]
+    #[test]
+    fn test_builder() {
+        let client = HltbClient::builder()
+            .sandbox(false)
+            .retries(2)
+            .timeout(Duration::from_secs(5))
+            .base_url("http://localhost:8080")
+            .build()
+            .unwrap();
+        let config = client.config();
+        assert_eq!(config.backend, Backend::Browser);
+        assert!(!config.sandbox);
+        assert_eq!(config.retries, 2);
+        assert_eq!(config.timeout, Duration::from_secs(5));
+        assert_eq!(config.base_url, "http://localhost:8080/");
+        assert_eq!(config.user_agent, USER_AGENT);
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let client = HltbClient::builder()
+            .rate_limit(Duration::from_secs(10))
+            .build()
+            .unwrap();
+        assert_eq!(client.reserve_request(), Some(Duration::ZERO));
+        let wait = client.reserve_request().unwrap();
+        assert!(wait > Duration::from_secs(9) && wait <= Duration::from_secs(10));
+
+        let unlimited = HltbClient::builder().build().unwrap();
+        assert_eq!(unlimited.reserve_request(), None);
+    }
+
+    #[tokio::test]
+    async fn test_host_slots() {
+        let url = "https://slots.test/game/5900";
+        let mut permits = Vec::new();
+        for _ in 0..MAX_CONCURRENT_REQUESTS {
+            permits.push(host_slot(url).await.unwrap());
+        }
+        let next = tokio::time::timeout(Duration::from_millis(20), host_slot(url));
+        assert!(next.await.is_err());
+        #[cfg(feature = "browser")]
+        {
+            let cancel = CancellationToken::new();
+            cancel.cancel();
+            let error = host_slot_blocking(url, Some(&cancel)).unwrap_err();
+            assert_eq!(
+                error.downcast_ref::<HltbError>(),
+                Some(&HltbError::Cancelled)
+            );
+        }
+        let other =
+            tokio::time::timeout(Duration::from_millis(20), host_slot("https://other.test/"));
+        assert!(other.await.is_ok());
+        permits.pop();
+        assert!(host_slot("HTTPS://SLOTS.TEST/?q=metal").await.is_ok());
+    }
+
+    #[test]
+    fn test_config_from_vars() {
+        let vars = HashMap::from([
+            ("HLTB_TIMEOUT", "7.5"),
+            ("HLTB_RETRIES", "3"),
+            ("HLTB_CACHE_DIR", "/var/cache/hltb"),
+            ("HLTB_CACHE_SOFT_TTL", "3600"),
+            ("HLTB_HISTORY_DIR", "/var/lib/hltb"),
+            ("HLTB_OVERRIDES", "overrides.json"),
+            ("HLTB_PROXY", "http://proxy:3128"),
+            ("HLTB_IDENTITY", "my-app (+mailto:me@example.com)"),
+            ("HLTB_SANDBOX", "false"),
+            ("HLTB_CHROME_PATH", ""),
+            ("HLTB_MAX_BODY_SIZE", "none"),
+            ("HLTB_LAUNCH_PROFILE", "Container"),
+            ("HLTB_SHUTDOWN_TIMEOUT", "2"),
+            ("HLTB_DRY_RUN", "yes"),
+        ]);
+        let config = ClientConfig::from_vars(|name| vars.get(name).map(|v| v.to_string())).unwrap();
+        assert_eq!(config.timeout, Duration::from_millis(7500));
+        assert_eq!(config.retries, 3);
+        assert_eq!(config.cache_dir, Some(PathBuf::from("/var/cache/hltb")));
+        assert_eq!(
+            config.cache_policy,
+            CachePolicy::StaleWhileRevalidate {
+                soft_ttl: Duration::from_secs(3600)
+            }
+        );
+        assert_eq!(config.history_dir, Some(PathBuf::from("/var/lib/hltb")));
+        assert_eq!(config.overrides_file, Some(PathBuf::from("overrides.json")));
+        assert_eq!(config.proxy, Some("http://proxy:3128".to_string()));
+        assert_eq!(
+            config.full_user_agent(),
+            format!("{} my-app (+mailto:me@example.com)", USER_AGENT)
+        );
+        let identified = HltbClient::builder()
+            .identity("my-app/1.0", "https://example.com")
+            .build()
+            .unwrap();
+        assert_eq!(
+            identified.config().identity.as_deref(),
+            Some("my-app/1.0 (+https://example.com)")
+        );
+        assert!(!config.sandbox);
+        assert_eq!(config.chrome_path, None);
+        assert_eq!(config.base_url, BASE_URL);
+        assert_eq!(config.max_body_size, None);
+        assert_eq!(config.launch_profile, LaunchProfile::Container);
+        assert!(!config.launch_profile.sandbox(true));
+        assert_eq!(config.shutdown_timeout, Duration::from_secs(2));
+        assert!(config.dry_run);
+        assert_eq!(ClientConfig::default().max_body_size, Some(MAX_BODY_SIZE));
+
+        let invalid =
+            ClientConfig::from_vars(|name| (name == "HLTB_TIMEOUT").then(|| "soon".to_string()));
+        assert_eq!(
+            invalid.unwrap_err().to_string(),
+            "Invalid HLTB_TIMEOUT: soon"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_fetch() {
+        let client = HltbClient::builder()
+            .rate_limit(Duration::from_secs(60))
+            .build()
+            .unwrap();
+        // The first request takes the free slot, the second one waits a minute for its turn
+        client.reserve_request();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let started = Instant::now();
+        let result = client.search_by_id_with_cancel(5900, &cancel).await;
+        assert_eq!(result.unwrap_err().to_string(), "Lookup cancelled");
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[cfg(feature = "test-util")]
+    #[tokio::test]
+    async fn test_normalized_query_cache() {
+        let dir = std::env::temp_dir().join("hltb_normalized_query_test");
+        let game = Game::new(
+            "Hollow Knight".to_string(),
+            26286,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let client = HltbClient::builder()
+            .mock(MockBackend::new().with_game(game))
+            .cache_dir(&dir)
+            .build()
+            .unwrap();
+        let first = client.search("Hollow Knight").send_with_timings().await;
+        let second = client.search("hollow  Knight ").send_with_timings().await;
+        let matched = client.search("HOLLOW KNIGHT").send_with_match().await;
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(first.unwrap().timings.requests, 2);
+        let second = second.unwrap();
+        assert_eq!(second.game.hltb_id, 26286);
+        assert_eq!(second.timings.requests, 0);
+        // The results of the search are cached along with the ID
+        let matched = matched.unwrap();
+        assert_eq!(matched.info.candidates, 1);
+        assert_eq!(
+            matched.game.search_url,
+            Some(client.search_url("HOLLOW KNIGHT"))
+        );
+        assert_eq!(
+            second.game.search_url,
+            Some(client.search_url("hollow  Knight "))
+        );
+    }
+
+    #[cfg(all(feature = "browser", feature = "http"))]
+    #[tokio::test]
+    async fn test_backend_unavailable() {
+        let client = HltbClient::builder()
+            .backend(Backend::Browser)
+            .chrome_path("/nonexistent/chrome")
+            .base_url("http://127.0.0.1:9")
+            .timeout(Duration::from_secs(1))
+            .build()
+            .unwrap();
+        let error = client.search_by_id(5900).await.unwrap_err();
+        let Some(HltbError::BackendUnavailable(attempts)) = error.downcast_ref::<HltbError>()
+        else {
+            panic!("Unexpected error: {}", error);
+        };
+        let backends: Vec<&str> = attempts.iter().map(|a| a.backend.as_str()).collect();
+        assert_eq!(backends, ["browser", "http"]);
+        assert!(error.to_string().starts_with(
+            "No backend is usable: the browser backend failed (Chrome couldn't be launched"
+        ));
+    }
+
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn test_http_backend_cannot_search() {
+        let client = HltbClient::builder()
+            .backend(Backend::Http)
+            .build()
+            .unwrap();
+        let error = client.find_id("Metal Gear").await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Searching by name requires the browser backend"
+        );
+    }
+
+    #[test]
+    fn test_global() {
+        let client = global().unwrap();
+        assert!(std::ptr::eq(client, global().unwrap()));
+        #[cfg(feature = "browser")]
+        assert_eq!(
+            client.kept.as_ref().map(|kept| kept.idle),
+            Some(GLOBAL_BROWSER_IDLE)
+        );
+    }
+
+    #[test]
+    fn test_wait_strategy() {
+        let network_idle = WaitStrategy::NetworkIdle {
+            quiet: Duration::from_millis(500),
+        };
+        let client = HltbClient::builder()
+            .wait_strategy(PageKind::Search, network_idle.clone())
+            .poll_interval(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let config = client.config();
+        assert_eq!(config.wait_strategy(PageKind::Search), &network_idle);
+        assert_eq!(
+            config.wait_strategy(PageKind::Details),
+            &WaitStrategy::Selector
+        );
+        assert_eq!(config.poll_interval, Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(0), Duration::from_millis(500));
+        assert_eq!(retry_delay(2), Duration::from_secs(2));
+        assert_eq!(retry_delay(40), Duration::from_secs(32));
+    }
+
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn test_shared_http_client() {
+        let (url, connections) = serve("<html></html>");
+        let timeout = Duration::from_secs(5);
+        let client = HltbClient::builder()
+            .backend(Backend::Http)
+            .build()
+            .unwrap();
+        let shared = HltbClient::builder()
+            .backend(Backend::Http)
+            .http_client(client.http_client().unwrap().clone())
+            .build()
+            .unwrap();
+        let other = HltbClient::builder()
+            .backend(Backend::Http)
+            .build()
+            .unwrap();
+        client.download(&url, timeout).await.unwrap();
+        // The shared client reuses the connection of the pool
+        shared.download(&url, timeout).await.unwrap();
+        assert_eq!(connections.load(std::sync::atomic::Ordering::SeqCst), 1);
+        other.download(&url, timeout).await.unwrap();
+        assert_eq!(connections.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    /// Serves a page to every connection on a local port, counting the connections
+    ///
+    /// # Arguments
+    ///
+    /// * `body`:  &str - The HTML of the page
+    ///
+    /// returns: (String, Arc<AtomicUsize, Global>) - The base URL and the connection count
+    #[cfg(feature = "http")]
+    fn serve(body: &str) -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        use std::io::{BufRead, BufReader, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let connections = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let counted = connections.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { return };
+                counted.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let response = response.clone();
+                std::thread::spawn(move || {
+                    let mut reader = BufReader::new(&stream);
+                    let mut line = String::new();
+                    // Answers every request of a kept-alive connection
+                    while reader.read_line(&mut line).is_ok_and(|read| read > 0) {
+                        if line == "\r\n" && (&stream).write_all(response.as_bytes()).is_err() {
+                            return;
+                        }
+                        line.clear();
+                    }
+                });
+            }
+        });
+        (url, connections)
+    }
+
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn test_search_without_results() {
+        let (url, _) = serve(
+            r#"<html><body><div><div id="search-results-header">
+            <h3>No results for "Metal Gear Sold"</h3></div>
+            <p>Did you mean <a href="/?q=Metal%20Gear%20Solid">Metal Gear Solid</a>?</p>
+            </div></body></html>"#,
+        );
+        let client = HltbClient::builder()
+            .backend(Backend::Http)
+            .build()
+            .unwrap();
+        let content = client
+            .fetch_http(&url, PageKind::Search.selector(), Duration::from_secs(5))
+            .await
+            .unwrap();
+        let error = parse::search_result(&content).unwrap_err();
+        assert!(is_no_results(error.as_ref()));
+        assert_eq!(
+            parse::suggestion(&content).as_deref(),
+            Some("Metal Gear Solid")
+        );
+    }
+
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn test_body_too_large() {
+        let (url, _) = serve(&"<p>Metal Gear</p>".repeat(100));
+        let client = HltbClient::builder()
+            .backend(Backend::Http)
+            .max_body_size(Some(1000))
+            .build()
+            .unwrap();
+        let error = client
+            .fetch_http(&url, "p", Duration::from_secs(5))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<HltbError>(),
+            Some(&HltbError::BodyTooLarge {
+                url: url.clone(),
+                limit: 1000
+            })
+        );
+        let client = HltbClient::builder()
+            .backend(Backend::Http)
+            .max_body_size(None)
+            .build()
+            .unwrap();
+        assert!(client
+            .fetch_http(&url, "p", Duration::from_secs(5))
+            .await
+            .is_ok());
+    }
+}
@@ -0,0 +1,163 @@
+//! Provenance log of the fetched pages (`audit` feature).
+//!
+//! With [`HltbClientBuilder::audit_log`](super::HltbClientBuilder::audit_log), the
+//! client appends a JSON line to a file for every page it fetches: the URL, when it
+//! was fetched, the SHA-256 of its content and whether it could be parsed. Data
+//! pipelines can then prove where each datum came from, and find the page behind a
+//! problem; [`read_audit_log`] reads the log back.
+
+use serde::Deserialize;
+use serde::Serialize;
+#[cfg(feature = "audit")]
+use std::error::Error;
+#[cfg(feature = "audit")]
+use std::fs::{File, OpenOptions};
+#[cfg(feature = "audit")]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(feature = "audit")]
+use std::path::Path;
+#[cfg(feature = "audit")]
+use std::sync::Mutex;
+
+/// What became of a fetched page
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case", tag = "status", content = "error")]
+pub enum AuditOutcome {
+    /// The page was parsed successfully
+    Parsed,
+    /// The page was returned to the caller without being parsed
+    Unparsed,
+    /// The page couldn't be parsed
+    ParseError(String),
+    /// The page couldn't be fetched
+    RequestError(String),
+}
+
+/// A line of the audit log
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AuditEntry {
+    pub url: String,
+    /// When the entry was written, in RFC 3339 format
+    pub timestamp: String,
+    /// The SHA-256 of the content of the page, in hexadecimal, None if it couldn't be fetched
+    pub sha256: Option<String>,
+    /// The size of the content of the page in bytes, None if it couldn't be fetched
+    pub bytes: Option<usize>,
+    pub outcome: AuditOutcome,
+}
+
+/// The append-only file of an audit log
+#[cfg(feature = "audit")]
+pub(super) struct AuditLog {
+    file: Mutex<File>,
+}
+
+#[cfg(feature = "audit")]
+impl AuditLog {
+    /// Opens an audit log, creating the file if needed
+    ///
+    /// # Arguments
+    ///
+    /// * `path`:  &Path - The path of the log
+    ///
+    /// returns: Result<AuditLog, Box<dyn Error, Global>>
+    pub(super) fn open(path: &Path) -> Result<AuditLog, Box<dyn Error>> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(AuditLog {
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Appends the entry of a page to the log
+    ///
+    /// # Arguments
+    ///
+    /// * `url`:  &str - The URL of the page
+    /// * `content`:  Option<&str> - The content of the page, None if it couldn't be fetched
+    /// * `outcome`:  AuditOutcome - What became of the page
+    ///
+    /// returns: Result<(), Box<dyn Error, Global>>
+    pub(super) fn record(
+        &self,
+        url: &str,
+        content: Option<&str>,
+        outcome: AuditOutcome,
+    ) -> Result<(), Box<dyn Error>> {
+        use sha2::{Digest, Sha256};
+
+        let entry = AuditEntry {
+            url: url.to_string(),
+            timestamp: crate::repr::format_timestamp(std::time::SystemTime::now()),
+            sha256: content.map(|content| {
+                Sha256::digest(content.as_bytes())
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect()
+            }),
+            bytes: content.map(str::len),
+            outcome,
+        };
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+        // One write per line, so concurrent lookups don't interleave their entries
+        self.file.lock().unwrap().write_all(line.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Reads the entries of an audit log
+///
+/// # Arguments
+///
+/// * `path`:  impl AsRef<Path> - The path of the log
+///
+/// returns: Result<Vec<AuditEntry>, Box<dyn Error, Global>>
+#[cfg(feature = "audit")]
+pub fn read_audit_log(path: impl AsRef<Path>) -> Result<Vec<AuditEntry>, Box<dyn Error>> {
+    let file = File::open(path)?;
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            entries.push(serde_json::from_str(&line)?);
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(all(test, feature = "audit"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_log() {
+        let path = std::env::temp_dir().join(format!("hltb-audit-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let log = AuditLog::open(&path).unwrap();
+        log.record(
+            "https://howlongtobeat.com/game/5900",
+            Some("abc"),
+            AuditOutcome::Parsed,
+        )
+        .unwrap();
+        log.record(
+            "https://howlongtobeat.com/game/1",
+            None,
+            AuditOutcome::RequestError("timeout".to_string()),
+        )
+        .unwrap();
+
+        let entries = read_audit_log(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].sha256.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(entries[0].bytes, Some(3));
+        assert_eq!(
+            entries[1].outcome,
+            AuditOutcome::RequestError("timeout".to_string())
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Total time of a backlog.
+//!
+//! [`HltbClient::estimate_backlog`] resolves a list of games, by name or ID, and
+//! adds up how long they take in a play style, keeping track of what every game
+//! contributes and of the entries that couldn't be resolved.
+
+use super::HltbClient;
+use crate::export::ical::{plan_backlog, ScheduledGame};
+use crate::{EstimateKind, Game, PlayStyle};
+use std::error::Error;
+use std::time::SystemTime;
+
+/// A game of a backlog, looked up by name or by its How Long to Beat ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BacklogEntry {
+    Name(String),
+    Id(u32),
+}
+
+impl From<&str> for BacklogEntry {
+    fn from(name: &str) -> Self {
+        BacklogEntry::Name(name.to_string())
+    }
+}
+
+impl From<String> for BacklogEntry {
+    fn from(name: String) -> Self {
+        BacklogEntry::Name(name)
+    }
+}
+
+impl From<u32> for BacklogEntry {
+    fn from(hltb_id: u32) -> Self {
+        BacklogEntry::Id(hltb_id)
+    }
+}
+
+impl std::fmt::Display for BacklogEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BacklogEntry::Name(name) => write!(f, "{}", name),
+            BacklogEntry::Id(hltb_id) => write!(f, "#{}", hltb_id),
+        }
+    }
+}
+
+/// An entry of a backlog with the result of its lookup
+type Lookup = (BacklogEntry, Result<Game, Box<dyn Error>>);
+
+/// What a resolved game adds to the total of a backlog
+#[derive(Debug, Clone, PartialEq)]
+pub struct BacklogItem {
+    pub entry: BacklogEntry,
+    pub game: Game,
+    /// The time of the game in the play style in seconds, None if nobody submitted one
+    pub seconds: Option<f32>,
+}
+
+/// The total time of a backlog
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BacklogReport {
+    /// The sum of the times of the games in seconds
+    pub total_seconds: f32,
+    /// The resolved games, in the order of the backlog
+    pub items: Vec<BacklogItem>,
+    /// The entries that couldn't be resolved, with the reason
+    pub unresolved: Vec<(BacklogEntry, String)>,
+}
+
+impl BacklogReport {
+    /// Builds the report of a backlog from the lookup of each entry
+    ///
+    /// # Arguments
+    ///
+    /// * `style`:  PlayStyle - The play style whose average times are added up
+    /// * `results`:  Vec<Lookup> - Every entry with the result of its lookup
+    ///
+    /// returns: BacklogReport
+    fn new(style: PlayStyle, results: Vec<Lookup>) -> Self {
+        let mut report = BacklogReport::default();
+        for (entry, result) in results {
+            match result {
+                Ok(game) => {
+                    let seconds = game.estimate(style, EstimateKind::Average);
+                    report.total_seconds += seconds.unwrap_or(0.0);
+                    report.items.push(BacklogItem {
+                        entry,
+                        game,
+                        seconds,
+                    });
+                }
+                Err(e) => report.unresolved.push((entry, e.to_string())),
+            }
+        }
+        report
+    }
+
+    /// The sum of the times of the games in hours
+    ///
+    /// returns: f32
+    pub fn total_hours(&self) -> f32 {
+        self.total_seconds / 3600.0
+    }
+
+    /// The resolved games that have no time in the play style, and so add nothing to the total
+    ///
+    /// returns: impl Iterator<Item = &BacklogItem>
+    pub fn without_time(&self) -> impl Iterator<Item = &BacklogItem> {
+        self.items.iter().filter(|item| item.seconds.is_none())
+    }
+
+    /// Plans when every game with a time would be played, in the order of the backlog
+    ///
+    /// Export the plan as a calendar with [`export_ical`](crate::export::ical::export_ical).
+    ///
+    /// # Arguments
+    ///
+    /// * `weekly_hours`:  f32 - How many hours are played per week
+    /// * `start`:  SystemTime - When the plan starts, e.g. `SystemTime::now()`
+    ///
+    /// returns: Result<Vec<ScheduledGame>, Box<dyn Error, Global>> - An error if the budget
+    /// isn't positive
+    pub fn plan(
+        &self,
+        weekly_hours: f32,
+        start: SystemTime,
+    ) -> Result<Vec<ScheduledGame>, Box<dyn Error>> {
+        let games = self
+            .items
+            .iter()
+            .filter_map(|item| Some((&item.game, item.seconds?)));
+        plan_backlog(games, weekly_hours, start)
+    }
+}
+
+impl HltbClient {
+    /// Resolves every game of a backlog and adds up their average times in a play style
+    ///
+    /// Entries are looked up one after the other, like [`HltbClient::search_batch`].
+    ///
+    /// # Arguments
+    ///
+    /// * `entries`:  &[BacklogEntry] - The games, by name or ID
+    /// * `style`:  PlayStyle - The play style to add up
+    ///
+    /// returns: BacklogReport
+    pub async fn estimate_backlog(
+        &self,
+        entries: &[BacklogEntry],
+        style: PlayStyle,
+    ) -> BacklogReport {
+        let mut results = Vec::new();
+        for entry in entries {
+            let result = match entry {
+                BacklogEntry::Name(name) => self.search_by_name(name).await,
+                BacklogEntry::Id(hltb_id) => self.search_by_id(*hltb_id).await,
+            };
+            results.push((entry.clone(), result));
+        }
+        BacklogReport::new(style, results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Styles;
+
+    fn game(title: &str, hltb_id: u32, main_story: Option<f32>) -> Game {
+        Game::new(
+            title.to_string(),
+            hltb_id,
+            main_story.map(|average| Styles::new(Some(average), None, None, None)),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_backlog_report() {
+        let report = BacklogReport::new(
+            PlayStyle::MainStory,
+            vec![
+                (
+                    "Metal Gear".into(),
+                    Ok(game("Metal Gear", 5900, Some(15000.0))),
+                ),
+                (
+                    129232.into(),
+                    Ok(game("Helldivers 2", 129232, Some(21000.0))),
+                ),
+                (1.into(), Ok(game("Minecraft", 1, None))),
+                ("Unknown".into(), Err("No search result".into())),
+            ],
+        );
+        assert_eq!(report.total_hours(), 10.0);
+        assert_eq!(report.items[1].seconds, Some(21000.0));
+        assert_eq!(report.without_time().count(), 1);
+        assert_eq!(
+            report.unresolved,
+            vec![(
+                BacklogEntry::from("Unknown"),
+                "No search result".to_string()
+            )]
+        );
+    }
+}
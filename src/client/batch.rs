@@ -0,0 +1,400 @@
+//! Batch and crawl lookups with progress reporting.
+//!
+//! Every entry of a batch goes through the client one after the other, so the
+//! rate limit, retries and cache apply as for single lookups. A [`BatchProgress`]
+//! observer is told when each entry starts, resolves, fails or is retried, which
+//! is enough to drive a progress bar or a live log. [`HltbClient::resolve_batch`]
+//! also sorts the names into a [`BatchReport`] of matched, ambiguous and unmatched
+//! titles, so the doubtful ones can be fixed by hand.
+
+use super::{Call, HltbClient, TabSlot};
+use crate::export::jsonl::{exported_ids, JsonlExporter};
+use crate::parse::title_similarity;
+use crate::query::{MatchInfo, ResolvedGame, SearchQuery};
+use crate::{CancellationToken, Game};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::Path;
+
+/// How many rejected candidates are kept for an ambiguous title
+const REJECTED_CANDIDATES: usize = 3;
+
+/// A result of a search that wasn't picked, with how similar its title is to the name
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Candidate {
+    pub hltb_id: u32,
+    pub title: String,
+    /// How similar the title is to the searched name, from 0.0 to 1.0
+    pub similarity: f32,
+}
+
+/// A name resolved to a game with low confidence, see [`MatchInfo::is_low_confidence`]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AmbiguousTitle {
+    /// The position of the name in the batch
+    pub index: usize,
+    pub resolved: ResolvedGame,
+    /// The other results of the search with the most similar titles, most similar first
+    pub rejected: Vec<Candidate>,
+}
+
+/// A name that couldn't be resolved
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct UnmatchedTitle {
+    /// The position of the name in the batch
+    pub index: usize,
+    pub query: String,
+    /// Why the name couldn't be resolved
+    pub error: String,
+}
+
+/// The names of a batch sorted by how well they resolved, each list in the order of the batch
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+pub struct BatchReport {
+    /// The names resolved with confidence, with their position in the batch
+    pub matched: Vec<(usize, ResolvedGame)>,
+    /// The names resolved with low confidence, to review
+    pub ambiguous: Vec<AmbiguousTitle>,
+    /// The names that couldn't be resolved, to fix
+    pub unmatched: Vec<UnmatchedTitle>,
+}
+
+/// Receives the progress of a batch or crawl
+///
+/// Every method does nothing by default, so implementations only override the events they
+/// need. `index` is the position of the entry in the batch and `query` the name or ID
+/// being looked up.
+#[allow(unused_variables)]
+pub trait BatchProgress: Sync {
+    /// Called before an entry is looked up
+    fn on_started(&self, index: usize, query: &str) {}
+
+    /// Called when an entry is resolved to a game
+    fn on_resolved(&self, index: usize, query: &str, game: &Game) {}
+
+    /// Called when an entry can't be resolved, after its last retry
+    fn on_failed(&self, index: usize, query: &str, error: &str) {}
+
+    /// Called before a failed request of an entry is retried, with the number of the retry
+    fn on_retry(&self, index: usize, query: &str, attempt: u32, error: &str) {}
+}
+
+/// Ignores the progress
+impl BatchProgress for () {}
+
+impl HltbClient {
+    /// Searches several games by name, one after the other
+    ///
+    /// # Arguments
+    ///
+    /// * `names`:  &[&str] - The names of the games to search for
+    /// * `progress`:  &dyn BatchProgress - Notified of the progress of every name
+    ///
+    /// returns: Vec<Result<Game, Box<dyn Error, Global>>> - The result of every name, in order
+    pub async fn search_batch(
+        &self,
+        names: &[&str],
+        progress: &dyn BatchProgress,
+    ) -> Vec<Result<Game, Box<dyn Error>>> {
+        let cancel = CancellationToken::new();
+        let mut results = Vec::new();
+        for (index, name) in names.iter().enumerate() {
+            progress.on_started(index, name);
+            let on_retry =
+                |attempt: u32, error: &str| progress.on_retry(index, name, attempt, error);
+            let call = Call {
+                on_retry: Some(&on_retry),
+                ..Call::new(&self.config, &cancel)
+            };
+            let result = self.search_by_name_with(name, call).await;
+            match &result {
+                Ok(game) => progress.on_resolved(index, name, game),
+                Err(e) => progress.on_failed(index, name, &e.to_string()),
+            }
+            results.push(result);
+        }
+        results
+    }
+
+    /// Searches several games by name, one after the other, and sorts the names by how well
+    /// they resolved
+    ///
+    /// Ambiguous names come with the rejected results whose titles are the most similar to
+    /// them, so the right game can be picked by hand.
+    ///
+    /// # Arguments
+    ///
+    /// * `names`:  &[&str] - The names of the games to search for
+    /// * `progress`:  &dyn BatchProgress - Notified of the progress of every name
+    ///
+    /// returns: BatchReport
+    pub async fn resolve_batch(&self, names: &[&str], progress: &dyn BatchProgress) -> BatchReport {
+        let cancel = CancellationToken::new();
+        let mut report = BatchReport::default();
+        for (index, name) in names.iter().enumerate() {
+            progress.on_started(index, name);
+            let on_retry =
+                |attempt: u32, error: &str| progress.on_retry(index, name, attempt, error);
+            let tab = TabSlot::default();
+            let call = Call {
+                on_retry: Some(&on_retry),
+                ..Call::new(&self.config, &cancel)
+            }
+            .sharing_tab(&tab);
+            let found = match self.find_with(&SearchQuery::new(name), call).await {
+                Ok(found) => found,
+                Err(e) => {
+                    progress.on_failed(index, name, &e.to_string());
+                    report.unmatched.push(UnmatchedTitle {
+                        index,
+                        query: name.to_string(),
+                        error: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+            let mut game = match self.search_by_id_with(found.hltb_id, call).await {
+                Ok(game) => game,
+                Err(e) => {
+                    progress.on_failed(index, name, &e.to_string());
+                    report.unmatched.push(UnmatchedTitle {
+                        index,
+                        query: name.to_string(),
+                        error: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+            game.search_url = found.search_url;
+            progress.on_resolved(index, name, &game);
+            let mut info = MatchInfo::new(name, &game, found.results.len());
+            info.variant = found.variant;
+            info.overridden = found.overridden;
+            let resolved = ResolvedGame { game, info };
+            if !resolved.info.is_low_confidence() {
+                report.matched.push((index, resolved));
+                continue;
+            }
+            let mut rejected: Vec<Candidate> = found
+                .results
+                .into_iter()
+                .filter(|result| result.hltb_id != resolved.game.hltb_id)
+                .map(|result| Candidate {
+                    similarity: title_similarity(name, &result.title),
+                    hltb_id: result.hltb_id,
+                    title: result.title,
+                })
+                .collect();
+            rejected.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+            rejected.truncate(REJECTED_CANDIDATES);
+            report.ambiguous.push(AmbiguousTitle {
+                index,
+                resolved,
+                rejected,
+            });
+        }
+        report
+    }
+
+    /// Fetches games by ID and appends them to a JSON Lines file, resuming where a previous
+    /// crawl of the same file stopped
+    ///
+    /// IDs already in the file are skipped without being reported. Games that can't be
+    /// fetched are reported as failed and left out of the file, so the next crawl retries them.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids`:  &[u32] - The IDs of the games on How Long to Beat
+    /// * `path`:  &Path - The `.jsonl` file to append to
+    /// * `progress`:  &dyn BatchProgress - Notified of the progress of every ID
+    ///
+    /// returns: Result<usize, Box<dyn Error, Global>> - The number of games written
+    pub async fn crawl(
+        &self,
+        ids: &[u32],
+        path: &Path,
+        progress: &dyn BatchProgress,
+    ) -> Result<usize, Box<dyn Error>> {
+        let done = exported_ids(path)?;
+        let mut exporter = JsonlExporter::open(path)?;
+        let cancel = CancellationToken::new();
+        let mut written = 0;
+        for (index, hltb_id) in ids.iter().enumerate() {
+            if done.contains(hltb_id) {
+                continue;
+            }
+            let query = hltb_id.to_string();
+            progress.on_started(index, &query);
+            let on_retry =
+                |attempt: u32, error: &str| progress.on_retry(index, &query, attempt, error);
+            let call = Call {
+                on_retry: Some(&on_retry),
+                ..Call::new(&self.config, &cancel)
+            };
+            match self.search_by_id_with(*hltb_id, call).await {
+                Ok(game) => {
+                    exporter.write(&game)?;
+                    written += 1;
+                    progress.on_resolved(index, &query, &game);
+                }
+                Err(e) => progress.on_failed(index, &query, &e.to_string()),
+            }
+        }
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Log(Mutex<Vec<String>>);
+
+    impl BatchProgress for Log {
+        fn on_started(&self, index: usize, query: &str) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("started {} {}", index, query));
+        }
+
+        fn on_failed(&self, index: usize, query: &str, error: &str) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("failed {} {}: {}", index, query, error));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_crawl_skips_exported_ids() {
+        let path = std::env::temp_dir().join("hltb_crawl_test.jsonl");
+        let _ = std::fs::remove_file(&path);
+        let mut exporter = JsonlExporter::open(&path).unwrap();
+        exporter
+            .write(&Game::new(
+                "Metal Gear".to_string(),
+                5900,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ))
+            .unwrap();
+
+        let log = Log::default();
+        let client = HltbClient::builder().build().unwrap();
+        let written = client.crawl(&[5900], &path, &log).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written, 0);
+        assert!(log.0.lock().unwrap().is_empty());
+    }
+
+    #[cfg(feature = "test-util")]
+    #[tokio::test]
+    async fn test_resolve_batch() {
+        let game = |title: &str, hltb_id: u32| {
+            Game::new(
+                title.to_string(),
+                hltb_id,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+        };
+        let client = HltbClient::builder()
+            .mock(
+                crate::MockBackend::new()
+                    .with_game(game("Metal Gear", 5900))
+                    .with_game(game("Metal Gear 2: Solid Snake", 5901))
+                    .with_game(game("Metal Gear Solid", 5903))
+                    .with_game(game("Contra", 2065)),
+            )
+            .build()
+            .unwrap();
+        let report = client
+            .resolve_batch(&["Contra", "Metal Gea", "Zelda"], &())
+            .await;
+        assert_eq!(report.matched.len(), 1);
+        assert_eq!(report.matched[0].0, 0);
+        assert_eq!(report.ambiguous.len(), 1);
+        let ambiguous = &report.ambiguous[0];
+        assert_eq!(ambiguous.index, 1);
+        assert_eq!(ambiguous.resolved.game.hltb_id, 5900);
+        assert_eq!(
+            ambiguous
+                .rejected
+                .iter()
+                .map(|candidate| candidate.hltb_id)
+                .collect::<Vec<_>>(),
+            [5903, 5901]
+        );
+        assert_eq!(report.unmatched.len(), 1);
+        assert_eq!(report.unmatched[0].query, "Zelda");
+    }
+
+    #[cfg(feature = "test-util")]
+    #[tokio::test]
+    async fn test_resolve_batch_with_overrides() {
+        let game = |title: &str, hltb_id: u32| {
+            Game::new(
+                title.to_string(),
+                hltb_id,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+        };
+        let mut overrides = crate::overrides::TitleOverrides::new();
+        overrides.insert("Metal Gea", 5903);
+        overrides.insert("MGS2", 5904);
+        let client = HltbClient::builder()
+            .mock(
+                crate::MockBackend::new()
+                    .with_game(game("Metal Gear", 5900))
+                    .with_game(game("Metal Gear Solid", 5903))
+                    .with_game(game("Metal Gear Solid 2: Sons of Liberty", 5904)),
+            )
+            .overrides(overrides)
+            .build()
+            .unwrap();
+        let report = client.resolve_batch(&["Metal Gea", "mgs2"], &()).await;
+        assert!(report.ambiguous.is_empty() && report.unmatched.is_empty());
+        let ids: Vec<u32> = report
+            .matched
+            .iter()
+            .map(|(_, resolved)| resolved.game.hltb_id)
+            .collect();
+        assert_eq!(ids, [5903, 5904]);
+        assert!(report.matched[1].1.info.overridden);
+    }
+
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn test_search_batch_reports_failures() {
+        let log = Log::default();
+        let client = HltbClient::builder()
+            .backend(crate::Backend::Http)
+            .build()
+            .unwrap();
+        let results = client.search_batch(&["Metal Gear"], &log).await;
+        assert!(results[0].is_err());
+        assert_eq!(
+            *log.0.lock().unwrap(),
+            [
+                "started 0 Metal Gear",
+                "failed 0 Metal Gear: Searching by name requires the browser backend"
+            ]
+        );
+    }
+}
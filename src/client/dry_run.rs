@@ -0,0 +1,108 @@
+//! Dry runs of lookups.
+//!
+//! A client built with [`HltbClientBuilder::dry_run`](super::HltbClientBuilder::dry_run)
+//! serves what it can from its cache, overrides and cassette, and plans the
+//! requests to the site instead of making them: the lookups needing one fail with
+//! [`HltbError::DryRun`](crate::HltbError::DryRun), and
+//! [`HltbClient::planned_requests`] lists the pages they would have fetched, so
+//! the cost of a large batch can be estimated before running it.
+
+use super::{HltbClient, PageKind};
+
+/// A request a dry run would have made
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedRequest {
+    pub url: String,
+    pub page: PageKind,
+}
+
+impl HltbClient {
+    /// Records a request a dry run would have made
+    ///
+    /// # Arguments
+    ///
+    /// * `url`:  &str - The URL of the page
+    /// * `page`:  PageKind - The kind of the page
+    pub(super) fn plan(&self, url: &str, page: PageKind) {
+        self.planned.lock().unwrap().push(PlannedRequest {
+            url: url.to_string(),
+            page,
+        });
+    }
+
+    /// Returns the requests the lookups of a dry run would have made so far, in order
+    ///
+    /// returns: Vec<PlannedRequest>
+    pub fn planned_requests(&self) -> Vec<PlannedRequest> {
+        self.planned.lock().unwrap().clone()
+    }
+
+    /// Estimates how many requests the lookups of a dry run would have made so far
+    ///
+    /// A search by name that wasn't cached is followed by the details page of the game it
+    /// finds, unknown until the search is made, which is counted too unless that game is
+    /// cached by then.
+    ///
+    /// returns: usize
+    pub fn estimated_requests(&self) -> usize {
+        let planned = self.planned.lock().unwrap();
+        let searches = planned
+            .iter()
+            .filter(|request| request.page == PageKind::Search)
+            .count();
+        planned.len() + searches
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::*;
+    use crate::cache::{CachedQuery, DiskCache};
+    use crate::{Game, HltbError, MockBackend};
+
+    #[tokio::test]
+    async fn test_dry_run() {
+        let dir = std::env::temp_dir().join("hltb_dry_run_test");
+        let game = |title: &str, hltb_id: u32| {
+            Game::new(
+                title.to_string(),
+                hltb_id,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+        };
+        let cache = DiskCache::new(&dir);
+        cache.put(&game("Hollow Knight", 26286)).unwrap();
+        cache
+            .put_query("Hollow Knight", &CachedQuery::new(26286, Vec::new(), None))
+            .unwrap();
+        let client = HltbClient::builder()
+            .mock(MockBackend::new().with_game(game("Contra", 2065)))
+            .cache_dir(&dir)
+            .dry_run(true)
+            .build()
+            .unwrap();
+        let report = client
+            .resolve_batch(&["hollow knight", "Contra"], &())
+            .await;
+        let error = client.search_by_id(5900).await.unwrap_err();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(report.matched.len(), 1);
+        assert_eq!(report.unmatched.len(), 1);
+        assert!(matches!(
+            error.downcast_ref::<HltbError>(),
+            Some(HltbError::DryRun(_))
+        ));
+        let planned = client.planned_requests();
+        assert_eq!(
+            planned.iter().map(|r| r.page).collect::<Vec<_>>(),
+            [PageKind::Search, PageKind::Details]
+        );
+        assert_eq!(planned[1].url, format!("{}game/5900", crate::BASE_URL));
+        assert_eq!(client.estimated_requests(), 3);
+    }
+}
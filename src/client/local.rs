@@ -0,0 +1,107 @@
+//! Resolution of the titles of local libraries.
+//!
+//! [`HltbClient::resolve_local_title`] cleans a folder, executable or shortcut
+//! name up with [`LocalTitle::parse`], picks the result with the most similar
+//! title rather than the first one, and checks the year written in the name, if
+//! any, against the release year of the game.
+
+use super::HltbClient;
+use crate::local::LocalTitle;
+use crate::parse::title_similarity;
+use crate::query::{MatchInfo, MatchStrategy, ResolvedGame, SearchQuery};
+use crate::HltbError;
+use std::error::Error;
+
+/// How many other results are looked at when the picked game isn't from the year of the name
+const YEAR_CANDIDATES: usize = 3;
+
+impl HltbClient {
+    /// Finds the game of a local name: a folder, an executable or a shortcut of a scanned
+    /// library
+    ///
+    /// When the name carries a year and the game with the most similar title wasn't released
+    /// within a year of it, the next most similar results are tried, and the first one
+    /// released then is preferred, e.g. "DOOM (1993)" rather than the game of 2016.
+    ///
+    /// # Arguments
+    ///
+    /// * `raw`:  &str - The local name, e.g. "Hollow_Knight_v1.5.78 [GOG]"
+    ///
+    /// returns: Result<ResolvedGame, Box<dyn Error, Global>>
+    pub async fn resolve_local_title(&self, raw: &str) -> Result<ResolvedGame, Box<dyn Error>> {
+        let local = LocalTitle::parse(raw);
+        if local.name.is_empty() {
+            return Err(HltbError::NoMatch(raw.to_string()).into());
+        }
+        let query = SearchQuery::new(&local.name).strategy(MatchStrategy::BestTitle);
+        let resolved = self.search(query.clone()).send_with_match().await?;
+        let Some(year) = local.year else {
+            return Ok(resolved);
+        };
+        let from_year =
+            |release_year: Option<i32>| release_year.is_some_and(|y| (y - year).abs() <= 1);
+        if from_year(resolved.game.release_year) {
+            return Ok(resolved);
+        }
+        let mut results = self.search_results(query).await?;
+        let candidates = results.len();
+        results.retain(|result| result.hltb_id != resolved.game.hltb_id);
+        results.sort_by(|a, b| {
+            title_similarity(&local.name, &b.title)
+                .total_cmp(&title_similarity(&local.name, &a.title))
+        });
+        for result in results.iter().take(YEAR_CANDIDATES) {
+            let Ok(game) = self.search_by_id(result.hltb_id).await else {
+                continue;
+            };
+            if from_year(game.release_year) {
+                let info = MatchInfo::new(&local.name, &game, candidates);
+                return Ok(ResolvedGame { game, info });
+            }
+        }
+        Ok(resolved)
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::*;
+    use crate::client::MockBackend;
+    use crate::{Game, Styles};
+
+    fn doom(hltb_id: u32, release_year: i32) -> Game {
+        let mut game = Game::new(
+            "DOOM".to_string(),
+            hltb_id,
+            Some(Styles::new(Some(30000.0), None, None, None)),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        game.release_year = Some(release_year);
+        game
+    }
+
+    #[tokio::test]
+    async fn test_resolve_local_title() {
+        let client = HltbClient::builder()
+            .mock(
+                MockBackend::new()
+                    .with_game(doom(2708, 2016))
+                    .with_game(doom(2707, 1993)),
+            )
+            .build()
+            .unwrap();
+        let resolved = client.resolve_local_title("DOOM (1993)").await.unwrap();
+        assert_eq!(resolved.game.hltb_id, 2707);
+        assert_eq!(resolved.info.candidates, 2);
+        let resolved = client
+            .resolve_local_title("C:\\Games\\DOOM\\DOOM_x64.exe")
+            .await
+            .unwrap();
+        assert_eq!(resolved.game.hltb_id, 2708);
+        assert!(client.resolve_local_title("Setup.exe").await.is_err());
+    }
+}
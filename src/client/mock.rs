@@ -0,0 +1,309 @@
+//! In-memory backend for tests (`test-util` feature).
+//!
+//! [`MockBackend`] answers the requests of a client from canned pages instead of
+//! the site, so applications built on this crate can test their integration
+//! without network access or Chrome:
+//!
+//! ```ignore
+//! let client = HltbClient::builder()
+//!     .mock(MockBackend::new().with_game(game))
+//!     .build()?;
+//! ```
+//!
+//! Games registered with [`MockBackend::with_game`] are rendered to pages with
+//! the structure the parsers expect, so they round-trip through the real
+//! parsing code; pages captured from the site can be served as-is with
+//! [`MockBackend::with_page`].
+
+use crate::parse::{normalize_title, SearchResult};
+use crate::{Game, Platform, Styles};
+use std::collections::HashMap;
+use std::error::Error;
+use urlencoding::{decode, encode};
+
+#[derive(Debug, Clone, Default)]
+pub struct MockBackend {
+    games: Vec<Game>,
+    /// Raw pages by path relative to the base URL, e.g. "game/5900"
+    pages: HashMap<String, String>,
+}
+
+impl MockBackend {
+    /// Creates a backend without any page, which fails every request
+    ///
+    /// returns: MockBackend
+    pub fn new() -> MockBackend {
+        MockBackend::default()
+    }
+
+    /// Serves the details page of a game, and lists it in the searches its title contains
+    ///
+    /// Times are rounded to the minute, as on the site.
+    ///
+    /// # Arguments
+    ///
+    /// * `game`:  Game - The game to serve
+    ///
+    /// returns: MockBackend
+    pub fn with_game(mut self, game: Game) -> MockBackend {
+        self.games.push(game);
+        self
+    }
+
+    /// Serves a raw page, taking precedence over the pages rendered for games
+    ///
+    /// # Arguments
+    ///
+    /// * `path`:  &str - The path relative to the base URL: "game/<id>" for a details page
+    ///   or "?q=<name>" for a search page, with the name URL-encoded
+    /// * `html`:  &str - The HTML of the page
+    ///
+    /// returns: MockBackend
+    pub fn with_page(mut self, path: &str, html: &str) -> MockBackend {
+        self.pages.insert(path.to_string(), html.to_string());
+        self
+    }
+
+    /// Answers a request
+    ///
+    /// # Arguments
+    ///
+    /// * `path`:  &str - The path of the request relative to the base URL
+    ///
+    /// returns: Result<String, Box<dyn Error, Global>>
+    pub(super) fn respond(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        if let Some(html) = self.pages.get(path) {
+            return Ok(html.clone());
+        }
+        if let Some(query) = path.strip_prefix("?q=") {
+            let query = normalize_title(&decode(query)?);
+            let results: Vec<SearchResult> = self
+                .games
+                .iter()
+                .filter(|game| normalize_title(&game.title).contains(&query))
+                .map(|game| SearchResult {
+                    hltb_id: game.hltb_id,
+                    title: game.title.clone(),
+                    search_url: None,
+                })
+                .collect();
+            return Ok(render_search(&results));
+        }
+        path.strip_prefix("game/")
+            .and_then(|id| id.parse::<u32>().ok())
+            .and_then(|id| self.games.iter().find(|game| game.hltb_id == id))
+            .map(render_details)
+            .ok_or_else(|| format!("No mock response for {}", path).into())
+    }
+}
+
+/// Renders a search page listing results
+///
+/// # Arguments
+///
+/// * `results`:  &[SearchResult] - The results, in order
+///
+/// returns: String
+pub fn render_search(results: &[SearchResult]) -> String {
+    let items: String = results
+        .iter()
+        .map(|result| {
+            let title = escape(&result.title);
+            format!(
+                "<li><div><div class=\"GameCard_search_list_image__mock\"><a href=\"/game/{id}\" \
+                 title=\"{title}\"></a></div><div><h2><a href=\"/game/{id}\">{title}</a></h2>\
+                 </div></div></li>",
+                id = result.hltb_id,
+                title = title
+            )
+        })
+        .collect();
+    format!(
+        "<html><body><div id=\"search-results-header\"><ul>{}</ul></div></body></html>",
+        items
+    )
+}
+
+/// Renders the details page of a game
+///
+/// # Arguments
+///
+/// * `game`:  &Game - The game
+///
+/// returns: String
+pub fn render_details(game: &Game) -> String {
+    let mut info = String::new();
+    let mut push_info = |label: &str, value: String| {
+        info.push_str(&format!(
+            "<div class=\"GameSummary_profile_info__mock\">{}: {}</div>",
+            label,
+            escape(&value)
+        ))
+    };
+    if !game.platforms.is_empty() {
+        push_info(
+            "Platforms",
+            game.platforms
+                .iter()
+                .map(Platform::name)
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+    }
+    if !game.aliases.is_empty() {
+        push_info("Aliases", game.aliases.join(", "));
+    }
+    if let Some(year) = game.release_year {
+        push_info("NA", year.to_string());
+    }
+    let rows: String = [
+        ("Main Story", &game.main_story),
+        ("Main + Extra", &game.main_extra),
+        ("Completionist", &game.completionist),
+        ("All PlayStyles", &game.all_styles),
+        ("Co-Op", &game.co_op),
+        ("Competitive", &game.vs),
+    ]
+    .iter()
+    .filter_map(|(label, styles)| styles.as_ref().map(|styles| render_row(label, styles)))
+    .collect();
+    let labels: String = game
+        .focus
+        .iter()
+        .map(|focus| format!("<span>{}</span>", focus.label()))
+        .collect();
+    format!(
+        "<html><body><div id=\"__next\"><div><main><div><div><div><div>\
+         <div class=\"GameHeader_profile_header__mock\">{title}{labels}</div></div></div></div></div>\
+         <div><div><div class=\"GameSummary_content__mock\">{info}\
+         <div class=\"in scrollable scroll_blue shadow_box back_primary\">\
+         <table class=\"GameTimeTable_game_main_table__mock\"><tbody>{rows}</tbody></table>\
+         </div></div></div></div></main></div></div></body></html>",
+        title = escape(&game.title),
+        labels = labels,
+        info = info,
+        rows = rows
+    )
+}
+
+fn render_row(label: &str, styles: &Styles) -> String {
+    let cells: String = [styles.average, styles.median, styles.rushed, styles.leisure]
+        .iter()
+        .map(|time| format!("<td>{}</td>", render_time(*time)))
+        .collect();
+    format!("<tr><td>{}</td><td>--</td>{}</tr>", label, cells)
+}
+
+/// Writes a time the way the site does, e.g. "4h 10m", or "--" when there is none
+fn render_time(seconds: Option<f32>) -> String {
+    let minutes = seconds.map_or(0, |s| (s / 60.0).round() as u64);
+    match (minutes / 60, minutes % 60) {
+        (0, 0) => "--".to_string(),
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h {}m", h, m),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Returns the path of the search page of a name, for [`MockBackend::with_page`]
+///
+/// # Arguments
+///
+/// * `name`:  &str - The name of the game to search for
+///
+/// returns: String
+pub fn search_path(name: &str) -> String {
+    format!("?q={}", encode(name.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{self, ParseMode};
+    use crate::{Focus, HltbClient};
+
+    fn metal_gear() -> Game {
+        let mut game = Game::new(
+            "Metal Gear & Co".to_string(),
+            5900,
+            Some(Styles::new(Some(15000.0), Some(14400.0), None, Some(600.0))),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        game.platforms = vec![Platform::Nes, Platform::Msx2];
+        game.release_year = Some(1987);
+        game.aliases = vec!["メタルギア".to_string()];
+        game.focus = vec![Focus::SinglePlayer];
+        game
+    }
+
+    #[test]
+    fn test_render_details_round_trip() {
+        let game = metal_gear();
+        let html = render_details(&game);
+        let parsed = parse::game_with_mode(&html, 5900, ParseMode::Strict).unwrap();
+        assert!(parsed.data_eq(&game), "{:?} != {:?}", parsed, game);
+    }
+
+    #[tokio::test]
+    async fn test_mock_client() {
+        let client = HltbClient::builder()
+            .mock(MockBackend::new().with_game(metal_gear()))
+            .build()
+            .unwrap();
+        let mut game = client.search("metal gear").await.unwrap();
+        assert!(game.fetched_at.take().is_some());
+        assert_eq!(
+            game.search_url.take(),
+            Some(client.search_url("metal gear"))
+        );
+        assert!(game.data_eq(&metal_gear()), "{:?}", game);
+        let mut game = client.search_by_id(5900).await.unwrap();
+        game.fetched_at = None;
+        assert!(game.data_eq(&metal_gear()), "{:?}", game);
+        let resolved = client.search("metal gear").send_with_match().await.unwrap();
+        assert_eq!(resolved.info.candidates, 1);
+        assert!(resolved.info.is_low_confidence());
+        assert!(client.search("Zelda").await.is_err());
+
+        let client = HltbClient::builder()
+            .mock(MockBackend::new().with_page(&search_path("Zelda"), &render_search(&[])))
+            .build()
+            .unwrap();
+        assert!(client.search_results("Zelda").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_spelling_retries() {
+        let suggestion = "<html><body><p>Did you mean <a>Metal Gear</a>?</p></body></html>";
+        let client = HltbClient::builder()
+            .mock(
+                MockBackend::new()
+                    .with_game(metal_gear())
+                    .with_page(&search_path("Metal Gar"), suggestion),
+            )
+            .build()
+            .unwrap();
+        let resolved = client.search("Metal Gar").send_with_match().await.unwrap();
+        assert_eq!(resolved.game.hltb_id, 5900);
+        assert_eq!(resolved.info.variant.as_deref(), Some("Metal Gear"));
+        let resolved = client
+            .search("Metal Gear and Co")
+            .send_with_match()
+            .await
+            .unwrap();
+        assert_eq!(resolved.info.variant.as_deref(), Some("Metal Gear & Co"));
+        let resolved = client.search("Metal Gear").send_with_match().await.unwrap();
+        assert_eq!(resolved.info.variant, None);
+    }
+}
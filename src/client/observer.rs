@@ -0,0 +1,87 @@
+//! Hooks into the requests of a client.
+//!
+//! An [`Observer`] registered with [`HltbClientBuilder::observer`](super::HltbClientBuilder::observer)
+//! is told about every request, retry, cache hit, redirect and parse error of the client,
+//! so applications can feed their own metrics, logging or tracing without the
+//! crate depending on a telemetry stack.
+
+use std::time::Duration;
+
+/// Receives the events of a client
+///
+/// Every method does nothing by default, so implementations only override the events they
+/// need. Events are delivered synchronously on the task of the lookup, so implementations
+/// should return quickly.
+#[allow(unused_variables)]
+pub trait Observer: Send + Sync {
+    /// Called before a page is requested, for every attempt
+    fn on_request_start(&self, url: &str) {}
+
+    /// Called when a request ends, with how long it took and its error if it failed
+    fn on_request_end(&self, url: &str, elapsed: Duration, error: Option<&str>) {}
+
+    /// Called when a game is served from the cache instead of the site
+    fn on_cache_hit(&self, hltb_id: u32) {}
+
+    /// Called before a failed request is retried, with the number of the retry
+    fn on_retry(&self, url: &str, attempt: u32, error: &str) {}
+
+    /// Called when a fetched page can't be parsed
+    fn on_parse_error(&self, url: &str, error: &str) {}
+
+    /// Called when the site served the page of another game than the requested one, e.g.
+    /// because duplicate entries were merged, so stored IDs can be updated
+    fn on_redirect(&self, from: u32, to: u32) {}
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::*;
+    use crate::client::MockBackend;
+    use crate::HltbClient;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Events(Mutex<Vec<String>>);
+
+    impl Observer for Arc<Events> {
+        fn on_request_start(&self, url: &str) {
+            self.0.lock().unwrap().push(format!("start {}", url));
+        }
+
+        fn on_request_end(&self, url: &str, _elapsed: Duration, error: Option<&str>) {
+            let outcome = if error.is_some() { "failed" } else { "ok" };
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("end {} {}", url, outcome));
+        }
+
+        fn on_parse_error(&self, url: &str, _error: &str) {
+            self.0.lock().unwrap().push(format!("parse error {}", url));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_observer() {
+        let events = Arc::new(Events::default());
+        let client = HltbClient::builder()
+            .base_url("http://hltb.test")
+            .mock(MockBackend::new().with_page("game/1", "<html></html>"))
+            .observer(events.clone())
+            .build()
+            .unwrap();
+        assert!(client.search_by_id(1).await.is_err());
+        assert!(client.search_by_id(2).await.is_err());
+        assert_eq!(
+            *events.0.lock().unwrap(),
+            [
+                "start http://hltb.test/game/1",
+                "end http://hltb.test/game/1 ok",
+                "parse error http://hltb.test/game/1",
+                "start http://hltb.test/game/2",
+                "end http://hltb.test/game/2 failed",
+            ]
+        );
+    }
+}
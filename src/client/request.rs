@@ -0,0 +1,231 @@
+//! Per-call options of a lookup.
+//!
+//! [`SearchRequest`] starts from the configuration of its client and lets a
+//! single call change the timeout, retries or sandbox, filter on a platform or
+//! be cancelled, without affecting the other calls made with the client.
+
+use super::{Call, GamePage, HltbClient, TabSlot};
+use crate::parse::ParseMode;
+use crate::query::{MatchInfo, ResolvedGame, SearchQuery};
+use crate::timings::{TimedGame, Timings};
+use crate::{CancellationToken, Game, HltbError, Platform};
+use std::error::Error;
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// What a request looks up
+pub(super) enum Query {
+    Search(SearchQuery),
+    Id(u32),
+}
+
+pub struct SearchRequest<'a> {
+    client: &'a HltbClient,
+    query: Query,
+    config: super::ClientConfig,
+    platform: Option<Platform>,
+    cancel: CancellationToken,
+}
+
+impl<'a> SearchRequest<'a> {
+    pub(super) fn new(client: &'a HltbClient, query: Query) -> SearchRequest<'a> {
+        let platform = match &query {
+            Query::Search(search) => search.platform.clone(),
+            Query::Id(_) => None,
+        };
+        SearchRequest {
+            client,
+            query,
+            config: client.config.clone(),
+            platform,
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    /// Sets how long to wait for each page of this call
+    pub fn timeout(mut self, timeout: Duration) -> SearchRequest<'a> {
+        self.config.timeout = timeout;
+        self
+    }
+
+    /// Sets how many times a failed request of this call is retried
+    pub fn retries(mut self, retries: u32) -> SearchRequest<'a> {
+        self.config.retries = retries;
+        self
+    }
+
+    /// Sets whether to enable sandbox mode for the browser of this call
+    pub fn sandbox(mut self, sandbox: bool) -> SearchRequest<'a> {
+        self.config.sandbox = sandbox;
+        self
+    }
+
+    /// Sets how strictly the pages of this call are parsed
+    pub fn parse_mode(mut self, parse_mode: ParseMode) -> SearchRequest<'a> {
+        self.config.parse_mode = parse_mode;
+        self
+    }
+
+    /// Only accepts the game if it is available on a platform, or its name (e.g. "PlayStation 4")
+    pub fn platform(mut self, platform: impl Into<Platform>) -> SearchRequest<'a> {
+        self.platform = Some(platform.into());
+        self
+    }
+
+    /// Sets the token that cancels this call
+    pub fn cancel(mut self, cancel: &CancellationToken) -> SearchRequest<'a> {
+        self.cancel = cancel.clone();
+        self
+    }
+
+    /// Runs the lookup
+    ///
+    /// returns: Result<Game, Box<dyn Error, Global>>
+    pub async fn send(self) -> Result<Game, Box<dyn Error>> {
+        let call = Call::new(&self.config, &self.cancel);
+        let game = match &self.query {
+            Query::Search(search) => self.client.search_by_query_with(search, call).await?,
+            Query::Id(hltb_id) => self.client.search_by_id_with(*hltb_id, call).await?,
+        };
+        self.check_platform(&game)?;
+        Ok(game)
+    }
+
+    /// Runs the lookup and also returns the HTML of the details page
+    ///
+    /// The details page is always fetched, even when the game is cached, and the cache is
+    /// updated with it.
+    ///
+    /// returns: Result<GamePage, Box<dyn Error, Global>>
+    pub async fn send_with_html(self) -> Result<GamePage, Box<dyn Error>> {
+        let tab = TabSlot::default();
+        let call = Call::new(&self.config, &self.cancel).sharing_tab(&tab);
+        let (hltb_id, search_url) = match &self.query {
+            Query::Search(search) => {
+                let found = self.client.find_with(search, call).await?;
+                (found.hltb_id, found.search_url)
+            }
+            Query::Id(hltb_id) => (*hltb_id, None),
+        };
+        let mut page = self.client.fetch_page_with(hltb_id, call).await?;
+        self.client.store(&page.game)?;
+        page.game.search_url = search_url;
+        self.check_platform(&page.game)?;
+        Ok(page)
+    }
+
+    /// Runs the lookup and also measures how long its phases took, see [`Timings`]
+    ///
+    /// returns: Result<TimedGame, Box<dyn Error, Global>>
+    pub async fn send_with_timings(self) -> Result<TimedGame, Box<dyn Error>> {
+        let started = Instant::now();
+        let timings = Mutex::new(Timings::default());
+        let call = Call {
+            timings: Some(&timings),
+            ..Call::new(&self.config, &self.cancel)
+        };
+        let game = match &self.query {
+            Query::Search(search) => self.client.search_by_query_with(search, call).await?,
+            Query::Id(hltb_id) => self.client.search_by_id_with(*hltb_id, call).await?,
+        };
+        self.check_platform(&game)?;
+        let mut timings = timings.into_inner().unwrap();
+        timings.total = started.elapsed();
+        Ok(TimedGame { game, timings })
+    }
+
+    /// Runs a search by name and also describes how the name matched the game, so doubtful
+    /// matches can be reviewed
+    ///
+    /// returns: Result<ResolvedGame, Box<dyn Error, Global>> - An error for lookups by ID
+    pub async fn send_with_match(self) -> Result<ResolvedGame, Box<dyn Error>> {
+        let Query::Search(search) = &self.query else {
+            return Err(HltbError::Unsupported("Only searches by name have a match").into());
+        };
+        let tab = TabSlot::default();
+        let call = Call::new(&self.config, &self.cancel).sharing_tab(&tab);
+        let found = self.client.find_with(search, call).await?;
+        let mut game = self.client.search_by_id_with(found.hltb_id, call).await?;
+        game.search_url = found.search_url;
+        self.check_platform(&game)?;
+        let mut info = MatchInfo::new(&search.name, &game, found.results.len());
+        info.variant = found.variant;
+        info.overridden = found.overridden;
+        Ok(ResolvedGame { game, info })
+    }
+
+    fn check_platform(&self, game: &Game) -> Result<(), Box<dyn Error>> {
+        match &self.platform {
+            Some(platform) if !game.is_on_platform(platform) => Err(HltbError::NotOnPlatform {
+                title: game.title.clone(),
+                platform: platform.to_string(),
+            }
+            .into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<'a> IntoFuture for SearchRequest<'a> {
+    type Output = Result<Game, Box<dyn Error>>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + 'a>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.send())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_overrides_apply_to_one_call() {
+        let client = HltbClient::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let request = client
+            .game(5900)
+            .timeout(Duration::from_secs(5))
+            .retries(2)
+            .cancel(&cancel);
+        assert_eq!(request.config.timeout, Duration::from_secs(5));
+        assert_eq!(request.config.retries, 2);
+        assert_eq!(client.config().timeout, Duration::from_secs(30));
+        assert_eq!(request.await.unwrap_err().to_string(), "Lookup cancelled");
+    }
+
+    #[cfg(feature = "test-util")]
+    #[tokio::test]
+    async fn test_send_with_timings() {
+        let game = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let client = HltbClient::builder()
+            .mock(crate::MockBackend::new().with_game(game))
+            .build()
+            .unwrap();
+        let timed = client
+            .search("Metal Gear")
+            .send_with_timings()
+            .await
+            .unwrap();
+        assert_eq!(timed.game.hltb_id, 5900);
+        assert_eq!(timed.timings.requests, 2);
+        assert_eq!(timed.timings.launch, Duration::ZERO);
+        assert!(timed.timings.parse > Duration::ZERO);
+        assert!(timed.timings.total >= timed.timings.navigation + timed.timings.parse);
+    }
+}
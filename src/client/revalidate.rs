@@ -0,0 +1,119 @@
+//! Background refresh of stale cached games.
+//!
+//! With [`CachePolicy::StaleWhileRevalidate`](crate::cache::CachePolicy::StaleWhileRevalidate), a cached game past its soft TTL is
+//! served at once and its ID is queued. [`HltbClient::run_revalidation`], polled
+//! alongside the lookups of the application, refreshes the queued games as they
+//! come, so the next lookups are served fresh data without ever waiting for the
+//! site.
+
+use super::{Call, HltbClient};
+use crate::CancellationToken;
+
+impl HltbClient {
+    /// Queues a stale game to be refreshed, unless it already is
+    ///
+    /// # Arguments
+    ///
+    /// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+    pub(super) fn queue_stale(&self, hltb_id: u32) {
+        let mut stale = self.stale.lock().unwrap();
+        if !stale.contains(&hltb_id) {
+            stale.push(hltb_id);
+            self.stale_queued.notify_one();
+        }
+    }
+
+    /// Returns the IDs of the stale games served from the cache and waiting to be refreshed
+    ///
+    /// returns: Vec<u32>
+    pub fn stale_games(&self) -> Vec<u32> {
+        self.stale.lock().unwrap().clone()
+    }
+
+    /// Refreshes the stale games served from the cache so far, until the token is cancelled
+    ///
+    /// A game that can't be fetched keeps its cached entry, and is queued again the next time
+    /// it is served.
+    ///
+    /// # Arguments
+    ///
+    /// * `cancel`:  &CancellationToken - Cancels the refresh
+    ///
+    /// returns: usize - The number of refreshed games
+    pub async fn revalidate(&self, cancel: &CancellationToken) -> usize {
+        let mut refreshed = 0;
+        loop {
+            if cancel.is_cancelled() {
+                return refreshed;
+            }
+            let Some(hltb_id) = self.stale.lock().unwrap().first().copied() else {
+                return refreshed;
+            };
+            let result = self
+                .fetch_details_with(hltb_id, Call::new(&self.config, cancel))
+                .await
+                .and_then(|game| self.store(&game));
+            self.stale.lock().unwrap().retain(|id| *id != hltb_id);
+            if result.is_ok() {
+                refreshed += 1;
+            }
+        }
+    }
+
+    /// Refreshes the stale games as they are served from the cache, until the token is
+    /// cancelled
+    ///
+    /// Run it alongside the lookups, e.g. with `tokio::join!`, for cached games past their
+    /// soft TTL to be refreshed in the background, see [`CachePolicy::StaleWhileRevalidate`](crate::cache::CachePolicy::StaleWhileRevalidate).
+    ///
+    /// # Arguments
+    ///
+    /// * `cancel`:  &CancellationToken - Stops the refresh
+    pub async fn run_revalidation(&self, cancel: &CancellationToken) {
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                _ = self.stale_queued.notified() => {}
+            }
+            self.revalidate(cancel).await;
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::*;
+    use crate::cache::{CachePolicy, DiskCache};
+    use crate::Game;
+    use std::time::{Duration, SystemTime};
+
+    #[tokio::test]
+    async fn test_stale_while_revalidate() {
+        let dir = std::env::temp_dir().join("hltb_revalidate_test");
+        let game =
+            |title: &str| Game::new(title.to_string(), 5900, None, None, None, None, None, None);
+        let mut stale = game("Metal Gear (cached)");
+        stale.fetched_at = Some(SystemTime::now() - Duration::from_secs(7200));
+        DiskCache::new(&dir).put(&stale).unwrap();
+        let client = HltbClient::builder()
+            .mock(crate::MockBackend::new().with_game(game("Metal Gear")))
+            .cache_dir(&dir)
+            .cache_policy(CachePolicy::StaleWhileRevalidate {
+                soft_ttl: Duration::from_secs(3600),
+            })
+            .build()
+            .unwrap();
+        let cancel = CancellationToken::new();
+
+        let served = client.search_by_id(5900).await.unwrap();
+        let queued = client.stale_games();
+        let refreshed = client.revalidate(&cancel).await;
+        let fresh = client.search_by_id(5900).await.unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(served.title, "Metal Gear (cached)");
+        assert_eq!(queued, [5900]);
+        assert_eq!(refreshed, 1);
+        assert_eq!(fresh.title, "Metal Gear");
+        assert!(client.stale_games().is_empty());
+    }
+}
@@ -0,0 +1,356 @@
+//! Sequential lookups in a single browser tab.
+//!
+//! [`HltbClient::session`] launches one browser and keeps its tab on the site. The
+//! lookups of the returned [`HltbSession`] navigate within the page, typing in the
+//! search box and following the links to details pages, instead of launching a
+//! browser and loading the whole site for every page, which makes them much faster
+//! for interactive applications. Lookups take `&mut self`, so they run one after
+//! the other.
+//!
+//! A session shares the rate limit, the request slots and the observers of its
+//! client, but neither its cache nor its cassette.
+
+use super::{
+    host_slot, next_request_wait, ClientConfig, HltbClient, Observer, PageKind, WaitStrategy,
+};
+use crate::error::sendable;
+use crate::guard::BrowserGuard;
+use crate::parse::{self, SearchResult, DETAILS_TABLE_SELECTOR, SEARCH_HEADER_SELECTOR};
+use crate::{launch_tab_with_config, page_content, wait_for_page, Game, HltbError};
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime};
+use urlencoding::encode;
+
+/// The search box of the site
+const SEARCH_BOX_SELECTOR: &str = "input[type='search']";
+
+/// Flags the changes of the search results, so a search waits for its own results rather
+/// than those of the previous search
+const WATCH_RESULTS_SCRIPT: &str = "(() => {
+    window.__hltbResults = false;
+    if (window.__hltbObserver) window.__hltbObserver.disconnect();
+    window.__hltbObserver = new MutationObserver(records => {
+        if (records.some(r => {
+            const node = r.target.nodeType === 1 ? r.target : r.target.parentElement;
+            return node && (node.closest('#search-results-header')
+                || node.querySelector('#search-results-header'));
+        })) window.__hltbResults = true;
+    });
+    window.__hltbObserver.observe(document.body, {childList: true, subtree: true, characterData: true});
+})()";
+
+/// A browser tab kept on the site for sequential lookups, see [`HltbClient::session`]
+pub struct HltbSession {
+    tab: Option<Arc<BrowserGuard>>,
+    config: ClientConfig,
+    limits: Limits,
+}
+
+/// What the requests of a session share with the requests of its client
+#[derive(Clone)]
+struct Limits {
+    observers: Vec<Arc<dyn Observer>>,
+    /// The earliest time the next request may start, when rate limited
+    next_request: Arc<Mutex<Option<Instant>>>,
+}
+
+impl HltbClient {
+    /// Launches a browser on the site for a session of sequential lookups in a single tab
+    ///
+    /// The session uses the configuration of the client and shares its rate limit and
+    /// observers, but neither its cache nor its cassette.
+    ///
+    /// returns: Result<HltbSession, Box<dyn Error, Global>>
+    pub async fn session(&self) -> Result<HltbSession, Box<dyn Error>> {
+        let config = self.config.clone();
+        let limits = Limits {
+            observers: self.observers.clone(),
+            next_request: self.next_request.clone(),
+        };
+        let url = config.base_url.clone();
+        let launch = config.clone();
+        let tab = request(&limits, &config, &url, move || {
+            let tab = launch_tab_with_config(&launch)?;
+            tab.navigate_to(&launch.base_url)?.wait_until_navigated()?;
+            tab.wait_for_element(SEARCH_BOX_SELECTOR)?;
+            Ok(tab)
+        })
+        .await?;
+        Ok(HltbSession {
+            tab: Some(Arc::new(tab)),
+            config,
+            limits,
+        })
+    }
+}
+
+/// Makes a request of a session on a blocking thread, once the rate limit and a request
+/// slot of the site allow it, and reports it to the observers
+///
+/// # Arguments
+///
+/// * `limits`:  &Limits - The limits and observers of the session
+/// * `config`:  &ClientConfig - The configuration of the session
+/// * `url`:  &str - The URL the request leads to, for the observers
+/// * `run`:  F - Makes the request
+///
+/// returns: Result<T, Box<dyn Error, Global>>
+async fn request<T, F>(
+    limits: &Limits,
+    config: &ClientConfig,
+    url: &str,
+    run: F,
+) -> Result<T, Box<dyn Error>>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, Box<dyn Error>> + Send + 'static,
+{
+    if let Some(wait) = next_request_wait(config.rate_limit, &limits.next_request) {
+        tokio::time::sleep(wait).await;
+    }
+    let _slot = host_slot(url).await?;
+    let notify = |event: &dyn Fn(&dyn Observer)| {
+        limits
+            .observers
+            .iter()
+            .for_each(|observer| event(observer.as_ref()))
+    };
+    notify(&|observer| observer.on_request_start(url));
+    let started = Instant::now();
+    let result = tokio::task::spawn_blocking(move || run().map_err(sendable))
+        .await?
+        .map_err(|e| e as Box<dyn Error>);
+    let error = result.as_ref().err().map(|e| e.to_string());
+    notify(&|observer| observer.on_request_end(url, started.elapsed(), error.as_deref()));
+    result
+}
+
+impl Drop for HltbSession {
+    fn drop(&mut self) {
+        // Closing the tab blocks until the browser exits
+        let Some(tab) = self.tab.take() else {
+            return;
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => drop(runtime.spawn_blocking(move || drop(tab))),
+            Err(_) => drop(tab),
+        }
+    }
+}
+
+impl HltbSession {
+    /// Searches for a game by typing its name in the search box and lists the results
+    ///
+    /// # Arguments
+    ///
+    /// * `name`:  &str - The name of the game to search for
+    ///
+    /// returns: Result<Vec<SearchResult>, Box<dyn Error, Global>>
+    pub async fn search_results(
+        &mut self,
+        name: &str,
+    ) -> Result<Vec<SearchResult>, Box<dyn Error>> {
+        let name = name.trim().to_string();
+        let url = format!("{}?q={}", self.config.base_url, encode(&name));
+        let results_ready = results_ready_script()?;
+        let select_search_box = format!(
+            "document.querySelector({}).select()",
+            serde_json::to_string(SEARCH_BOX_SELECTOR)?
+        );
+        let content = self
+            .run(&url, PageKind::Search, move |tab, config| {
+                tab.evaluate(WATCH_RESULTS_SCRIPT, false)?;
+                tab.wait_for_element(SEARCH_BOX_SELECTOR)?.click()?;
+                // Typing replaces the selected previous search
+                tab.evaluate(&select_search_box, false)?;
+                tab.find_element(SEARCH_BOX_SELECTOR)?.type_into(&name)?;
+                tab.press_key("Enter")?;
+                wait_for_page(
+                    tab,
+                    SEARCH_HEADER_SELECTOR,
+                    &WaitStrategy::Function(results_ready),
+                    config,
+                    None,
+                )
+            })
+            .await?;
+        Ok(parse::search_results_with_cleaning(
+            &content,
+            &self.config.title_cleaning,
+        ))
+    }
+
+    /// Searches for a game by typing its name in the search box and opens the first result
+    ///
+    /// # Arguments
+    ///
+    /// * `name`:  &str - The name of the game to search for
+    ///
+    /// returns: Result<Game, Box<dyn Error, Global>>
+    pub async fn search(&mut self, name: &str) -> Result<Game, Box<dyn Error>> {
+        let result = self
+            .search_results(name)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| HltbError::NoMatch(name.to_string()))?;
+        self.search_by_id(result.hltb_id).await
+    }
+
+    /// Opens the details page of a game by following a link to it, or with in-page
+    /// navigation when the current page has none
+    ///
+    /// # Arguments
+    ///
+    /// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+    ///
+    /// returns: Result<Game, Box<dyn Error, Global>>
+    pub async fn search_by_id(&mut self, hltb_id: u32) -> Result<Game, Box<dyn Error>> {
+        let url = format!("{}game/{}", self.config.base_url, hltb_id);
+        let (open_details, details_ready) = details_scripts(hltb_id)?;
+        let content = self
+            .run(&url, PageKind::Details, move |tab, config| {
+                tab.evaluate(&open_details, false)?;
+                wait_for_page(
+                    tab,
+                    DETAILS_TABLE_SELECTOR,
+                    &WaitStrategy::Function(details_ready),
+                    config,
+                    None,
+                )
+            })
+            .await?;
+        let mut game = parse::game_with_cleaning(
+            &content,
+            hltb_id,
+            self.config.parse_mode,
+            &self.config.title_cleaning,
+        )?;
+        game.fetched_at = Some(SystemTime::now());
+        Ok(game)
+    }
+
+    /// Runs a navigation in the tab on a blocking thread and returns the content of the page
+    /// it leads to, waited for with the strategy of the page kind once the navigation is done
+    ///
+    /// # Arguments
+    ///
+    /// * `url`:  &str - The URL of the page the navigation leads to
+    /// * `page`:  PageKind - The kind of the page the navigation leads to
+    /// * `navigate`:  F - Navigates and waits until the page is the expected one
+    ///
+    /// returns: Result<String, Box<dyn Error, Global>>
+    async fn run<F>(&self, url: &str, page: PageKind, navigate: F) -> Result<String, Box<dyn Error>>
+    where
+        F: FnOnce(&BrowserGuard, &ClientConfig) -> Result<(), Box<dyn Error>> + Send + 'static,
+    {
+        let tab = self.tab.clone().ok_or("The session is closed")?;
+        let config = self.config.clone();
+        request(&self.limits, &self.config, url, move || {
+            navigate(&tab, &config)?;
+            if *config.wait_strategy(page) != WaitStrategy::Selector {
+                wait_for_page(
+                    &tab,
+                    page.selector(),
+                    config.wait_strategy(page),
+                    &config,
+                    None,
+                )?;
+            }
+            page_content(&tab, config.subtrees(page))
+        })
+        .await
+    }
+}
+
+/// Returns the condition met once the search typed in the search box has rendered its
+/// results, or its lack of results
+///
+/// returns: Result<String, Box<dyn Error, Global>>
+fn results_ready_script() -> Result<String, Box<dyn Error>> {
+    Ok(format!(
+        "window.__hltbResults === true && document.querySelector({}) !== null",
+        serde_json::to_string(SEARCH_HEADER_SELECTOR)?
+    ))
+}
+
+/// Returns the script opening the details page of a game from the current page, and the
+/// condition met once it is rendered
+///
+/// # Arguments
+///
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+///
+/// returns: Result<(String, String), Box<dyn Error, Global>>
+fn details_scripts(hltb_id: u32) -> Result<(String, String), Box<dyn Error>> {
+    let path = serde_json::to_string(&format!("/game/{}", hltb_id))?;
+    let open_details = format!(
+        "(() => {{
+            const link = document.querySelector(\"a[href='/game/{id}']\");
+            if (link) link.click();
+            else if (window.next && window.next.router) window.next.router.push({path});
+            else location.assign({path});
+        }})()",
+        id = hltb_id,
+        path = path
+    );
+    let details_ready = format!(
+        "location.pathname === {} && document.querySelector({}) !== null",
+        path,
+        serde_json::to_string(DETAILS_TABLE_SELECTOR)?
+    );
+    Ok((open_details, details_ready))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_navigation_scripts() {
+        // Met by a search without results too, whose header is rendered all the same
+        assert_eq!(
+            results_ready_script().unwrap(),
+            "window.__hltbResults === true \
+             && document.querySelector(\"#search-results-header\") !== null"
+        );
+        let (open_details, details_ready) = details_scripts(5900).unwrap();
+        assert!(open_details.contains("document.querySelector(\"a[href='/game/5900']\")"));
+        assert!(open_details.contains("window.next.router.push(\"/game/5900\")"));
+        assert!(open_details.contains("location.assign(\"/game/5900\")"));
+        assert!(details_ready.starts_with("location.pathname === \"/game/5900\" && "));
+    }
+
+    #[cfg(feature = "test-util")]
+    #[tokio::test]
+    async fn test_session_without_browser() {
+        use std::time::Duration;
+
+        #[derive(Default)]
+        struct Requests(Mutex<Vec<String>>);
+
+        impl Observer for Arc<Requests> {
+            fn on_request_end(&self, url: &str, _elapsed: Duration, error: Option<&str>) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(format!("{} {}", url, error.is_some()));
+            }
+        }
+
+        let requests = Arc::new(Requests::default());
+        let client = HltbClient::builder()
+            .chrome_path("/nonexistent/chrome")
+            .base_url("http://127.0.0.1:9/")
+            .observer(requests.clone())
+            .build()
+            .unwrap();
+        let error = client.session().await.err().unwrap();
+        assert!(matches!(
+            error.downcast_ref::<HltbError>(),
+            Some(HltbError::BackendUnavailable(_))
+        ));
+        assert_eq!(*requests.0.lock().unwrap(), ["http://127.0.0.1:9/ true"]);
+    }
+}
@@ -0,0 +1,172 @@
+//! Record and replay of fetched pages.
+//!
+//! In record mode every page the client fetches is saved to a cassette file,
+//! keyed by its path relative to the base URL; in replay mode the client serves
+//! the pages of the cassette instead of fetching them, without any network
+//! access or browser, so tests give the same results on every run.
+//!
+//! The free functions of the crate use a client configured from the environment,
+//! so their tests can be recorded once with `HLTB_RECORD=<cassette>` and replayed
+//! offline with `HLTB_REPLAY=<cassette>`.
+
+use crate::HltbError;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The version of the cassettes written by this version of the crate
+const CASSETTE_VERSION: u32 = 1;
+
+/// Whether the client records or replays its pages
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum VcrMode {
+    /// Fetches the pages without recording them
+    #[default]
+    Off,
+    /// Fetches the pages and saves them to a cassette file
+    Record(PathBuf),
+    /// Serves the pages saved in a cassette file, failing on the others
+    Replay(PathBuf),
+}
+
+#[derive(Deserialize, Serialize, Default)]
+struct CassetteFile {
+    version: u32,
+    pages: BTreeMap<String, String>,
+}
+
+pub(super) struct Cassette {
+    path: PathBuf,
+    replay: bool,
+    pages: Mutex<BTreeMap<String, String>>,
+}
+
+impl Cassette {
+    /// Opens the cassette of a mode, if it has one
+    ///
+    /// A cassette that doesn't exist yet is empty when recording, and an error when replaying.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode`:  &VcrMode - The mode of the client
+    ///
+    /// returns: Result<Option<Cassette>, Box<dyn Error, Global>>
+    pub(super) fn open(mode: &VcrMode) -> Result<Option<Cassette>, Box<dyn Error>> {
+        let (path, replay) = match mode {
+            VcrMode::Off => return Ok(None),
+            VcrMode::Record(path) => (path, false),
+            VcrMode::Replay(path) => (path, true),
+        };
+        let pages = match fs::read_to_string(path) {
+            Ok(content) => read_pages(&content)?,
+            Err(e) if e.kind() == ErrorKind::NotFound && !replay => BTreeMap::new(),
+            Err(e) => return Err(format!("Can't read cassette {}: {}", path.display(), e).into()),
+        };
+        Ok(Some(Cassette {
+            path: path.clone(),
+            replay,
+            pages: Mutex::new(pages),
+        }))
+    }
+
+    /// Whether the pages are served from the cassette rather than fetched
+    pub(super) fn is_replaying(&self) -> bool {
+        self.replay
+    }
+
+    /// Returns a recorded page
+    ///
+    /// # Arguments
+    ///
+    /// * `key`:  &str - The path of the page relative to the base URL
+    ///
+    /// returns: Result<String, Box<dyn Error, Global>>
+    pub(super) fn replay(&self, key: &str) -> Result<String, Box<dyn Error>> {
+        self.pages
+            .lock()
+            .unwrap()
+            .get(key)
+            .cloned()
+            .ok_or_else(|| HltbError::NotRecorded(key.to_string()).into())
+    }
+
+    /// Saves a fetched page to the cassette file, replacing any previous recording of it
+    ///
+    /// # Arguments
+    ///
+    /// * `key`:  &str - The path of the page relative to the base URL
+    /// * `content`:  &str - The HTML of the page
+    ///
+    /// returns: Result<(), Box<dyn Error, Global>>
+    pub(super) fn record(&self, key: &str, content: &str) -> Result<(), Box<dyn Error>> {
+        if self.replay {
+            return Ok(());
+        }
+        let mut pages = self.pages.lock().unwrap();
+        pages.insert(key.to_string(), content.to_string());
+        write_pages(&self.path, &pages)
+    }
+}
+
+fn read_pages(content: &str) -> Result<BTreeMap<String, String>, Box<dyn Error>> {
+    let file: CassetteFile = serde_json::from_str(content)?;
+    if file.version > CASSETTE_VERSION {
+        return Err(format!(
+            "Cassette version {} is newer than the supported version {}",
+            file.version, CASSETTE_VERSION
+        )
+        .into());
+    }
+    Ok(file.pages)
+}
+
+fn write_pages(path: &Path, pages: &BTreeMap<String, String>) -> Result<(), Box<dyn Error>> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let file = CassetteFile {
+        version: CASSETTE_VERSION,
+        pages: pages.clone(),
+    };
+    // Pretty-printed so re-recorded cassettes give readable diffs
+    fs::write(path, serde_json::to_string_pretty(&file)?)?;
+    Ok(())
+}
+
+#[cfg(all(test, feature = "browser"))]
+mod tests {
+    use super::*;
+    use crate::HltbClient;
+
+    const SEARCH: &str = r#"<html><body><div id="search-results-header"><ul><li><div>
+        <div class="GameCard_search_list_image__1"><a href="/game/5900" title="Metal Gear"></a></div>
+    </div></li></ul></div></body></html>"#;
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let path = std::env::temp_dir().join("hltb_vcr_test.json");
+        let _ = fs::remove_file(&path);
+        assert!(Cassette::open(&VcrMode::Replay(path.clone())).is_err());
+
+        let cassette = Cassette::open(&VcrMode::Record(path.clone()))
+            .unwrap()
+            .unwrap();
+        cassette.record("?q=Metal%20Gear", SEARCH).unwrap();
+
+        let client = HltbClient::builder().replay(&path).build().unwrap();
+        let results = client.search_results("Metal Gear").await.unwrap();
+        let missing = client.search_by_id(1).await.unwrap_err().to_string();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(results[0].hltb_id, 5900);
+        assert_eq!(
+            results[0].search_url.as_deref(),
+            Some("https://howlongtobeat.com/?q=Metal%20Gear")
+        );
+        assert_eq!(missing, "No recorded response for game/1");
+    }
+}
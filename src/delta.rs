@@ -0,0 +1,247 @@
+//! Changes between two fetches of the same game.
+//!
+//! [`Game::diff`] lists every time that moved, with its old and new value, and
+//! every other field that changed, so watchers, webhooks and changelog-style
+//! interfaces describe updates the same way.
+
+use crate::{Game, Styles};
+use serde::{Deserialize, Serialize};
+
+/// A time that changed between two fetches
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+pub struct TimeChange {
+    /// The changed time, e.g. "main_story.average"
+    pub field: String,
+    pub old: Option<f32>,
+    pub new: Option<f32>,
+}
+
+impl TimeChange {
+    /// Returns how much the time moved in seconds, negative when it got shorter
+    ///
+    /// returns: Option<f32> - None if the time appeared or disappeared
+    pub fn change(&self) -> Option<f32> {
+        Some(self.new? - self.old?)
+    }
+
+    /// Returns how much the time moved relative to its old value, e.g. 0.1 for 10% longer
+    ///
+    /// returns: Option<f32> - None if the time appeared, disappeared or was zero
+    pub fn relative_change(&self) -> Option<f32> {
+        let old = self.old.filter(|old| *old > 0.0)?;
+        Some(self.change()? / old)
+    }
+
+    /// Checks whether the time moved by more than a relative threshold
+    ///
+    /// A time that appears or disappears always exceeds it.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold`:  f32 - The relative change, e.g. 0.05 for 5%
+    ///
+    /// returns: bool
+    pub fn exceeds(&self, threshold: f32) -> bool {
+        match (self.old, self.new) {
+            (Some(old), Some(new)) if old > 0.0 => ((new - old) / old).abs() > threshold,
+            (old, new) => old != new,
+        }
+    }
+}
+
+/// A field other than the times that changed between two fetches, with both values as text
+#[derive(Deserialize, Debug, PartialEq, Eq, Serialize, Clone)]
+pub struct FieldChange {
+    /// The changed field, e.g. "title" or "platforms"
+    pub field: String,
+    pub old: String,
+    pub new: String,
+}
+
+/// Everything that changed between two fetches of a game
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+pub struct GameDelta {
+    /// The How Long to Beat ID of the newer fetch
+    pub hltb_id: u32,
+    /// The title of the newer fetch
+    pub title: String,
+    /// The times that changed, in the order of the play styles then of the kinds of time
+    pub times: Vec<TimeChange>,
+    /// The other fields that changed
+    pub fields: Vec<FieldChange>,
+}
+
+type StyleTime = fn(&Styles) -> Option<f32>;
+
+impl GameDelta {
+    /// Compares two fetches of a game
+    ///
+    /// The fetch dates are not compared, since they always differ.
+    ///
+    /// # Arguments
+    ///
+    /// * `older`:  &Game - The previous fetch
+    /// * `newer`:  &Game - The current fetch
+    ///
+    /// returns: GameDelta
+    pub fn between(older: &Game, newer: &Game) -> GameDelta {
+        GameDelta {
+            hltb_id: newer.hltb_id,
+            title: newer.title.clone(),
+            times: time_changes(older, newer),
+            fields: field_changes(older, newer),
+        }
+    }
+
+    /// Checks whether nothing changed
+    ///
+    /// returns: bool
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty() && self.fields.is_empty()
+    }
+
+    /// Returns the times that moved by more than a relative threshold
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold`:  f32 - The relative change, e.g. 0.05 for 5%
+    ///
+    /// returns: Vec<&TimeChange>
+    pub fn times_exceeding(&self, threshold: f32) -> Vec<&TimeChange> {
+        self.times
+            .iter()
+            .filter(|change| change.exceeds(threshold))
+            .collect()
+    }
+}
+
+fn time_changes(older: &Game, newer: &Game) -> Vec<TimeChange> {
+    let pairs = [
+        ("main_story", &older.main_story, &newer.main_story),
+        ("main_extra", &older.main_extra, &newer.main_extra),
+        ("completionist", &older.completionist, &newer.completionist),
+        ("all_styles", &older.all_styles, &newer.all_styles),
+        ("co_op", &older.co_op, &newer.co_op),
+        ("vs", &older.vs, &newer.vs),
+    ];
+    let kinds: [(&str, StyleTime); 4] = [
+        ("average", |s| s.average),
+        ("median", |s| s.median),
+        ("rushed", |s| s.rushed),
+        ("leisure", |s| s.leisure),
+    ];
+    let mut changes = Vec::new();
+    for (name, old_styles, new_styles) in pairs {
+        for (kind, get) in kinds {
+            let old = old_styles.as_ref().and_then(get);
+            let new = new_styles.as_ref().and_then(get);
+            if old != new {
+                changes.push(TimeChange {
+                    field: format!("{}.{}", name, kind),
+                    old,
+                    new,
+                });
+            }
+        }
+    }
+    changes
+}
+
+fn field_changes(older: &Game, newer: &Game) -> Vec<FieldChange> {
+    let join = |values: Vec<String>| values.join(", ");
+    let pairs = [
+        (
+            "hltb_id",
+            older.hltb_id.to_string(),
+            newer.hltb_id.to_string(),
+        ),
+        ("title", older.title.clone(), newer.title.clone()),
+        (
+            "platforms",
+            join(older.platforms.iter().map(|p| p.to_string()).collect()),
+            join(newer.platforms.iter().map(|p| p.to_string()).collect()),
+        ),
+        (
+            "release_year",
+            older
+                .release_year
+                .map(|y| y.to_string())
+                .unwrap_or_default(),
+            newer
+                .release_year
+                .map(|y| y.to_string())
+                .unwrap_or_default(),
+        ),
+        (
+            "aliases",
+            join(older.aliases.clone()),
+            join(newer.aliases.clone()),
+        ),
+        (
+            "focus",
+            join(older.focus.iter().map(|f| format!("{:?}", f)).collect()),
+            join(newer.focus.iter().map(|f| format!("{:?}", f)).collect()),
+        ),
+    ];
+    pairs
+        .into_iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(field, old, new)| FieldChange {
+            field: field.to_string(),
+            old,
+            new,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Platform;
+
+    fn game(main_average: f32) -> Game {
+        Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            Some(Styles::new(Some(main_average), Some(14400.0), None, None)),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_diff() {
+        let older = game(15000.0);
+        let mut newer = game(16500.0);
+        newer.completionist = Some(Styles::new(Some(19800.0), None, None, None));
+        newer.platforms = vec![Platform::Nes];
+        newer.release_year = Some(1987);
+        let delta = newer.diff(&older);
+        assert_eq!(delta.times.len(), 2);
+        assert_eq!(delta.times[0].field, "main_story.average");
+        assert_eq!(delta.times[0].change(), Some(1500.0));
+        assert_eq!(delta.times[0].relative_change(), Some(0.1));
+        assert_eq!(delta.times[1].field, "completionist.average");
+        assert_eq!(delta.times[1].change(), None);
+        assert_eq!(delta.times_exceeding(0.2).len(), 1);
+        assert_eq!(
+            delta.fields,
+            vec![
+                FieldChange {
+                    field: "platforms".to_string(),
+                    old: String::new(),
+                    new: "NES".to_string(),
+                },
+                FieldChange {
+                    field: "release_year".to_string(),
+                    old: String::new(),
+                    new: "1987".to_string(),
+                },
+            ]
+        );
+        assert!(older.diff(&older.clone()).is_empty());
+    }
+}
@@ -0,0 +1,170 @@
+//! Distributions of the submitted times.
+//!
+//! The table of a details page only gives four numbers per play style (average,
+//! median, rushed and leisure). Some pages also carry the distribution of the
+//! submitted times in the data they were rendered from, the game of their
+//! `__NEXT_DATA__` JSON: a histogram under `<prefix>_dist` and percentiles under
+//! `<prefix>_p<N>` (e.g. `comp_main_p90`), the prefix being the one of the play
+//! style (`comp_main`, `comp_plus`, `comp_100` or `comp_all`) and times being in
+//! seconds. Pages without them simply have no distribution.
+
+use crate::parse::next_data;
+use crate::PlayStyle;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// A bar of a histogram of submitted times
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone, Copy)]
+pub struct Bucket {
+    /// The shortest time of the bucket in seconds, included
+    pub from: f32,
+    /// The longest time of the bucket in seconds, excluded
+    pub to: f32,
+    /// How many submitted times fall in the bucket
+    pub count: u32,
+}
+
+/// How the submitted times of a play style are spread
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone, Default)]
+pub struct Distribution {
+    /// The histogram of the times, from the shortest bucket, empty if the page has none
+    pub buckets: Vec<Bucket>,
+    /// The times in seconds by percentile, from 1 to 99, empty if the page has none
+    pub percentiles: BTreeMap<u8, f32>,
+}
+
+impl Distribution {
+    /// Returns the number of times of the histogram
+    ///
+    /// returns: u32
+    pub fn total(&self) -> u32 {
+        self.buckets.iter().map(|bucket| bucket.count).sum()
+    }
+
+    /// Checks whether the page gave neither a histogram nor percentiles
+    ///
+    /// returns: bool
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty() && self.percentiles.is_empty()
+    }
+}
+
+/// Returns the prefix of the fields of a play style in the data of a game
+fn prefix(style: PlayStyle) -> &'static str {
+    match style {
+        PlayStyle::MainStory => "comp_main",
+        PlayStyle::MainExtra => "comp_plus",
+        PlayStyle::Completionist => "comp_100",
+        PlayStyle::AllStyles => "comp_all",
+    }
+}
+
+/// Parses the distributions of the submitted times of a details page, where available
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the details page
+///
+/// returns: BTreeMap<PlayStyle, Distribution> - The play styles with a distribution
+pub fn distributions(content: &str) -> BTreeMap<PlayStyle, Distribution> {
+    let Some(data) = next_data(content) else {
+        return BTreeMap::new();
+    };
+    let Some(game) = data["props"]["pageProps"]["game"]["data"]["game"][0].as_object() else {
+        return BTreeMap::new();
+    };
+    let styles = [
+        PlayStyle::MainStory,
+        PlayStyle::MainExtra,
+        PlayStyle::Completionist,
+        PlayStyle::AllStyles,
+    ];
+    styles
+        .into_iter()
+        .filter_map(|style| {
+            let prefix = prefix(style);
+            let buckets = game
+                .get(&format!("{}_dist", prefix))
+                .and_then(Value::as_array)
+                .map(|buckets| buckets.iter().filter_map(parse_bucket).collect())
+                .unwrap_or_default();
+            let percentiles = game
+                .iter()
+                .filter_map(|(key, value)| {
+                    let percentile = key.strip_prefix(prefix)?.strip_prefix("_p")?.parse().ok()?;
+                    let time = value.as_f64()? as f32;
+                    (1..=99).contains(&percentile).then_some((percentile, time))
+                })
+                .collect();
+            let distribution = Distribution {
+                buckets,
+                percentiles,
+            };
+            (!distribution.is_empty()).then_some((style, distribution))
+        })
+        .collect()
+}
+
+/// Parses a bucket of a histogram, either `{"min": .., "max": .., "count": ..}` or
+/// `[min, max, count]`
+fn parse_bucket(value: &Value) -> Option<Bucket> {
+    let (from, to, count) = match value {
+        Value::Array(values) => (values.first()?, values.get(1)?, values.get(2)?),
+        Value::Object(fields) => (fields.get("min")?, fields.get("max")?, fields.get("count")?),
+        _ => return None,
+    };
+    Some(Bucket {
+        from: from.as_f64()? as f32,
+        to: to.as_f64()? as f32,
+        count: count.as_u64()?.try_into().ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distributions() {
+        let content = r#"<html><body><script id="__NEXT_DATA__" type="application/json">
+            {"props": {"pageProps": {"game": {"data": {"game": [{
+                "game_id": 5900,
+                "comp_main": 15000,
+                "comp_main_dist": [[0, 7200, 3], {"min": 7200, "max": 14400, "count": 12}, "bad"],
+                "comp_main_p10": 9000,
+                "comp_main_p90": 25000,
+                "comp_main_p100": 99999,
+                "comp_100_p50": 18000
+            }]}}}}}
+        </script></body></html>"#;
+        let distributions = distributions(content);
+        assert_eq!(
+            distributions.keys().collect::<Vec<_>>(),
+            [&PlayStyle::MainStory, &PlayStyle::Completionist]
+        );
+        let main = &distributions[&PlayStyle::MainStory];
+        assert_eq!(
+            main.buckets,
+            [
+                Bucket {
+                    from: 0.0,
+                    to: 7200.0,
+                    count: 3
+                },
+                Bucket {
+                    from: 7200.0,
+                    to: 14400.0,
+                    count: 12
+                },
+            ]
+        );
+        assert_eq!(main.total(), 15);
+        assert_eq!(
+            main.percentiles,
+            BTreeMap::from([(10, 9000.0), (90, 25000.0)])
+        );
+        assert!(super::distributions("<html></html>").is_empty());
+    }
+}
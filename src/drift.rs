@@ -0,0 +1,216 @@
+//! Detection of changes of the site's markup.
+//!
+//! The site changes its pages from time to time, which silently breaks the
+//! selectors of the parsers. [`compare`] parses a live page and a known-good
+//! fixture of the same game and reports how their structure differs: a time
+//! row or a field found in the fixture but not in the live page usually means a
+//! selector or a label no longer matches. Times themselves are not compared, as
+//! they change whenever users submit new ones.
+//!
+//! [`detect_drift`] fetches the live page itself, so a deployment can run it
+//! periodically and alert before lookups start failing.
+
+use crate::parse::{self, ParseMode};
+use crate::{Game, Platform, Styles};
+use std::error::Error;
+use std::fmt;
+
+/// A part of a page that differs between the fixture and the live page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drift {
+    /// The part of the page, e.g. "title", "main_story" or "page" when the live page can't be
+    /// parsed strictly
+    pub field: String,
+    /// What the fixture has
+    pub fixture: String,
+    /// What the live page has
+    pub live: String,
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: expected {}, found {}",
+            self.field, self.fixture, self.live
+        )
+    }
+}
+
+/// The differences between the fixture and the live page of a game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriftReport {
+    pub hltb_id: u32,
+    pub drifts: Vec<Drift>,
+}
+
+impl DriftReport {
+    /// Whether the live page has the same structure as the fixture
+    ///
+    /// returns: bool
+    pub fn is_clean(&self) -> bool {
+        self.drifts.is_empty()
+    }
+}
+
+/// Compares the structure of a live details page with a fixture of the same game
+///
+/// The live page is parsed strictly first, and leniently when that fails, so both the
+/// failure and the fields that can still be read are reported.
+///
+/// # Arguments
+///
+/// * `fixture`:  &str - The HTML of a details page known to parse correctly
+/// * `live`:  &str - The HTML of the same details page, as currently served
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+///
+/// returns: Result<DriftReport, Box<dyn Error, Global>> - An error if the fixture itself
+/// can't be parsed
+pub fn compare(fixture: &str, live: &str, hltb_id: u32) -> Result<DriftReport, Box<dyn Error>> {
+    let expected = shape(&parse::game_with_mode(fixture, hltb_id, ParseMode::Strict)?);
+    let mut drifts = Vec::new();
+    let live_game = match parse::game_with_mode(live, hltb_id, ParseMode::Strict) {
+        Ok(game) => Some(game),
+        Err(e) => {
+            drifts.push(Drift {
+                field: "page".to_string(),
+                fixture: "a parsable page".to_string(),
+                live: e.to_string(),
+            });
+            parse::game_with_mode(live, hltb_id, ParseMode::Lenient).ok()
+        }
+    };
+    if let Some(live_game) = live_game {
+        for ((field, fixture), (_, live)) in expected.into_iter().zip(shape(&live_game)) {
+            if fixture != live {
+                drifts.push(Drift {
+                    field: field.to_string(),
+                    fixture,
+                    live,
+                });
+            }
+        }
+    }
+    Ok(DriftReport { hltb_id, drifts })
+}
+
+/// Fetches the live details page of a game and compares it with a fixture
+///
+/// # Arguments
+///
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+/// * `fixture`:  &str - The HTML of its details page, known to parse correctly
+///
+/// returns: Result<DriftReport, Box<dyn Error, Global>>
+#[cfg(any(feature = "browser", feature = "http"))]
+pub async fn detect_drift(hltb_id: u32, fixture: &str) -> Result<DriftReport, Box<dyn Error>> {
+    crate::HltbClient::builder()
+        .build()?
+        .detect_drift(hltb_id, fixture)
+        .await
+}
+
+#[cfg(any(feature = "browser", feature = "http"))]
+impl crate::HltbClient {
+    /// Fetches the live details page of a game and compares it with a fixture
+    ///
+    /// # Arguments
+    ///
+    /// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+    /// * `fixture`:  &str - The HTML of its details page, known to parse correctly
+    ///
+    /// returns: Result<DriftReport, Box<dyn Error, Global>>
+    pub async fn detect_drift(
+        &self,
+        hltb_id: u32,
+        fixture: &str,
+    ) -> Result<DriftReport, Box<dyn Error>> {
+        let live = self.detail_html(hltb_id).await?;
+        compare(fixture, &live, hltb_id)
+    }
+}
+
+/// Describes which parts of a game were found, without the times themselves
+fn shape(game: &Game) -> Vec<(&'static str, String)> {
+    vec![
+        ("title", game.title.clone()),
+        ("main_story", shape_styles(&game.main_story)),
+        ("main_extra", shape_styles(&game.main_extra)),
+        ("completionist", shape_styles(&game.completionist)),
+        ("all_styles", shape_styles(&game.all_styles)),
+        ("co_op", shape_styles(&game.co_op)),
+        ("vs", shape_styles(&game.vs)),
+        (
+            "platforms",
+            game.platforms
+                .iter()
+                .map(Platform::name)
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        (
+            "release_year",
+            game.release_year
+                .map_or("none".to_string(), |_| "a year".to_string()),
+        ),
+        ("aliases", game.aliases.join(", ")),
+    ]
+}
+
+fn shape_styles(styles: &Option<Styles>) -> String {
+    let Some(styles) = styles else {
+        return "no row".to_string();
+    };
+    let times: Vec<&str> = [
+        ("average", styles.average),
+        ("median", styles.median),
+        ("rushed", styles.rushed),
+        ("leisure", styles.leisure),
+    ]
+    .iter()
+    .filter(|(_, time)| time.is_some())
+    .map(|(name, _)| *name)
+    .collect();
+    format!("times ({})", times.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = r#"<html><body><div id="__next"><div><main>
+        <div><div><div><div>
+            <div class="GameHeader_profile_header__q_PID">Metal Gear</div>
+        </div></div></div></div>
+        <div><div><div class="GameSummary_content__x1">
+            <div class="GameSummary_profile_info__e935c">Platforms: NES, MSX2</div>
+            <div class="in scrollable scroll_blue shadow_box back_primary">
+                <table class="GameTimeTable_game_main_table__7uN3H"><tbody>
+                    <tr><td>Main Story</td><td>1.2K</td><td>4h 10m</td><td>4h</td><td>2h 46m</td><td>7h 12m</td></tr>
+                </tbody></table>
+            </div>
+        </div></div></div>
+    </main></div></div></body></html>"#;
+
+    #[test]
+    fn test_compare() {
+        let newer_times = FIXTURE.replace("4h 10m", "4h 12m");
+        assert!(compare(FIXTURE, &newer_times, 5900).unwrap().is_clean());
+
+        let renamed_row = FIXTURE.replace("Main Story", "Story");
+        let report = compare(FIXTURE, &renamed_row, 5900).unwrap();
+        assert_eq!(
+            report.drifts[0].live,
+            "Unexpected page structure: unknown row \"Story\""
+        );
+        assert_eq!(
+            report.drifts[1].to_string(),
+            "main_story: expected times (average, median, rushed, leisure), found no row"
+        );
+
+        let renamed_table = FIXTURE.replace("_game_main_table", "_times");
+        let report = compare(FIXTURE, &renamed_table, 5900).unwrap();
+        assert_eq!(report.drifts.len(), 1);
+        assert_eq!(report.drifts[0].live, "Game times not found");
+    }
+}
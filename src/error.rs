@@ -0,0 +1,149 @@
+//! Errors raised by the crate.
+//!
+//! Functions return `Box<dyn Error>` so that the errors of the browser, HTTP and
+//! JSON layers pass through unchanged. The errors the crate raises itself are
+//! [`HltbError`]s, which callers can tell apart with `downcast_ref`:
+//!
+//! ```
+//! use howlongtobeat_scraper::HltbError;
+//!
+//! let error: Box<dyn std::error::Error> = HltbError::Cancelled.into();
+//! assert_eq!(error.downcast_ref::<HltbError>(), Some(&HltbError::Cancelled));
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HltbError {
+    /// A page never contained the element matching a selector
+    ElementNotFound(String),
+    /// A page never met the condition of a wait strategy, e.g. a JavaScript expression
+    WaitTimedOut(String),
+    /// A page lacks some data, e.g. "Game title"
+    MissingData(&'static str),
+    /// A page doesn't have the structure the parser expects (strict parsing only)
+    Unexpected(String),
+    /// No search result matches the query
+    NoMatch(String),
+    /// A replayed cassette has no recording of a page
+    NotRecorded(String),
+    /// The lookup was cancelled
+    Cancelled,
+    /// The configured backend can't perform the operation
+    Unsupported(&'static str),
+    /// A configuration value is invalid, e.g. an environment variable
+    InvalidConfig { name: String, value: String },
+    /// The game was found but isn't available on the requested platform
+    NotOnPlatform { title: String, platform: String },
+    /// A page body exceeded the maximum size, in bytes
+    BodyTooLarge { url: String, limit: usize },
+    /// No backend could fetch the page, with what was tried
+    BackendUnavailable(Vec<BackendAttempt>),
+    /// A page wasn't requested because the client only plans its requests
+    DryRun(String),
+}
+
+/// A backend that couldn't be used, with why and how to make it usable
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackendAttempt {
+    /// The backend, e.g. "browser"
+    pub backend: String,
+    /// Why it couldn't be used, e.g. "Chrome was not found"
+    pub reason: String,
+    /// How to make it usable
+    pub fix: String,
+}
+
+impl BackendAttempt {
+    /// Describes a backend that couldn't be used
+    ///
+    /// # Arguments
+    ///
+    /// * `backend`:  &str - The backend, e.g. "browser"
+    /// * `reason`:  &str - Why it couldn't be used
+    /// * `fix`:  &str - How to make it usable
+    ///
+    /// returns: BackendAttempt
+    pub fn new(backend: &str, reason: &str, fix: &str) -> BackendAttempt {
+        BackendAttempt {
+            backend: backend.to_string(),
+            reason: reason.to_string(),
+            fix: fix.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for HltbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HltbError::ElementNotFound(selector) => write!(f, "Element not found: {}", selector),
+            HltbError::WaitTimedOut(condition) => write!(f, "Timed out waiting for {}", condition),
+            HltbError::MissingData(what) => write!(f, "{} not found", what),
+            HltbError::Unexpected(what) => write!(f, "Unexpected page structure: {}", what),
+            HltbError::NoMatch(name) => write!(f, "No search result matches {}", name),
+            HltbError::NotRecorded(path) => write!(f, "No recorded response for {}", path),
+            HltbError::Cancelled => write!(f, "Lookup cancelled"),
+            HltbError::Unsupported(operation) => write!(f, "{}", operation),
+            HltbError::InvalidConfig { name, value } => write!(f, "Invalid {}: {}", name, value),
+            HltbError::NotOnPlatform { title, platform } => {
+                write!(f, "{} is not available on {}", title, platform)
+            }
+            HltbError::BodyTooLarge { url, limit } => {
+                write!(f, "The page {} is larger than {} bytes", url, limit)
+            }
+            HltbError::BackendUnavailable(attempts) => {
+                write!(f, "No backend is usable")?;
+                for (i, attempt) in attempts.iter().enumerate() {
+                    write!(
+                        f,
+                        "{} the {} backend failed ({}), to fix it {}",
+                        if i == 0 { ":" } else { ";" },
+                        attempt.backend,
+                        attempt.reason,
+                        attempt.fix
+                    )?;
+                }
+                Ok(())
+            }
+            HltbError::DryRun(url) => write!(f, "Not requested in a dry run: {}", url),
+        }
+    }
+}
+
+impl Error for HltbError {}
+
+impl HltbError {
+    /// Creates an error for an invalid configuration value
+    ///
+    /// # Arguments
+    ///
+    /// * `name`:  &str - The name of the setting, e.g. "HLTB_TIMEOUT"
+    /// * `value`:  &str - The invalid value
+    ///
+    /// returns: HltbError
+    pub(crate) fn invalid_config(name: &str, value: &str) -> HltbError {
+        HltbError::InvalidConfig {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+/// Makes an error sendable across threads, e.g. out of the blocking thread of the browser
+///
+/// A [`HltbError`] is kept as is so it can still be matched on, other errors are
+/// converted to their message.
+///
+/// # Arguments
+///
+/// * `error`:  Box<dyn Error> - The error
+///
+/// returns: Box<dyn Error + Send + Sync, Global>
+#[cfg(any(feature = "browser", feature = "serve", feature = "node"))]
+pub(crate) fn sendable(error: Box<dyn Error>) -> Box<dyn Error + Send + Sync> {
+    match error.downcast::<HltbError>() {
+        Ok(error) => error,
+        Err(error) => error.to_string().into(),
+    }
+}
@@ -0,0 +1,282 @@
+//! CSV export.
+//!
+//! Every game is flattened into a [`GameRecord`], one column per play style and
+//! time kind, so the output opens directly in a spreadsheet. Times are in seconds
+//! and missing values are left empty.
+
+use crate::{Game, Platform, Styles};
+use serde::Serialize;
+use std::error::Error;
+use std::io::Write;
+
+/// The header of the CSV export, in column order
+pub const CSV_COLUMNS: [&str; 28] = [
+    "hltb_id",
+    "title",
+    "platforms",
+    "release_year",
+    "main_story_average",
+    "main_story_median",
+    "main_story_rushed",
+    "main_story_leisure",
+    "main_extra_average",
+    "main_extra_median",
+    "main_extra_rushed",
+    "main_extra_leisure",
+    "completionist_average",
+    "completionist_median",
+    "completionist_rushed",
+    "completionist_leisure",
+    "all_styles_average",
+    "all_styles_median",
+    "all_styles_rushed",
+    "all_styles_leisure",
+    "co_op_average",
+    "co_op_median",
+    "co_op_rushed",
+    "co_op_leisure",
+    "vs_average",
+    "vs_median",
+    "vs_rushed",
+    "vs_leisure",
+];
+
+/// A game flattened into a single row, with one field per column of [`CSV_COLUMNS`]
+#[derive(Serialize, Debug, PartialEq, Clone, Default)]
+pub struct GameRecord {
+    pub hltb_id: u32,
+    pub title: String,
+    /// The platforms of the game, separated by "; "
+    pub platforms: String,
+    pub release_year: Option<i32>,
+    pub main_story_average: Option<f32>,
+    pub main_story_median: Option<f32>,
+    pub main_story_rushed: Option<f32>,
+    pub main_story_leisure: Option<f32>,
+    pub main_extra_average: Option<f32>,
+    pub main_extra_median: Option<f32>,
+    pub main_extra_rushed: Option<f32>,
+    pub main_extra_leisure: Option<f32>,
+    pub completionist_average: Option<f32>,
+    pub completionist_median: Option<f32>,
+    pub completionist_rushed: Option<f32>,
+    pub completionist_leisure: Option<f32>,
+    pub all_styles_average: Option<f32>,
+    pub all_styles_median: Option<f32>,
+    pub all_styles_rushed: Option<f32>,
+    pub all_styles_leisure: Option<f32>,
+    pub co_op_average: Option<f32>,
+    pub co_op_median: Option<f32>,
+    pub co_op_rushed: Option<f32>,
+    pub co_op_leisure: Option<f32>,
+    pub vs_average: Option<f32>,
+    pub vs_median: Option<f32>,
+    pub vs_rushed: Option<f32>,
+    pub vs_leisure: Option<f32>,
+}
+
+impl From<&Game> for GameRecord {
+    fn from(game: &Game) -> GameRecord {
+        let kinds = |styles: &Option<Styles>| match styles {
+            Some(s) => [s.average, s.median, s.rushed, s.leisure],
+            None => [None; 4],
+        };
+        let [main_story_average, main_story_median, main_story_rushed, main_story_leisure] =
+            kinds(&game.main_story);
+        let [main_extra_average, main_extra_median, main_extra_rushed, main_extra_leisure] =
+            kinds(&game.main_extra);
+        let [completionist_average, completionist_median, completionist_rushed, completionist_leisure] =
+            kinds(&game.completionist);
+        let [all_styles_average, all_styles_median, all_styles_rushed, all_styles_leisure] =
+            kinds(&game.all_styles);
+        let [co_op_average, co_op_median, co_op_rushed, co_op_leisure] = kinds(&game.co_op);
+        let [vs_average, vs_median, vs_rushed, vs_leisure] = kinds(&game.vs);
+        GameRecord {
+            hltb_id: game.hltb_id,
+            title: game.title.clone(),
+            platforms: game
+                .platforms
+                .iter()
+                .map(Platform::name)
+                .collect::<Vec<_>>()
+                .join("; "),
+            release_year: game.release_year,
+            main_story_average,
+            main_story_median,
+            main_story_rushed,
+            main_story_leisure,
+            main_extra_average,
+            main_extra_median,
+            main_extra_rushed,
+            main_extra_leisure,
+            completionist_average,
+            completionist_median,
+            completionist_rushed,
+            completionist_leisure,
+            all_styles_average,
+            all_styles_median,
+            all_styles_rushed,
+            all_styles_leisure,
+            co_op_average,
+            co_op_median,
+            co_op_rushed,
+            co_op_leisure,
+            vs_average,
+            vs_median,
+            vs_rushed,
+            vs_leisure,
+        }
+    }
+}
+
+impl GameRecord {
+    /// Returns the times of the record, in the order of [`CSV_COLUMNS`] after `release_year`
+    ///
+    /// returns: [Option<f32>; 24]
+    pub(super) fn times(&self) -> [Option<f32>; 24] {
+        [
+            self.main_story_average,
+            self.main_story_median,
+            self.main_story_rushed,
+            self.main_story_leisure,
+            self.main_extra_average,
+            self.main_extra_median,
+            self.main_extra_rushed,
+            self.main_extra_leisure,
+            self.completionist_average,
+            self.completionist_median,
+            self.completionist_rushed,
+            self.completionist_leisure,
+            self.all_styles_average,
+            self.all_styles_median,
+            self.all_styles_rushed,
+            self.all_styles_leisure,
+            self.co_op_average,
+            self.co_op_median,
+            self.co_op_rushed,
+            self.co_op_leisure,
+            self.vs_average,
+            self.vs_median,
+            self.vs_rushed,
+            self.vs_leisure,
+        ]
+    }
+
+    /// Returns the values of the record as CSV fields, in the order of [`CSV_COLUMNS`]
+    ///
+    /// returns: Vec<String>
+    fn fields(&self) -> Vec<String> {
+        let time = |t: Option<f32>| t.map(|t| t.to_string()).unwrap_or_default();
+        let mut fields = vec![
+            self.hltb_id.to_string(),
+            self.title.clone(),
+            self.platforms.clone(),
+            self.release_year.map(|y| y.to_string()).unwrap_or_default(),
+        ];
+        fields.extend(self.times().map(time));
+        fields
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or a line break
+///
+/// # Arguments
+///
+/// * `field`:  &str - The field to escape
+///
+/// returns: String
+fn escape_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Writes a CSV line, terminated by CRLF as in RFC 4180
+///
+/// # Arguments
+///
+/// * `writer`:  &mut W - Where to write the line
+/// * `fields`:  &[S] - The fields of the line
+///
+/// returns: Result<(), Box<dyn Error, Global>>
+pub(super) fn write_line<W: Write, S: AsRef<str>>(
+    writer: &mut W,
+    fields: &[S],
+) -> Result<(), Box<dyn Error>> {
+    let line = fields
+        .iter()
+        .map(|f| escape_field(f.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",");
+    write!(writer, "{}\r\n", line)?;
+    Ok(())
+}
+
+/// Writes several games as CSV, with a header line followed by one line per game
+///
+/// # Arguments
+///
+/// * `games`:  &[Game] - The resolved games
+/// * `writer`:  W - Where to write the CSV
+///
+/// returns: Result<(), Box<dyn Error, Global>>
+pub fn export_csv<W: Write>(games: &[Game], mut writer: W) -> Result<(), Box<dyn Error>> {
+    write_line(&mut writer, &CSV_COLUMNS)?;
+    for game in games {
+        write_line(&mut writer, &GameRecord::from(game).fields())?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_csv() {
+        let mut game = Game::new(
+            "Metal Gear, \"Solid\"".to_string(),
+            5900,
+            Some(Styles::new(Some(15000.0), None, None, None)),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        game.platforms = vec![Platform::Nes, Platform::Msx2];
+        game.release_year = Some(1987);
+
+        let mut output = Vec::new();
+        export_csv(&[game], &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.split("\r\n").collect();
+        assert_eq!(lines[0], CSV_COLUMNS.join(","));
+        assert!(lines[1].starts_with("5900,\"Metal Gear, \"\"Solid\"\"\",NES; MSX2,1987,15000,,"));
+        assert_eq!(lines[1].split(',').count(), CSV_COLUMNS.len() + 1);
+        assert_eq!(lines[2], "");
+    }
+
+    #[test]
+    fn test_record_matches_columns() {
+        let game = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let record = GameRecord::from(&game);
+        assert_eq!(record.fields().len(), CSV_COLUMNS.len());
+        let json = serde_json::to_value(&record).unwrap();
+        for column in CSV_COLUMNS {
+            assert!(json.get(column).is_some(), "missing field {}", column);
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! iCalendar export of a backlog plan.
+//!
+//! [`plan_backlog`] spreads a weekly play-time budget evenly over the days and
+//! plays the games one after the other, so every game gets the days it would be
+//! played on. [`export_ical`] writes the plan as an iCalendar file (RFC 5545)
+//! with one all-day event per game, which calendar applications import as is.
+
+use crate::repr::{civil_from_days, format_duration};
+use crate::{game_url, Game, HltbError};
+use std::error::Error;
+use std::io::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: u64 = 86400;
+
+/// A game of a backlog plan with the days it is played on
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledGame {
+    pub hltb_id: u32,
+    pub title: String,
+    /// How long the game takes in seconds
+    pub seconds: f32,
+    /// The day the game is started on, at midnight UTC
+    pub start: SystemTime,
+    /// The day the game is finished on, at midnight UTC
+    pub finish: SystemTime,
+}
+
+/// Plans when every game of a backlog would be played, in order, with a weekly budget
+///
+/// The budget is spread evenly over the days of the week, and a game starts on the day the
+/// previous one is finished when there is time left that day.
+///
+/// # Arguments
+///
+/// * `games`:  impl IntoIterator<Item = (&Game, f32)> - The games in the order they are played,
+///   with how long each takes in seconds
+/// * `weekly_hours`:  f32 - How many hours are played per week
+/// * `start`:  SystemTime - When the plan starts, e.g. `SystemTime::now()`
+///
+/// returns: Result<Vec<ScheduledGame>, Box<dyn Error, Global>> - An error if the budget isn't
+/// positive
+pub fn plan_backlog<'a>(
+    games: impl IntoIterator<Item = (&'a Game, f32)>,
+    weekly_hours: f32,
+    start: SystemTime,
+) -> Result<Vec<ScheduledGame>, Box<dyn Error>> {
+    if !(weekly_hours > 0.0 && weekly_hours.is_finite()) {
+        return Err(HltbError::invalid_config("weekly hours", &weekly_hours.to_string()).into());
+    }
+    let daily_seconds = f64::from(weekly_hours) * 3600.0 / 7.0;
+    let first_day = start
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / SECONDS_PER_DAY;
+    let day = |offset: f64| {
+        UNIX_EPOCH + Duration::from_secs((first_day + offset as u64) * SECONDS_PER_DAY)
+    };
+    let mut played = 0.0;
+    let mut plan = Vec::new();
+    for (game, seconds) in games {
+        let begin = (played / daily_seconds).floor();
+        played += f64::from(seconds.max(0.0));
+        let end = ((played / daily_seconds).ceil() - 1.0).max(begin);
+        plan.push(ScheduledGame {
+            hltb_id: game.hltb_id,
+            title: game.title.clone(),
+            seconds,
+            start: day(begin),
+            finish: day(end),
+        });
+    }
+    Ok(plan)
+}
+
+/// Formats a day as an iCalendar date, e.g. "20240208"
+fn ical_date(time: SystemTime) -> String {
+    let days = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / SECONDS_PER_DAY;
+    let (year, month, day) = civil_from_days(days as i64);
+    format!("{:04}{:02}{:02}", year, month, day)
+}
+
+/// Escapes an iCalendar text value
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Writes a content line, folded at 75 bytes and terminated by CRLF as in RFC 5545
+fn write_line<W: Write>(writer: &mut W, line: &str) -> Result<(), Box<dyn Error>> {
+    let mut rest = line;
+    let mut limit = 75;
+    while rest.len() > limit {
+        let mut cut = limit;
+        while !rest.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        write!(writer, "{}\r\n ", &rest[..cut])?;
+        rest = &rest[cut..];
+        // The leading space of continuation lines counts towards their length
+        limit = 74;
+    }
+    write!(writer, "{}\r\n", rest)?;
+    Ok(())
+}
+
+/// Writes a backlog plan as an iCalendar file, with one all-day event per game
+///
+/// # Arguments
+///
+/// * `plan`:  &[ScheduledGame] - The plan, see [`plan_backlog`]
+/// * `stamp`:  SystemTime - When the calendar is created, e.g. `SystemTime::now()`
+/// * `writer`:  W - Where to write the calendar
+///
+/// returns: Result<(), Box<dyn Error, Global>>
+pub fn export_ical<W: Write>(
+    plan: &[ScheduledGame],
+    stamp: SystemTime,
+    mut writer: W,
+) -> Result<(), Box<dyn Error>> {
+    let stamp = stamp
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let second_of_day = stamp % SECONDS_PER_DAY;
+    let stamp = format!(
+        "{}T{:02}{:02}{:02}Z",
+        ical_date(UNIX_EPOCH + Duration::from_secs(stamp)),
+        second_of_day / 3600,
+        second_of_day % 3600 / 60,
+        second_of_day % 60
+    );
+    write_line(&mut writer, "BEGIN:VCALENDAR")?;
+    write_line(&mut writer, "VERSION:2.0")?;
+    write_line(
+        &mut writer,
+        "PRODID:-//howlongtobeat-scraper//Backlog plan//EN",
+    )?;
+    for (index, game) in plan.iter().enumerate() {
+        let end = game.finish + Duration::from_secs(SECONDS_PER_DAY);
+        write_line(&mut writer, "BEGIN:VEVENT")?;
+        write_line(
+            &mut writer,
+            &format!("UID:{}-{}@howlongtobeat-scraper", index, game.hltb_id),
+        )?;
+        write_line(&mut writer, &format!("DTSTAMP:{}", stamp))?;
+        write_line(
+            &mut writer,
+            &format!("DTSTART;VALUE=DATE:{}", ical_date(game.start)),
+        )?;
+        write_line(&mut writer, &format!("DTEND;VALUE=DATE:{}", ical_date(end)))?;
+        write_line(
+            &mut writer,
+            &format!("SUMMARY:{}", escape_text(&format!("Play {}", game.title))),
+        )?;
+        let description = format!(
+            "About {}\n{}",
+            format_duration(game.seconds),
+            game_url(game.hltb_id)
+        );
+        write_line(
+            &mut writer,
+            &format!("DESCRIPTION:{}", escape_text(&description)),
+        )?;
+        write_line(&mut writer, &format!("URL:{}", game_url(game.hltb_id)))?;
+        write_line(&mut writer, "END:VEVENT")?;
+    }
+    write_line(&mut writer, "END:VCALENDAR")?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(title: &str, hltb_id: u32) -> Game {
+        Game::new(
+            title.to_string(),
+            hltb_id,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_plan_backlog() {
+        let metal_gear = game("Metal Gear", 5900);
+        let contra = game("Contra, the arcade port", 1);
+        // 2024-02-08T13:45:00Z, with 2 hours a day
+        let start = UNIX_EPOCH + Duration::from_secs(1_707_399_900);
+        let plan = plan_backlog([(&metal_gear, 15000.0), (&contra, 3600.0)], 14.0, start).unwrap();
+        assert_eq!(ical_date(plan[0].start), "20240208");
+        assert_eq!(ical_date(plan[0].finish), "20240210");
+        assert_eq!(ical_date(plan[1].start), "20240210");
+        assert_eq!(ical_date(plan[1].finish), "20240210");
+        assert!(plan_backlog([(&contra, 3600.0)], 0.0, start).is_err());
+
+        let mut output = Vec::new();
+        export_ical(&plan[1..], start, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(output.contains("DTSTAMP:20240208T134500Z\r\n"));
+        assert!(output.contains("DTSTART;VALUE=DATE:20240210\r\nDTEND;VALUE=DATE:20240211\r\n"));
+        assert!(output.contains("SUMMARY:Play Contra\\, the arcade port\r\n"));
+        assert!(output.contains("DESCRIPTION:About 1h\\nhttps://howlongtobeat.com/game/1\r\n"));
+        assert!(output.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn test_fold_lines() {
+        let mut output = Vec::new();
+        write_line(&mut output, &format!("SUMMARY:{}", "é".repeat(60))).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.split("\r\n").collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|line| line.len() <= 75));
+        assert_eq!(
+            output.replace("\r\n ", ""),
+            format!("SUMMARY:{}\r\n", "é".repeat(60))
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! JSON Lines export.
+//!
+//! Games are appended one JSON object per line as they are resolved, so a long
+//! crawl never holds its results in memory and can resume after an interruption
+//! by skipping the IDs already in the file.
+
+use crate::Game;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::Path;
+
+pub struct JsonlExporter {
+    file: File,
+}
+
+impl JsonlExporter {
+    /// Opens a `.jsonl` file for appending, creating it if needed
+    ///
+    /// # Arguments
+    ///
+    /// * `path`:  &Path - The file to append to
+    ///
+    /// returns: Result<JsonlExporter, Box<dyn Error, Global>>
+    pub fn open(path: &Path) -> Result<JsonlExporter, Box<dyn Error>> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(JsonlExporter { file })
+    }
+
+    /// Appends a game as a single line, written to the file immediately
+    ///
+    /// # Arguments
+    ///
+    /// * `game`:  &Game - The resolved game
+    ///
+    /// returns: Result<(), Box<dyn Error, Global>>
+    pub fn write(&mut self, game: &Game) -> Result<(), Box<dyn Error>> {
+        let mut line = serde_json::to_vec(game)?;
+        line.push(b'\n');
+        self.file.write_all(&line)?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+/// Lists the IDs of the games already exported to a `.jsonl` file, to resume a crawl
+///
+/// A missing file has no games, and lines that don't parse (such as a line cut short by an
+/// interruption) are ignored.
+///
+/// # Arguments
+///
+/// * `path`:  &Path - The exported file
+///
+/// returns: Result<HashSet<u32>, Box<dyn Error, Global>>
+pub fn exported_ids(path: &Path) -> Result<HashSet<u32>, Box<dyn Error>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(HashSet::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut ids = HashSet::new();
+    for line in BufReader::new(file).lines() {
+        if let Ok(game) = serde_json::from_str::<Game>(&line?) {
+            ids.insert(game.hltb_id);
+        }
+    }
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_and_resume() {
+        let path = std::env::temp_dir().join("hltb_jsonl_test.jsonl");
+        let _ = std::fs::remove_file(&path);
+        assert!(exported_ids(&path).unwrap().is_empty());
+
+        let game = |title: &str, hltb_id| {
+            Game::new(
+                title.to_string(),
+                hltb_id,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+        };
+        JsonlExporter::open(&path)
+            .unwrap()
+            .write(&game("Metal Gear", 5900))
+            .unwrap();
+        let mut exporter = JsonlExporter::open(&path).unwrap();
+        exporter.write(&game("Helldivers 2", 129232)).unwrap();
+        exporter.file.write_all(b"{\"hltb_id\": 1, \"tit").unwrap();
+
+        let ids = exported_ids(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(ids, HashSet::from([5900, 129232]));
+    }
+}
@@ -0,0 +1,115 @@
+//! Lutris export.
+//!
+//! Lutris identifies games by slug, so every entry is keyed by the slug derived
+//! from the title the same way Lutris does (lowercase, words joined by dashes).
+
+use crate::{Game, Styles};
+use serde::Serialize;
+use std::error::Error;
+use std::io::Write;
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct LutrisEntry {
+    pub slug: String,
+    pub name: String,
+    pub hltb_id: u32,
+    pub main_story_hours: Option<f32>,
+    pub main_extra_hours: Option<f32>,
+    pub completionist_hours: Option<f32>,
+    pub all_styles_hours: Option<f32>,
+}
+
+/// Converts a title to a Lutris slug
+///
+/// # Arguments
+///
+/// * `title`:  &str - The title of the game
+///
+/// returns: String
+pub fn slugify(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Returns the average time of a play style in hours, rounded to one decimal
+///
+/// # Arguments
+///
+/// * `styles`:  &Option<Styles> - The play style times
+///
+/// returns: Option<f32>
+fn average_hours(styles: &Option<Styles>) -> Option<f32> {
+    styles
+        .as_ref()
+        .and_then(|s| s.average)
+        .map(|seconds| (seconds / 360.0).round() / 10.0)
+}
+
+/// Builds the Lutris entry of a game
+///
+/// # Arguments
+///
+/// * `game`:  &Game - The resolved game
+///
+/// returns: LutrisEntry
+pub fn to_lutris(game: &Game) -> LutrisEntry {
+    LutrisEntry {
+        slug: slugify(&game.title),
+        name: game.title.clone(),
+        hltb_id: game.hltb_id,
+        main_story_hours: average_hours(&game.main_story),
+        main_extra_hours: average_hours(&game.main_extra),
+        completionist_hours: average_hours(&game.completionist),
+        all_styles_hours: average_hours(&game.all_styles),
+    }
+}
+
+/// Writes the Lutris entries of several games as a JSON array
+///
+/// # Arguments
+///
+/// * `games`:  &[Game] - The resolved games
+/// * `writer`:  W - Where to write the JSON
+///
+/// returns: Result<(), Box<dyn Error, Global>>
+pub fn export_lutris<W: Write>(games: &[Game], writer: W) -> Result<(), Box<dyn Error>> {
+    let entries: Vec<LutrisEntry> = games.iter().map(to_lutris).collect();
+    serde_json::to_writer_pretty(writer, &entries)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(
+            slugify("The Witcher 3: Wild Hunt"),
+            "the-witcher-3-wild-hunt"
+        );
+        assert_eq!(slugify("  Half-Life 2 "), "half-life-2");
+    }
+
+    #[test]
+    fn test_to_lutris() {
+        let game = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            Some(Styles::new(Some(15000.0), None, None, None)),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let entry = to_lutris(&game);
+        assert_eq!(entry.slug, "metal-gear");
+        assert_eq!(entry.main_story_hours, Some(4.2));
+        assert_eq!(entry.main_extra_hours, None);
+    }
+}
@@ -0,0 +1,26 @@
+//! Writers that turn resolved games into formats consumed by other tools.
+
+pub mod csv;
+pub mod ical;
+pub mod jsonl;
+pub mod lutris;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+pub mod playnite;
+pub mod timeseries;
+
+use crate::Styles;
+
+/// Returns the average time of a play style in whole seconds
+///
+/// # Arguments
+///
+/// * `styles`:  &Option<Styles> - The play style times
+///
+/// returns: Option<u64>
+fn average_seconds(styles: &Option<Styles>) -> Option<u64> {
+    styles
+        .as_ref()
+        .and_then(|s| s.average)
+        .map(|seconds| seconds.round() as u64)
+}
@@ -0,0 +1,122 @@
+//! Parquet export.
+//!
+//! Games are flattened into the same columns as the CSV export, with times as
+//! nullable 32-bit floats (seconds), so the file loads straight into pandas or
+//! polars.
+
+use crate::export::csv::{GameRecord, CSV_COLUMNS};
+use crate::Game;
+use arrow_array::{ArrayRef, Float32Array, Int32Array, RecordBatch, StringArray, UInt32Array};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+use std::error::Error;
+use std::io::Write;
+use std::sync::Arc;
+
+/// Builds an Arrow record batch of several games, one row per game
+///
+/// # Arguments
+///
+/// * `games`:  &[Game] - The resolved games
+///
+/// returns: Result<RecordBatch, Box<dyn Error, Global>>
+pub fn to_record_batch(games: &[Game]) -> Result<RecordBatch, Box<dyn Error>> {
+    let records: Vec<GameRecord> = games.iter().map(GameRecord::from).collect();
+    let mut fields = vec![
+        Field::new(CSV_COLUMNS[0], DataType::UInt32, false),
+        Field::new(CSV_COLUMNS[1], DataType::Utf8, false),
+        Field::new(CSV_COLUMNS[2], DataType::Utf8, false),
+        Field::new(CSV_COLUMNS[3], DataType::Int32, true),
+    ];
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt32Array::from_iter_values(
+            records.iter().map(|r| r.hltb_id),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            records.iter().map(|r| r.title.as_str()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            records.iter().map(|r| r.platforms.as_str()),
+        )),
+        Arc::new(Int32Array::from(
+            records.iter().map(|r| r.release_year).collect::<Vec<_>>(),
+        )),
+    ];
+    let times: Vec<[Option<f32>; 24]> = records.iter().map(GameRecord::times).collect();
+    for (i, name) in CSV_COLUMNS[4..].iter().enumerate() {
+        fields.push(Field::new(*name, DataType::Float32, true));
+        columns.push(Arc::new(Float32Array::from(
+            times.iter().map(|t| t[i]).collect::<Vec<_>>(),
+        )));
+    }
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new(fields)),
+        columns,
+    )?)
+}
+
+/// Writes several games as a Parquet file
+///
+/// # Arguments
+///
+/// * `games`:  &[Game] - The resolved games
+/// * `writer`:  W - Where to write the Parquet file
+///
+/// returns: Result<(), Box<dyn Error, Global>>
+pub fn export_parquet<W: Write + Send>(games: &[Game], writer: W) -> Result<(), Box<dyn Error>> {
+    let batch = to_record_batch(games)?;
+    let mut writer = ArrowWriter::try_new(writer, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Styles;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::fs::File;
+
+    #[test]
+    fn test_export_parquet() {
+        let game = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            Some(Styles::new(Some(15000.0), None, None, None)),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let path = std::env::temp_dir().join("hltb_parquet_test.parquet");
+        export_parquet(&[game], File::create(&path).unwrap()).unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|b| b.unwrap()).collect();
+        std::fs::remove_file(&path).unwrap();
+
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 1);
+        assert_eq!(batch.num_columns(), CSV_COLUMNS.len());
+        let titles = batch
+            .column_by_name("title")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(titles.value(0), "Metal Gear");
+        let main_story = batch
+            .column_by_name("main_story_average")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float32Array>()
+            .unwrap();
+        assert_eq!(main_story.value(0), 15000.0);
+        assert!(batch.column_by_name("vs_average").unwrap().is_null(0));
+    }
+}
@@ -0,0 +1,116 @@
+//! Playnite extension data export.
+//!
+//! Writes one JSON file per game in the layout used by the HowLongToBeat Playnite
+//! extension (`ExtensionsData/<extension id>/HowLongToBeat/<game id>.json`), so the
+//! files can be dropped into the Playnite data directory as-is.
+
+use super::average_seconds;
+use crate::{Game, BASE_URL};
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PlayniteHltbData {
+    pub main_story: u64,
+    pub main_extra: u64,
+    pub completionist: u64,
+    pub solo: u64,
+    pub co_op: u64,
+    pub vs: u64,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PlayniteHltbItem {
+    pub name: String,
+    pub id: String,
+    pub url: String,
+    pub game_hltb_data: PlayniteHltbData,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PlayniteGameData {
+    pub id: String,
+    pub name: String,
+    pub items: Vec<PlayniteHltbItem>,
+}
+
+/// Builds the Playnite extension data of a game
+///
+/// # Arguments
+///
+/// * `playnite_id`:  &str - The ID of the game in the Playnite library
+/// * `game`:  &Game - The resolved game
+///
+/// returns: PlayniteGameData
+pub fn to_playnite(playnite_id: &str, game: &Game) -> PlayniteGameData {
+    PlayniteGameData {
+        id: playnite_id.to_string(),
+        name: game.title.clone(),
+        items: vec![PlayniteHltbItem {
+            name: game.title.clone(),
+            id: game.hltb_id.to_string(),
+            url: format!("{}game/{}", BASE_URL, game.hltb_id),
+            game_hltb_data: PlayniteHltbData {
+                main_story: average_seconds(&game.main_story).unwrap_or(0),
+                main_extra: average_seconds(&game.main_extra).unwrap_or(0),
+                completionist: average_seconds(&game.completionist).unwrap_or(0),
+                solo: average_seconds(&game.all_styles).unwrap_or(0),
+                co_op: average_seconds(&game.co_op).unwrap_or(0),
+                vs: average_seconds(&game.vs).unwrap_or(0),
+            },
+        }],
+    }
+}
+
+/// Writes the Playnite extension data of several games into a directory
+///
+/// # Arguments
+///
+/// * `entries`:  &[(&str, &Game)] - The Playnite game IDs paired with their resolved game
+/// * `dir`:  &Path - The `HowLongToBeat` extension data directory
+///
+/// returns: Result<(), Box<dyn Error, Global>>
+pub fn export_playnite(entries: &[(&str, &Game)], dir: &Path) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(dir)?;
+    for (playnite_id, game) in entries {
+        let data = to_playnite(playnite_id, game);
+        let path = dir.join(format!("{}.json", playnite_id));
+        fs::write(path, serde_json::to_string_pretty(&data)?)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Styles;
+
+    #[test]
+    fn test_to_playnite() {
+        let game = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            Some(Styles::new(Some(15000.0), None, None, None)),
+            None,
+            None,
+            Some(Styles::new(Some(16440.4), None, None, None)),
+            None,
+            None,
+        );
+        let data = to_playnite("c5b2e4d2-51b0-4f4d-9c8e-3a8d1f2b7e10", &game);
+        let item = &data.items[0];
+        assert_eq!(item.id, "5900");
+        assert_eq!(item.url, "https://howlongtobeat.com/game/5900");
+        assert_eq!(item.game_hltb_data.main_story, 15000);
+        assert_eq!(item.game_hltb_data.solo, 16440);
+        assert_eq!(item.game_hltb_data.co_op, 0);
+
+        let json = serde_json::to_value(&data).unwrap();
+        assert_eq!(json["Items"][0]["GameHltbData"]["MainStory"], 15000);
+    }
+}
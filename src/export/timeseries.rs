@@ -0,0 +1,153 @@
+//! Time series export of the history of games.
+//!
+//! The snapshots kept by a [`HistoryStore`](crate::history::HistoryStore) are
+//! flattened into one [`TimePoint`] per snapshot and play style, in long format,
+//! so plotting tools can draw one line per game and style straight from the CSV
+//! or JSON output. Dates are RFC 3339 timestamps and times are in seconds.
+
+use super::csv::write_line;
+use crate::repr::format_timestamp;
+use crate::Game;
+use serde::Serialize;
+use std::error::Error;
+use std::io::Write;
+
+/// The header of the CSV time series, in column order
+pub const TIME_SERIES_COLUMNS: [&str; 5] = ["hltb_id", "date", "style", "average", "median"];
+
+/// The times of a play style in one snapshot of a game
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct TimePoint {
+    pub hltb_id: u32,
+    /// When the snapshot was fetched
+    pub date: String,
+    /// The play style, e.g. "main_story"
+    pub style: String,
+    pub average: Option<f32>,
+    pub median: Option<f32>,
+}
+
+/// Flattens snapshots of games into time points, in the order of the snapshots then of the
+/// play styles
+///
+/// Snapshots without a fetch date are skipped, and so are the play styles without times.
+///
+/// # Arguments
+///
+/// * `snapshots`:  &[Game] - The snapshots, e.g. from [`HistoryStore::snapshots`](crate::history::HistoryStore::snapshots)
+///
+/// returns: Vec<TimePoint>
+pub fn time_points(snapshots: &[Game]) -> Vec<TimePoint> {
+    let mut points = Vec::new();
+    for game in snapshots {
+        let Some(fetched_at) = game.fetched_at else {
+            continue;
+        };
+        let styles = [
+            ("main_story", &game.main_story),
+            ("main_extra", &game.main_extra),
+            ("completionist", &game.completionist),
+            ("all_styles", &game.all_styles),
+            ("co_op", &game.co_op),
+            ("vs", &game.vs),
+        ];
+        for (style, times) in styles {
+            let Some(times) = times else {
+                continue;
+            };
+            points.push(TimePoint {
+                hltb_id: game.hltb_id,
+                date: format_timestamp(fetched_at),
+                style: style.to_string(),
+                average: times.average,
+                median: times.median,
+            });
+        }
+    }
+    points
+}
+
+/// Writes time points as CSV, with a header line followed by one line per point
+///
+/// # Arguments
+///
+/// * `points`:  &[TimePoint] - The time points
+/// * `writer`:  W - Where to write the CSV
+///
+/// returns: Result<(), Box<dyn Error, Global>>
+pub fn export_time_series_csv<W: Write>(
+    points: &[TimePoint],
+    mut writer: W,
+) -> Result<(), Box<dyn Error>> {
+    let time = |t: Option<f32>| t.map(|t| t.to_string()).unwrap_or_default();
+    write_line(&mut writer, &TIME_SERIES_COLUMNS)?;
+    for point in points {
+        let fields = [
+            point.hltb_id.to_string(),
+            point.date.clone(),
+            point.style.clone(),
+            time(point.average),
+            time(point.median),
+        ];
+        write_line(&mut writer, &fields)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes time points as a JSON array
+///
+/// # Arguments
+///
+/// * `points`:  &[TimePoint] - The time points
+/// * `writer`:  W - Where to write the JSON
+///
+/// returns: Result<(), Box<dyn Error, Global>>
+pub fn export_time_series_json<W: Write>(
+    points: &[TimePoint],
+    mut writer: W,
+) -> Result<(), Box<dyn Error>> {
+    serde_json::to_writer(&mut writer, points)?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Styles;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_export_time_series() {
+        let mut game = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            Some(Styles::new(Some(15000.0), Some(14400.0), None, None)),
+            None,
+            Some(Styles::new(Some(19800.0), None, None, None)),
+            None,
+            None,
+            None,
+        );
+        let undated = game.clone();
+        game.fetched_at = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_707_399_900));
+        let points = time_points(&[undated, game]);
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[1].style, "completionist");
+
+        let mut csv = Vec::new();
+        export_time_series_csv(&points, &mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "hltb_id,date,style,average,median\r\n\
+             5900,2024-02-08T13:45:00Z,main_story,15000,14400\r\n\
+             5900,2024-02-08T13:45:00Z,completionist,19800,\r\n"
+        );
+        let mut json = Vec::new();
+        export_time_series_json(&points, &mut json).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(json[0]["date"], "2024-02-08T13:45:00Z");
+        assert_eq!(json[1]["median"], serde_json::Value::Null);
+    }
+}
@@ -0,0 +1,41 @@
+//! Fetching of pages, without parsing.
+//!
+//! Looking up a game is two stages: fetching a page, then parsing it with the
+//! [`parse`](crate::parse) module. These functions expose the first stage on its
+//! own, so callers can cache, record or transform the HTML before parsing it:
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let html = howlongtobeat_scraper::fetch::detail_html(5900).await?;
+//! let game = howlongtobeat_scraper::parse::game(&html, 5900)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! They use a client with the default configuration; use the methods of the same
+//! name on [`HltbClient`] for a configured one.
+
+use crate::HltbClient;
+use std::error::Error;
+
+/// Fetches the rendered search page of a name
+///
+/// # Arguments
+///
+/// * `name`:  &str - The name of the game to search for
+///
+/// returns: Result<String, Box<dyn Error, Global>>
+pub async fn search_html(name: &str) -> Result<String, Box<dyn Error>> {
+    HltbClient::builder().build()?.search_html(name).await
+}
+
+/// Fetches the details page of a game
+///
+/// # Arguments
+///
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+///
+/// returns: Result<String, Box<dyn Error, Global>>
+pub async fn detail_html(hltb_id: u32) -> Result<String, Box<dyn Error>> {
+    HltbClient::builder().build()?.detail_html(hltb_id).await
+}
@@ -0,0 +1,92 @@
+//! C-compatible interface returning games as JSON strings.
+//!
+//! Every string returned by this module is owned by the caller and must be
+//! released with [`hltb_string_free`]. Failed lookups return a JSON object of
+//! the form `{"error": "<message>"}` instead of a game. The matching C header is
+//! generated with `cbindgen --config cbindgen.toml --output include/howlongtobeat_scraper.h`.
+
+use crate::{search_by_id_with_sandbox, search_by_name_with_sandbox, Game};
+use std::error::Error;
+use std::ffi::{c_char, CStr, CString};
+use std::future::Future;
+use std::ptr;
+
+/// Runs a lookup to completion and serializes its outcome to a C string
+///
+/// # Arguments
+///
+/// * `lookup`:  F - The lookup to run
+///
+/// returns: *mut c_char
+fn run_to_json<F>(lookup: F) -> *mut c_char
+where
+    F: Future<Output = Result<Game, Box<dyn Error>>>,
+{
+    let result = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| e.into())
+        .and_then(|runtime| runtime.block_on(lookup));
+    let json = match result {
+        Ok(game) => serde_json::to_string(&game).unwrap_or_default(),
+        Err(e) => serde_json::json!({ "error": e.to_string() }).to_string(),
+    };
+    CString::new(json).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Searches for a game by name and returns it as JSON
+///
+/// Returns NULL if `name` is NULL or not valid UTF-8.
+///
+/// # Safety
+///
+/// `name` must be NULL or point to a NUL-terminated string that stays valid for the duration of the call.
+#[no_mangle]
+pub unsafe extern "C" fn hltb_search_by_name(name: *const c_char, sandbox: bool) -> *mut c_char {
+    if name.is_null() {
+        return ptr::null_mut();
+    }
+    match CStr::from_ptr(name).to_str() {
+        Ok(name) => run_to_json(search_by_name_with_sandbox(name, sandbox)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Fetches a game by its How Long to Beat ID and returns it as JSON
+#[no_mangle]
+pub extern "C" fn hltb_search_by_id(hltb_id: u32, sandbox: bool) -> *mut c_char {
+    run_to_json(search_by_id_with_sandbox(hltb_id, sandbox))
+}
+
+/// Releases a string returned by this library
+///
+/// # Safety
+///
+/// `s` must be NULL or a pointer returned by this library that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn hltb_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_to_json_error() {
+        let json = run_to_json(async { Err("Element not found".into()) });
+        let text = unsafe { CStr::from_ptr(json) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        unsafe { hltb_string_free(json) };
+        assert_eq!(text, r#"{"error":"Element not found"}"#);
+    }
+
+    #[test]
+    fn test_search_by_name_null() {
+        assert!(unsafe { hltb_search_by_name(ptr::null(), true) }.is_null());
+    }
+}
@@ -0,0 +1,208 @@
+//! YAML, TOML and binary serialization of games.
+//!
+//! Each format is behind its own feature flag (`yaml`, `toml`, `bincode`,
+//! `msgpack`). In the text formats a catalog is written as a `games` list, since
+//! TOML documents must be tables at the top level; YAML uses the same layout so
+//! both files read the same.
+//!
+//! The binary formats encode the fields of [`Game`] by position rather than by
+//! name, so the declaration order of its fields is part of the format.
+
+use crate::Game;
+use std::error::Error;
+
+#[cfg(any(feature = "yaml", feature = "toml"))]
+#[derive(serde::Deserialize, serde::Serialize)]
+struct Catalog {
+    #[serde(default)]
+    games: Vec<Game>,
+}
+
+/// Serializes a catalog of games to YAML
+///
+/// # Arguments
+///
+/// * `games`:  &[Game] - The games of the catalog
+///
+/// returns: Result<String, Box<dyn Error, Global>>
+#[cfg(feature = "yaml")]
+pub fn to_yaml(games: &[Game]) -> Result<String, Box<dyn Error>> {
+    let catalog = Catalog {
+        games: games.to_vec(),
+    };
+    Ok(serde_yaml::to_string(&catalog)?)
+}
+
+/// Parses a catalog of games from YAML
+///
+/// # Arguments
+///
+/// * `content`:  &str - The YAML document
+///
+/// returns: Result<Vec<Game>, Box<dyn Error, Global>>
+#[cfg(feature = "yaml")]
+pub fn from_yaml(content: &str) -> Result<Vec<Game>, Box<dyn Error>> {
+    Ok(serde_yaml::from_str::<Catalog>(content)?.games)
+}
+
+/// Serializes a catalog of games to TOML, as an array of `[[games]]` tables
+///
+/// # Arguments
+///
+/// * `games`:  &[Game] - The games of the catalog
+///
+/// returns: Result<String, Box<dyn Error, Global>>
+#[cfg(feature = "toml")]
+pub fn to_toml(games: &[Game]) -> Result<String, Box<dyn Error>> {
+    let catalog = Catalog {
+        games: games.to_vec(),
+    };
+    Ok(toml::to_string(&catalog)?)
+}
+
+/// Parses a catalog of games from TOML
+///
+/// # Arguments
+///
+/// * `content`:  &str - The TOML document
+///
+/// returns: Result<Vec<Game>, Box<dyn Error, Global>>
+#[cfg(feature = "toml")]
+pub fn from_toml(content: &str) -> Result<Vec<Game>, Box<dyn Error>> {
+    Ok(toml::from_str::<Catalog>(content)?.games)
+}
+
+/// Encodes a value with bincode, for compact caches and IPC
+///
+/// # Arguments
+///
+/// * `value`:  &T - The value to encode, e.g. a `Game` or a `Vec<Game>`
+///
+/// returns: Result<Vec<u8>, Box<dyn Error, Global>>
+#[cfg(feature = "bincode")]
+pub fn to_bincode<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, Box<dyn Error>> {
+    Ok(bincode::serialize(value)?)
+}
+
+/// Decodes a value encoded with [`to_bincode`]
+///
+/// # Arguments
+///
+/// * `bytes`:  &[u8] - The encoded value
+///
+/// returns: Result<T, Box<dyn Error, Global>>
+#[cfg(feature = "bincode")]
+pub fn from_bincode<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T, Box<dyn Error>> {
+    Ok(bincode::deserialize(bytes)?)
+}
+
+/// Encodes a value as MessagePack, with structs written as arrays of their fields
+///
+/// # Arguments
+///
+/// * `value`:  &T - The value to encode, e.g. a `Game` or a `Vec<Game>`
+///
+/// returns: Result<Vec<u8>, Box<dyn Error, Global>>
+#[cfg(feature = "msgpack")]
+pub fn to_msgpack<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, Box<dyn Error>> {
+    Ok(rmp_serde::to_vec(value)?)
+}
+
+/// Decodes a value encoded with [`to_msgpack`]
+///
+/// # Arguments
+///
+/// * `bytes`:  &[u8] - The encoded value
+///
+/// returns: Result<T, Box<dyn Error, Global>>
+#[cfg(feature = "msgpack")]
+pub fn from_msgpack<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T, Box<dyn Error>> {
+    Ok(rmp_serde::from_slice(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Platform, Styles};
+
+    fn catalog() -> Vec<Game> {
+        let mut metal_gear = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            Some(Styles::new(
+                Some(15000.0),
+                Some(14400.0),
+                Some(9000.0),
+                None,
+            )),
+            None,
+            Some(Styles::new(Some(21600.0), None, None, None)),
+            None,
+            None,
+            None,
+        );
+        metal_gear.platforms = vec![Platform::Nes, Platform::Msx2];
+        metal_gear.release_year = Some(1987);
+        let helldivers = Game::new(
+            "Helldivers 2".to_string(),
+            129232,
+            None,
+            None,
+            None,
+            None,
+            Some(Styles::new(Some(111600.0), None, None, None)),
+            None,
+        );
+        vec![metal_gear, helldivers]
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml_round_trip() {
+        let games = catalog();
+        let yaml = to_yaml(&games).unwrap();
+        assert!(yaml.contains("title: Metal Gear"));
+        let parsed = from_yaml(&yaml).unwrap();
+        assert_eq!(parsed.len(), games.len());
+        assert!(parsed.iter().zip(&games).all(|(a, b)| a.data_eq(b)));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_round_trip() {
+        let games = catalog();
+        let toml = to_toml(&games).unwrap();
+        assert!(toml.contains("[[games]]"));
+        let parsed = from_toml(&toml).unwrap();
+        assert_eq!(parsed.len(), games.len());
+        assert!(parsed.iter().zip(&games).all(|(a, b)| a.data_eq(b)));
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn test_bincode_round_trip() {
+        let games = catalog();
+        let bytes = to_bincode(&games[0]).unwrap();
+        // hltb_id then the length of the title: the field order is part of the format
+        assert_eq!(bytes[..12], [0x0c, 0x17, 0, 0, 10, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(from_bincode::<Game>(&bytes).unwrap().data_eq(&games[0]));
+        let bytes = to_bincode(&games).unwrap();
+        let parsed = from_bincode::<Vec<Game>>(&bytes).unwrap();
+        assert_eq!(parsed.len(), games.len());
+        assert!(parsed.iter().zip(&games).all(|(a, b)| a.data_eq(b)));
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_msgpack_round_trip() {
+        let games = catalog();
+        let bytes = to_msgpack(&games[0]).unwrap();
+        // An array16 of the 16 fields, starting with hltb_id as a uint16
+        assert_eq!(bytes[..6], [0xdc, 0x00, 0x10, 0xcd, 0x17, 0x0c]);
+        assert!(from_msgpack::<Game>(&bytes).unwrap().data_eq(&games[0]));
+        let bytes = to_msgpack(&games).unwrap();
+        let parsed = from_msgpack::<Vec<Game>>(&bytes).unwrap();
+        assert_eq!(parsed.len(), games.len());
+        assert!(parsed.iter().zip(&games).all(|(a, b)| a.data_eq(b)));
+    }
+}
@@ -0,0 +1,139 @@
+//! Forum threads linked from a game page.
+
+use crate::{fetch_page_content, BASE_URL};
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+use serde::Serialize;
+use std::error::Error;
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone, Default)]
+pub struct ForumThread {
+    pub title: String,
+    /// The absolute URL of the thread
+    pub url: String,
+    pub replies: Option<u32>,
+    /// The absolute URL of the last post of the thread
+    pub last_activity_url: Option<String>,
+}
+
+/// Fetches the discussion threads of a game
+///
+/// # Arguments
+///
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+///
+/// returns: Result<Vec<ForumThread>, Box<dyn Error, Global>>
+pub async fn get_forum_threads_with_sandbox(
+    hltb_id: u32,
+    sandbox: bool,
+) -> Result<Vec<ForumThread>, Box<dyn Error>> {
+    let url = format!("{}game/{}/forum", BASE_URL, hltb_id);
+    let content = fetch_page_content(&url, "main table", sandbox)?;
+    Ok(parse_forum_threads(&content))
+}
+
+/// Fetches the discussion threads of a game (with sandbox enabled by default)
+///
+/// # Arguments
+///
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+///
+/// returns: Result<Vec<ForumThread>, Box<dyn Error, Global>>
+pub async fn get_forum_threads(hltb_id: u32) -> Result<Vec<ForumThread>, Box<dyn Error>> {
+    get_forum_threads_with_sandbox(hltb_id, true).await
+}
+
+/// Returns the absolute URL of the first link of a cell
+///
+/// # Arguments
+///
+/// * `cell`:  ElementRef - The cell
+/// * `selector`:  &Selector - The selector of links
+///
+/// returns: Option<String>
+fn link_url(cell: ElementRef, selector: &Selector) -> Option<String> {
+    let href = cell.select(selector).next()?.value().attr("href")?;
+    if href.starts_with("http") {
+        Some(href.to_string())
+    } else {
+        Some(BASE_URL.to_owned() + href.trim_start_matches('/'))
+    }
+}
+
+/// Parses the threads of a game forum page
+///
+/// Columns are identified by their header, like the game lists of user profiles.
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the forum page
+///
+/// returns: Vec<ForumThread>
+fn parse_forum_threads(content: &str) -> Vec<ForumThread> {
+    let document = Html::parse_document(content);
+    let header_selector = Selector::parse("main table thead th").unwrap();
+    let row_selector = Selector::parse("main table tbody tr").unwrap();
+    let cell_selector = Selector::parse("td").unwrap();
+    let link_selector = Selector::parse("a[href]").unwrap();
+
+    let headers: Vec<String> = document
+        .select(&header_selector)
+        .map(|th| th.text().collect::<String>().trim().to_lowercase())
+        .collect();
+
+    let mut threads = Vec::new();
+    for row in document.select(&row_selector) {
+        let mut thread = ForumThread::default();
+        for (header, cell) in headers.iter().zip(row.select(&cell_selector)) {
+            let text = cell.text().collect::<String>().trim().to_string();
+            match header.as_str() {
+                "topic" | "thread" | "title" => {
+                    thread.title = text;
+                    thread.url = link_url(cell, &link_selector).unwrap_or_default();
+                }
+                "replies" | "posts" => thread.replies = text.replace(',', "").parse().ok(),
+                "last post" | "last activity" | "latest" => {
+                    thread.last_activity_url = link_url(cell, &link_selector)
+                }
+                _ => {}
+            }
+        }
+        if !thread.title.is_empty() && !thread.url.is_empty() {
+            threads.push(thread);
+        }
+    }
+    threads
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_forum_threads() {
+        let content = r#"<html><body><main><table>
+            <thead><tr><th>Topic</th><th>Replies</th><th>Last Post</th></tr></thead>
+            <tbody>
+                <tr>
+                    <td><a href="/forum/thread/812">Is the MSX2 version worth it?</a></td>
+                    <td>1,024</td>
+                    <td><a href="/forum/thread/812/43#post-9910">2 days ago</a></td>
+                </tr>
+                <tr><td>Deleted topic</td><td>0</td><td></td></tr>
+            </tbody>
+        </table></main></body></html>"#;
+        let threads = parse_forum_threads(content);
+        assert_eq!(
+            threads,
+            vec![ForumThread {
+                title: "Is the MSX2 version worth it?".to_string(),
+                url: "https://howlongtobeat.com/forum/thread/812".to_string(),
+                replies: Some(1024),
+                last_activity_url: Some(
+                    "https://howlongtobeat.com/forum/thread/812/43#post-9910".to_string()
+                ),
+            }]
+        );
+    }
+}
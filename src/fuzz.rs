@@ -0,0 +1,105 @@
+//! Generators of page snippets for property testing (`fuzz` feature).
+//!
+//! The parsers must never panic, whatever HTML the site (or a proxy, or a
+//! captive portal) sends back. These [proptest] strategies generate snippets
+//! shaped like the pages of the site, with both plausible and arbitrary times,
+//! so the parsers of [`parse`](crate::parse) can be exercised well beyond the
+//! recorded fixtures, from this crate's tests or from downstream ones. The
+//! `fuzz/` directory holds the matching `cargo fuzz` targets.
+
+use proptest::prelude::*;
+
+/// The labels of the rows of the table of times, plus one the parsers don't know
+const ROW_LABELS: &[&str] = &[
+    "Main Story",
+    "Main + Extra",
+    "Main + Extras",
+    "Completionist",
+    "All PlayStyles",
+    "Co-Op",
+    "Competitive",
+    "Speedrun",
+];
+
+/// Generates the text of a cell of times, e.g. "4h 10m", "59½ Hours", "--" or arbitrary text
+///
+/// returns: impl Strategy<Value = String>
+pub fn time_text() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just("--".to_string()),
+        (0u32..500, 0u32..60).prop_map(|(hours, minutes)| format!("{}h {}m", hours, minutes)),
+        (0u32..60).prop_map(|minutes| format!("{}m", minutes)),
+        (0u32..200, prop::sample::select(vec!["", "½", "¼", "¾"]))
+            .prop_map(|(hours, fraction)| format!("{}{} Hours", hours, fraction)),
+        any::<String>(),
+    ]
+}
+
+/// Generates a row of the table of times, with any number of cells
+///
+/// returns: impl Strategy<Value = String>
+pub fn row_html() -> impl Strategy<Value = String> {
+    (
+        prop::sample::select(ROW_LABELS),
+        prop::collection::vec(time_text(), 0..7),
+    )
+        .prop_map(|(label, cells)| {
+            let cells: String = cells
+                .iter()
+                .map(|cell| format!("<td>{}</td>", cell))
+                .collect();
+            format!("<tr><td>{}</td>{}</tr>", label, cells)
+        })
+}
+
+/// Generates a details page with the structure of the site's, with any title and rows
+///
+/// returns: impl Strategy<Value = String>
+pub fn details_html() -> impl Strategy<Value = String> {
+    ("[^<>]{0,40}", prop::collection::vec(row_html(), 0..8)).prop_map(|(title, rows)| {
+        format!(
+            "<html><body><div id=\"__next\"><div><main><div><div><div><div>\
+             <div class=\"GameHeader_profile_header__fuzz\">{}</div></div></div></div></div>\
+             <div><div><div class=\"GameSummary_content__fuzz\">\
+             <div class=\"in scrollable scroll_blue shadow_box back_primary\">\
+             <table class=\"GameTimeTable_game_main_table__fuzz\"><tbody>{}</tbody></table>\
+             </div></div></div></div></main></div></div></body></html>",
+            title,
+            rows.concat()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{self, ParseMode};
+
+    proptest! {
+        #[test]
+        fn time_cells_never_panic(text in time_text()) {
+            let _ = parse::time_cell(&text);
+        }
+
+        #[test]
+        fn rows_never_panic(row in row_html()) {
+            let _ = parse::time_row(&row, ParseMode::Strict);
+            let _ = parse::time_row(&row, ParseMode::Lenient);
+        }
+
+        #[test]
+        fn details_pages_never_panic(html in details_html(), hltb_id in any::<u32>()) {
+            let _ = parse::game_with_mode(&html, hltb_id, ParseMode::Strict);
+            if let Ok(game) = parse::game_with_mode(&html, hltb_id, ParseMode::Lenient) {
+                prop_assert_eq!(game.hltb_id, hltb_id);
+            }
+        }
+
+        #[test]
+        fn arbitrary_html_never_panics(html in any::<String>()) {
+            let _ = parse::game(&html, 1);
+            let _ = parse::search_results(&html);
+            let _ = parse::next_data(&html);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Cleanup of the browsers launched by the crate.
+//!
+//! A [`BrowserGuard`] owns a launched browser and its tab. Dropping it, whether the
+//! lookup succeeded, failed, panicked or was cancelled, closes the tab and shuts the
+//! browser down, killing its process if it doesn't exit in time, so long-running
+//! applications don't accumulate Chrome processes.
+
+use headless_chrome::{Browser, Tab};
+use std::ops::Deref;
+use std::process::Command;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+pub(crate) struct BrowserGuard {
+    browser: Option<Browser>,
+    tab: Arc<Tab>,
+    /// How long the browser gets to exit before its process is killed
+    shutdown_timeout: Duration,
+}
+
+impl BrowserGuard {
+    /// Takes ownership of a browser and its tab
+    ///
+    /// # Arguments
+    ///
+    /// * `browser`:  Browser - The launched browser
+    /// * `tab`:  Arc<Tab> - The tab opened in the browser
+    /// * `shutdown_timeout`:  Duration - How long the browser gets to exit before its process is killed
+    ///
+    /// returns: BrowserGuard
+    pub(crate) fn new(browser: Browser, tab: Arc<Tab>, shutdown_timeout: Duration) -> BrowserGuard {
+        BrowserGuard {
+            browser: Some(browser),
+            tab,
+            shutdown_timeout,
+        }
+    }
+}
+
+impl Deref for BrowserGuard {
+    type Target = Tab;
+
+    fn deref(&self) -> &Tab {
+        &self.tab
+    }
+}
+
+impl Drop for BrowserGuard {
+    fn drop(&mut self) {
+        // Without unload handlers, which a broken page could keep from returning
+        let _ = self.tab.close(false);
+        let Some(browser) = self.browser.take() else {
+            return;
+        };
+        let pid = browser.get_process_id();
+        // Dropping the browser stops its process, but may hang on a stuck connection
+        let (done, exited) = mpsc::channel();
+        thread::spawn(move || {
+            drop(browser);
+            let _ = done.send(());
+        });
+        if exited.recv_timeout(self.shutdown_timeout).is_err() {
+            if let Some(pid) = pid {
+                kill_process(pid);
+            }
+        }
+    }
+}
+
+/// Kills a process and the processes it started, ignoring failures as they may be gone
+///
+/// The descendants are listed before any is killed, since they are handed over to another
+/// parent as soon as theirs dies. The browser process itself, a child of this one, is then
+/// reaped by the driver, whose connection to the browser drops with it.
+///
+/// # Arguments
+///
+/// * `pid`:  u32 - The ID of the process
+fn kill_process(pid: u32) {
+    #[cfg(windows)]
+    let status = Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &pid.to_string()])
+        .status();
+    #[cfg(not(windows))]
+    let status = {
+        let mut pids = vec![pid];
+        let mut next = 0;
+        while let Some(parent) = pids.get(next).copied() {
+            pids.extend(child_processes(parent));
+            next += 1;
+        }
+        Command::new("kill")
+            .arg("-KILL")
+            .args(pids.iter().map(u32::to_string))
+            .status()
+    };
+    let _ = status;
+}
+
+/// Lists the IDs of the direct children of a process, none if they can't be listed
+///
+/// # Arguments
+///
+/// * `pid`:  u32 - The ID of the process
+///
+/// returns: Vec<u32, Global>
+#[cfg(not(windows))]
+fn child_processes(pid: u32) -> Vec<u32> {
+    Command::new("pgrep")
+        .args(["-P", &pid.to_string()])
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .split_whitespace()
+                .filter_map(|child| child.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kill_process() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        kill_process(child.id());
+        assert!(!child.wait().unwrap().success());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_kill_process_tree() {
+        use std::io::{BufRead, BufReader};
+        let mut child = Command::new("sh")
+            .args(["-c", "sleep 30 & echo $!; wait"])
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let grandchild: u32 = line.trim().parse().unwrap();
+        kill_process(child.id());
+        assert!(!child.wait().unwrap().success());
+        // Gone, or a zombie left for its new parent to reap, once the signal is delivered
+        let killed = || {
+            std::fs::read_to_string(format!("/proc/{}/stat", grandchild))
+                .map_or(true, |stat| stat.contains(") Z "))
+        };
+        for _ in 0..100 {
+            if killed() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(killed());
+    }
+}
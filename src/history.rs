@@ -0,0 +1,147 @@
+//! On-disk history of fetched games.
+//!
+//! Where [`DiskCache`](crate::cache::DiskCache) keeps the latest fetch of a game,
+//! [`HistoryStore`] keeps every one of them, so the evolution of the times can be
+//! studied, e.g. how the estimates of a game settle after its launch. Each game has
+//! a file of its snapshots, one cache entry per line, so snapshots written by older
+//! versions of the crate keep loading.
+
+use crate::cache::{decode_entry, encode_entry};
+use crate::Game;
+use std::error::Error;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Reads a time out of a snapshot
+pub type SnapshotTime = fn(&Game) -> Option<f32>;
+
+pub struct HistoryStore {
+    dir: PathBuf,
+}
+
+impl HistoryStore {
+    /// Creates a store keeping one file of snapshots per game in a directory
+    ///
+    /// # Arguments
+    ///
+    /// * `dir`:  impl Into<PathBuf> - The directory of the store, created on the first write
+    ///
+    /// returns: HistoryStore
+    pub fn new(dir: impl Into<PathBuf>) -> HistoryStore {
+        HistoryStore { dir: dir.into() }
+    }
+
+    fn path(&self, hltb_id: u32) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", hltb_id))
+    }
+
+    /// Appends a snapshot of a game to its history
+    ///
+    /// # Arguments
+    ///
+    /// * `game`:  &Game - The fetched game
+    ///
+    /// returns: Result<(), Box<dyn Error, Global>>
+    pub fn record(&self, game: &Game) -> Result<(), Box<dyn Error>> {
+        fs::create_dir_all(&self.dir)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(game.hltb_id))?;
+        writeln!(file, "{}", encode_entry(game)?)?;
+        Ok(())
+    }
+
+    /// Returns every recorded snapshot of a game, oldest first
+    ///
+    /// Snapshots that can't be decoded are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+    ///
+    /// returns: Vec<Game>
+    pub fn snapshots(&self, hltb_id: u32) -> Vec<Game> {
+        let content = fs::read_to_string(self.path(hltb_id)).unwrap_or_default();
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| decode_entry(line).ok())
+            .collect()
+    }
+
+    /// Returns the time series of a time of a game, oldest first
+    ///
+    /// Snapshots without a fetch date or without the time are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+    /// * `time`:  SnapshotTime - Reads the time out of a snapshot
+    ///
+    /// returns: Vec<(SystemTime, f32)>
+    pub fn series(&self, hltb_id: u32, time: SnapshotTime) -> Vec<(SystemTime, f32)> {
+        self.snapshots(hltb_id)
+            .iter()
+            .filter_map(|game| Some((game.fetched_at?, time(game)?)))
+            .collect()
+    }
+
+    /// Returns the time series of the main story average of a game, in seconds, oldest first
+    ///
+    /// # Arguments
+    ///
+    /// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+    ///
+    /// returns: Vec<(SystemTime, f32)>
+    pub fn history(&self, hltb_id: u32) -> Vec<(SystemTime, f32)> {
+        self.series(hltb_id, |game| game.main_story.as_ref()?.average)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Styles;
+    use std::time::Duration;
+
+    #[test]
+    fn test_history() {
+        let dir = std::env::temp_dir().join("hltb_history_test");
+        let _ = fs::remove_dir_all(&dir);
+        let store = HistoryStore::new(&dir);
+        let snapshot = |days: u64, main_average: Option<f32>| {
+            let mut game = Game::new(
+                "Helldivers 2".to_string(),
+                129232,
+                main_average.map(|average| Styles::new(Some(average), None, None, None)),
+                None,
+                None,
+                None,
+                None,
+                None,
+            );
+            game.fetched_at = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(days * 86400));
+            game
+        };
+        store.record(&snapshot(1, None)).unwrap();
+        store.record(&snapshot(2, Some(36000.0))).unwrap();
+        store.record(&snapshot(9, Some(40000.0))).unwrap();
+        let snapshots = store.snapshots(129232);
+        let history = store.history(129232);
+        let missing = store.snapshots(1);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(snapshots.len(), 3);
+        assert_eq!(
+            history,
+            vec![
+                (snapshot(2, None).fetched_at.unwrap(), 36000.0),
+                (snapshot(9, None).fetched_at.unwrap(), 40000.0),
+            ]
+        );
+        assert!(missing.is_empty());
+    }
+}
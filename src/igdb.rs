@@ -0,0 +1,253 @@
+//! Cross-referencing of How Long to Beat games with IGDB entries.
+//!
+//! IGDB requires a Twitch application client ID and an app access token, see
+//! <https://api-docs.igdb.com/#account-creation>.
+
+use crate::Game;
+use serde::Deserialize;
+use serde::Serialize;
+use std::error::Error;
+
+const IGDB_GAMES_URL: &str = "https://api.igdb.com/v4/games";
+
+#[derive(Debug, Clone)]
+pub struct IgdbCredentials {
+    pub client_id: String,
+    pub access_token: String,
+}
+
+impl IgdbCredentials {
+    /// Creates a new IgdbCredentials struct
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id`:  &str - The Twitch application client ID
+    /// * `access_token`:  &str - The Twitch app access token
+    ///
+    /// returns: IgdbCredentials
+    pub fn new(client_id: &str, access_token: &str) -> IgdbCredentials {
+        IgdbCredentials {
+            client_id: client_id.to_string(),
+            access_token: access_token.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+pub struct IgdbGame {
+    pub id: u64,
+    pub name: String,
+    pub first_release_date: Option<i64>,
+}
+
+impl IgdbGame {
+    /// Returns the year of the first release of the game
+    ///
+    /// returns: Option<i32>
+    pub fn release_year(&self) -> Option<i32> {
+        self.first_release_date.map(year_from_timestamp)
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+pub struct IgdbPair {
+    pub hltb_id: u32,
+    pub igdb_id: u64,
+    /// Whether the release years of both entries agree
+    pub year_matched: bool,
+}
+
+/// Converts a unix timestamp to its UTC calendar year
+///
+/// # Arguments
+///
+/// * `timestamp`:  i64 - Seconds since the unix epoch
+///
+/// returns: i32
+fn year_from_timestamp(timestamp: i64) -> i32 {
+    // Civil-from-days algorithm, see http://howardhinnant.github.io/date_algorithms.html
+    let days = timestamp.div_euclid(86_400) + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let year = year_of_era + era * 400;
+    (if month_index >= 10 { year + 1 } else { year }) as i32
+}
+
+/// Normalizes a title for comparison by keeping only lowercase alphanumeric words
+///
+/// # Arguments
+///
+/// * `title`:  &str - The title to normalize
+///
+/// returns: String
+fn normalize_title(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Searches IGDB for games by name
+///
+/// # Arguments
+///
+/// * `credentials`:  &IgdbCredentials - The IGDB credentials
+/// * `name`:  &str - The name of the game to search for
+///
+/// returns: Result<Vec<IgdbGame>, Box<dyn Error, Global>>
+pub async fn search_igdb(
+    credentials: &IgdbCredentials,
+    name: &str,
+) -> Result<Vec<IgdbGame>, Box<dyn Error>> {
+    let body = format!(
+        "search \"{}\"; fields id,name,first_release_date; limit 20;",
+        name.replace('"', "")
+    );
+    let games = reqwest::Client::new()
+        .post(IGDB_GAMES_URL)
+        .header("Client-ID", &credentials.client_id)
+        .bearer_auth(&credentials.access_token)
+        .body(body)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Vec<IgdbGame>>()
+        .await?;
+    Ok(games)
+}
+
+/// Picks the IGDB entry matching a How Long to Beat game
+///
+/// Candidates must have the same normalized title. If the release year of the game is known,
+/// a candidate released the same year is preferred; otherwise a candidate is only accepted when
+/// it is the single one with that title.
+///
+/// # Arguments
+///
+/// * `game`:  &Game - The How Long to Beat game
+/// * `candidates`:  &[IgdbGame] - The IGDB search results
+///
+/// returns: Option<IgdbPair>
+pub fn match_game(game: &Game, candidates: &[IgdbGame]) -> Option<IgdbPair> {
+    let title = normalize_title(&game.title);
+    let same_title: Vec<&IgdbGame> = candidates
+        .iter()
+        .filter(|c| normalize_title(&c.name) == title)
+        .collect();
+
+    if let Some(year) = game.release_year {
+        if let Some(candidate) = same_title.iter().find(|c| c.release_year() == Some(year)) {
+            return Some(IgdbPair {
+                hltb_id: game.hltb_id,
+                igdb_id: candidate.id,
+                year_matched: true,
+            });
+        }
+    }
+
+    match same_title.as_slice() {
+        [candidate] => Some(IgdbPair {
+            hltb_id: game.hltb_id,
+            igdb_id: candidate.id,
+            year_matched: false,
+        }),
+        _ => None,
+    }
+}
+
+/// Reconciles How Long to Beat games with their IGDB entries
+///
+/// Games without a reliable IGDB match are left out of the result.
+///
+/// # Arguments
+///
+/// * `credentials`:  &IgdbCredentials - The IGDB credentials
+/// * `games`:  &[Game] - The How Long to Beat games to reconcile
+///
+/// returns: Result<Vec<IgdbPair>, Box<dyn Error, Global>>
+pub async fn reconcile(
+    credentials: &IgdbCredentials,
+    games: &[Game],
+) -> Result<Vec<IgdbPair>, Box<dyn Error>> {
+    let mut pairs = Vec::new();
+    for game in games {
+        let candidates = search_igdb(credentials, &game.title).await?;
+        if let Some(pair) = match_game(game, &candidates) {
+            pairs.push(pair);
+        }
+    }
+    Ok(pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: u64, name: &str, first_release_date: Option<i64>) -> IgdbGame {
+        IgdbGame {
+            id,
+            name: name.to_string(),
+            first_release_date,
+        }
+    }
+
+    #[test]
+    fn test_year_from_timestamp() {
+        assert_eq!(year_from_timestamp(0), 1970);
+        assert_eq!(year_from_timestamp(551_404_800), 1987);
+        assert_eq!(year_from_timestamp(1_704_067_199), 2023);
+        assert_eq!(year_from_timestamp(1_704_067_200), 2024);
+    }
+
+    #[test]
+    fn test_match_game_prefers_release_year() {
+        let mut game = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        game.release_year = Some(1987);
+        let candidates = vec![
+            candidate(1, "Metal Gear", Some(1_104_537_600)),
+            candidate(2, "Metal Gear", Some(551_404_800)),
+            candidate(3, "Metal Gear Solid", Some(906_249_600)),
+        ];
+        let pair = match_game(&game, &candidates).unwrap();
+        assert_eq!(pair.igdb_id, 2);
+        assert!(pair.year_matched);
+    }
+
+    #[test]
+    fn test_match_game_ambiguous_without_year() {
+        let game = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let candidates = vec![
+            candidate(1, "Metal Gear", None),
+            candidate(2, "METAL GEAR", None),
+        ];
+        assert_eq!(match_game(&game, &candidates), None);
+        assert_eq!(
+            match_game(&game, &candidates[..1]).map(|p| p.igdb_id),
+            Some(1)
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Console library resolution.
+//!
+//! Console services don't expose a local library, so this adapter works from a
+//! plain list of titles with optional platform hints, as found in
+//! TrueAchievements or PSNProfiles exports.
+
+use crate::{search_by_name_on_platform_with_sandbox, search_by_name_with_sandbox, Game, Platform};
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+pub struct ConsoleTitle {
+    pub title: String,
+    pub platform: Option<Platform>,
+}
+
+impl ConsoleTitle {
+    /// Creates a new ConsoleTitle struct
+    ///
+    /// # Arguments
+    ///
+    /// * `title`:  &str - The title as written in the export
+    /// * `platform`:  Option<&str> - The platform hint as written in the export, if any
+    ///
+    /// returns: ConsoleTitle
+    pub fn new(title: &str, platform: Option<&str>) -> ConsoleTitle {
+        ConsoleTitle {
+            title: title.trim().to_string(),
+            platform: platform
+                .filter(|p| !p.trim().is_empty())
+                .map(Platform::from),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ConsoleMatch {
+    pub entry: ConsoleTitle,
+    pub game: Game,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct UnmatchedTitle {
+    pub entry: ConsoleTitle,
+    pub reason: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Default)]
+pub struct ConsoleLibraryReport {
+    pub matched: Vec<ConsoleMatch>,
+    pub unmatched: Vec<UnmatchedTitle>,
+}
+
+/// Converts a platform hint to the name HLTB uses for the platform
+///
+/// Unknown hints are returned trimmed but otherwise untouched.
+///
+/// # Arguments
+///
+/// * `hint`:  &str - The platform hint (e.g. "PS4", "Xbox Series X|S")
+///
+/// returns: String
+pub fn normalize_platform(hint: &str) -> String {
+    Platform::from(hint).to_string()
+}
+
+/// Resolves a list of console titles on How Long to Beat
+///
+/// When an entry has a platform hint, the game is only matched if HLTB lists it on that platform.
+///
+/// # Arguments
+///
+/// * `entries`:  Vec<ConsoleTitle> - The titles to resolve
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser
+///
+/// returns: ConsoleLibraryReport
+pub async fn resolve_titles(entries: Vec<ConsoleTitle>, sandbox: bool) -> ConsoleLibraryReport {
+    let mut report = ConsoleLibraryReport::default();
+    for entry in entries {
+        let result = match &entry.platform {
+            Some(platform) => {
+                search_by_name_on_platform_with_sandbox(&entry.title, platform, sandbox).await
+            }
+            None => search_by_name_with_sandbox(&entry.title, sandbox).await,
+        };
+        match result {
+            Ok(game) => report.matched.push(ConsoleMatch { entry, game }),
+            Err(e) => report.unmatched.push(UnmatchedTitle {
+                entry,
+                reason: e.to_string(),
+            }),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_platform() {
+        assert_eq!(normalize_platform("PS4"), "PlayStation 4");
+        assert_eq!(normalize_platform(" Xbox Series X|S "), "Xbox Series X/S");
+        assert_eq!(normalize_platform("Nintendo Switch"), "Nintendo Switch");
+    }
+
+    #[test]
+    fn test_console_title_new() {
+        let entry = ConsoleTitle::new(" Bloodborne ", Some("ps4"));
+        assert_eq!(entry.title, "Bloodborne");
+        assert_eq!(entry.platform, Some(Platform::PlayStation4));
+        assert_eq!(ConsoleTitle::new("Halo", Some("  ")).platform, None);
+    }
+}
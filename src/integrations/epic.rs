@@ -0,0 +1,192 @@
+//! Epic Games Launcher library import.
+//!
+//! The launcher writes one `.item` JSON manifest per installed title in its
+//! `Manifests` directory. On Windows this lives under `%PROGRAMDATA%`, on Linux
+//! it lives inside the Wine prefix the launcher was installed into.
+
+use crate::client::is_no_results;
+use crate::{Game, HltbClient};
+use serde::Deserialize;
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MANIFESTS_SUBDIR: &str = "Epic/EpicGamesLauncher/Data/Manifests";
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct EpicManifest {
+    pub display_name: String,
+    pub app_name: String,
+    #[serde(default)]
+    pub main_game_app_name: Option<String>,
+    #[serde(default)]
+    pub install_location: Option<String>,
+    #[serde(default)]
+    pub catalog_namespace: Option<String>,
+    #[serde(default)]
+    pub catalog_item_id: Option<String>,
+    #[serde(default)]
+    pub app_version_string: Option<String>,
+    #[serde(default)]
+    pub app_categories: Vec<String>,
+    #[serde(default, rename = "bIsIncompleteInstall")]
+    pub is_incomplete_install: bool,
+}
+
+impl EpicManifest {
+    /// Whether the manifest describes a playable, fully installed game
+    ///
+    /// DLCs, add-ons and interrupted installs are excluded.
+    ///
+    /// returns: bool
+    pub fn is_installed_game(&self) -> bool {
+        if self.is_incomplete_install {
+            return false;
+        }
+        if let Some(main) = &self.main_game_app_name {
+            if !main.is_empty() && main != &self.app_name {
+                return false;
+            }
+        }
+        self.app_categories.is_empty() || self.app_categories.iter().any(|c| c == "games")
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct EpicGame {
+    pub manifest: EpicManifest,
+    pub game: Option<Game>,
+}
+
+/// Returns the default location of the Epic Games Launcher manifests directory
+///
+/// On Windows this is `%PROGRAMDATA%\Epic\EpicGamesLauncher\Data\Manifests`. On
+/// other platforms the Wine prefix from `WINEPREFIX` (or `~/.wine`) is used.
+///
+/// returns: Option<PathBuf>
+pub fn default_manifests_dir() -> Option<PathBuf> {
+    let program_data = if cfg!(windows) {
+        PathBuf::from(std::env::var_os("PROGRAMDATA")?)
+    } else {
+        let prefix = match std::env::var_os("WINEPREFIX") {
+            Some(prefix) => PathBuf::from(prefix),
+            None => PathBuf::from(std::env::var_os("HOME")?).join(".wine"),
+        };
+        prefix.join("drive_c").join("ProgramData")
+    };
+    Some(program_data.join(MANIFESTS_SUBDIR))
+}
+
+/// Parses the content of a single `.item` manifest
+///
+/// # Arguments
+///
+/// * `content`:  &str - The JSON content of the manifest
+///
+/// returns: Result<EpicManifest, Box<dyn Error, Global>>
+pub fn parse_manifest(content: &str) -> Result<EpicManifest, Box<dyn Error>> {
+    Ok(serde_json::from_str(content)?)
+}
+
+/// Reads every installed game from a manifests directory
+///
+/// Manifests that cannot be parsed are skipped, as are DLCs and incomplete installs.
+///
+/// # Arguments
+///
+/// * `dir`:  &Path - The Epic Games Launcher manifests directory
+///
+/// returns: Result<Vec<EpicManifest>, Box<dyn Error, Global>>
+pub fn read_manifests(dir: &Path) -> Result<Vec<EpicManifest>, Box<dyn Error>> {
+    let mut manifests = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("item") {
+            continue;
+        }
+        let content = fs::read_to_string(&path)?;
+        if let Ok(manifest) = parse_manifest(&content) {
+            if manifest.is_installed_game() {
+                manifests.push(manifest);
+            }
+        }
+    }
+    manifests.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+    Ok(manifests)
+}
+
+/// Enumerates the installed games of a manifests directory and resolves them on How Long to Beat
+///
+/// Titles that cannot be found are returned with `game` set to `None`, other failures (e.g.
+/// no usable backend) stop the resolution. Every title is looked up with the same client.
+///
+/// # Arguments
+///
+/// * `dir`:  &Path - The Epic Games Launcher manifests directory
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser
+///
+/// returns: Result<Vec<EpicGame>, Box<dyn Error, Global>>
+pub async fn resolve_installed_games(
+    dir: &Path,
+    sandbox: bool,
+) -> Result<Vec<EpicGame>, Box<dyn Error>> {
+    let client = HltbClient::builder().sandbox(sandbox).build()?;
+    let mut games = Vec::new();
+    for manifest in read_manifests(dir)? {
+        let game = match client.search_by_name(&manifest.display_name).await {
+            Ok(game) => Some(game),
+            Err(e) if is_no_results(e.as_ref()) => None,
+            Err(e) => return Err(e),
+        };
+        games.push(EpicGame { manifest, game });
+    }
+    Ok(games)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"{
+        "FormatVersion": 0,
+        "bIsIncompleteInstall": false,
+        "DisplayName": "Control",
+        "AppName": "Calluna",
+        "MainGameAppName": "Calluna",
+        "InstallLocation": "C:\\Program Files\\Epic Games\\Control",
+        "CatalogNamespace": "calluna",
+        "CatalogItemId": "c6e2ba6bd3f44b6b9a3b1f9a1e2a2a40",
+        "AppVersionString": "1.0.0",
+        "AppCategories": ["public", "games", "applications"]
+    }"#;
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = parse_manifest(MANIFEST).unwrap();
+        assert_eq!(manifest.display_name, "Control");
+        assert_eq!(manifest.app_name, "Calluna");
+        assert!(manifest.is_installed_game());
+    }
+
+    #[test]
+    fn test_dlc_is_not_installed_game() {
+        let mut manifest = parse_manifest(MANIFEST).unwrap();
+        manifest.app_name = "CallunaDLC".to_string();
+        assert!(!manifest.is_installed_game());
+    }
+
+    #[test]
+    fn test_read_manifests() {
+        let dir = std::env::temp_dir().join("hltb_epic_manifests_test");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("control.item"), MANIFEST).unwrap();
+        fs::write(dir.join("broken.item"), "{").unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+        let manifests = read_manifests(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(manifests.len(), 1);
+        assert_eq!(manifests[0].display_name, "Control");
+    }
+}
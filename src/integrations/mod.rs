@@ -0,0 +1,5 @@
+//! Adapters that read game libraries from third-party launchers and stores
+//! and resolve their titles against How Long to Beat.
+
+pub mod console;
+pub mod epic;
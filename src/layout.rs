@@ -0,0 +1,204 @@
+//! Fingerprints of the site's frontend.
+//!
+//! The site is a Next.js application whose markup uses CSS modules: classes are
+//! named after their component, e.g. `GameSummary_profile_info__a1b2c`, the
+//! suffix changing with every build. A [`LayoutFingerprint`] records the build ID
+//! of a page and the components it is made of, so it can be stored along with the
+//! results and compared later: a new build ID means the site was redeployed, and
+//! components that appeared or vanished mean the redeployment changed the
+//! structure the parsers rely on. [`LayoutFingerprint::check`] compares a page
+//! with the components the parsers were written for.
+
+use crate::parse::next_data;
+use scraper::{Html, Selector};
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// The components of a search page the parsers read
+pub const SEARCH_COMPONENTS: &[&str] = &["GameCard_search_list_image"];
+
+/// The components of a details page the parsers read
+pub const DETAILS_COMPONENTS: &[&str] = &[
+    "GameHeader_profile_header",
+    "GameSummary_content",
+    "GameSummary_profile_info",
+    "GameTimeTable_game_main_table",
+];
+
+/// The build and the components of a page of the site
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct LayoutFingerprint {
+    /// The ID of the Next.js build that rendered the page, if it has one
+    pub build_id: Option<String>,
+    /// The CSS module components of the page, e.g. "GameSummary_profile_info"
+    pub components: BTreeSet<String>,
+}
+
+/// How the layout of a page differs from a reference
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LayoutWarning {
+    /// Whether the page comes from another build than the reference
+    pub build_changed: bool,
+    /// The components of the reference missing from the page
+    pub missing: Vec<String>,
+    /// The components of the page the reference doesn't have
+    pub added: Vec<String>,
+}
+
+impl fmt::Display for LayoutWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut changes = Vec::new();
+        if self.build_changed {
+            changes.push("the site was redeployed".to_string());
+        }
+        if !self.missing.is_empty() {
+            changes.push(format!("missing {}", self.missing.join(", ")));
+        }
+        if !self.added.is_empty() {
+            changes.push(format!("new {}", self.added.join(", ")));
+        }
+        write!(f, "Layout changed: {}", changes.join("; "))
+    }
+}
+
+/// Computes the fingerprint of a page
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the page
+///
+/// returns: LayoutFingerprint
+pub fn fingerprint(content: &str) -> LayoutFingerprint {
+    let document = Html::parse_document(content);
+    let selector = Selector::parse("[class]").unwrap();
+    let components = document
+        .select(&selector)
+        .flat_map(|element| element.value().classes())
+        .filter_map(component)
+        .collect();
+    LayoutFingerprint {
+        build_id: next_data(content)
+            .and_then(|data| data.get("buildId")?.as_str().map(str::to_string)),
+        components,
+    }
+}
+
+/// Returns the component of a CSS module class, e.g. "GameSummary_profile_info" for
+/// "GameSummary_profile_info__a1b2c", None for other classes
+fn component(class: &str) -> Option<String> {
+    let (name, hash) = class.rsplit_once("__")?;
+    let (module, _) = name.split_once('_')?;
+    let is_module = module.starts_with(|c: char| c.is_ascii_uppercase()) && !hash.is_empty();
+    is_module.then(|| name.to_string())
+}
+
+impl LayoutFingerprint {
+    /// Compares the layout with a previous fingerprint, e.g. one stored along with results
+    ///
+    /// Components that appeared only count when the build changed too, as pages of the same
+    /// build differ by their content.
+    ///
+    /// # Arguments
+    ///
+    /// * `previous`:  &LayoutFingerprint - The reference fingerprint
+    ///
+    /// returns: Option<LayoutWarning> - None when the page has the layout of the reference
+    pub fn compare(&self, previous: &LayoutFingerprint) -> Option<LayoutWarning> {
+        let build_changed = self.build_id.is_some()
+            && previous.build_id.is_some()
+            && self.build_id != previous.build_id;
+        let warning = LayoutWarning {
+            build_changed,
+            missing: previous
+                .components
+                .difference(&self.components)
+                .cloned()
+                .collect(),
+            added: self
+                .components
+                .difference(&previous.components)
+                .cloned()
+                .collect(),
+        };
+        let structural = !warning.missing.is_empty() || !warning.added.is_empty();
+        (warning.build_changed && structural).then_some(warning)
+    }
+
+    /// Compares the layout with the components the parsers were written for
+    ///
+    /// Search pages are told apart from details pages by their components.
+    ///
+    /// returns: Option<LayoutWarning> - None when every component the parsers read is there
+    pub fn check(&self) -> Option<LayoutWarning> {
+        let expected = if self.has_any(SEARCH_COMPONENTS) && !self.has_any(DETAILS_COMPONENTS) {
+            SEARCH_COMPONENTS
+        } else {
+            DETAILS_COMPONENTS
+        };
+        let missing: Vec<String> = expected
+            .iter()
+            .filter(|component| !self.components.contains(**component))
+            .map(|component| component.to_string())
+            .collect();
+        (!missing.is_empty()).then(|| LayoutWarning {
+            missing,
+            ..Default::default()
+        })
+    }
+
+    fn has_any(&self, components: &[&str]) -> bool {
+        components
+            .iter()
+            .any(|component| self.components.contains(*component))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><body><div id="__next">
+        <div class="GameHeader_profile_header__x1 shadow_text">Metal Gear</div>
+        <div class="GameSummary_content__x2"><div class="GameSummary_profile_info__x3">NA: 1987</div>
+        <table class="GameTimeTable_game_main_table__x4"></table></div>
+        <div class="in scrollable back_primary"></div></div>
+        <script id="__NEXT_DATA__" type="application/json">{"buildId":"abc123"}</script>
+        </body></html>"#;
+
+    #[test]
+    fn test_fingerprint() {
+        let layout = fingerprint(PAGE);
+        assert_eq!(layout.build_id.as_deref(), Some("abc123"));
+        assert_eq!(
+            layout.components.iter().collect::<Vec<_>>(),
+            DETAILS_COMPONENTS.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(layout.check(), None);
+        assert_eq!(layout.compare(&layout), None);
+    }
+
+    #[test]
+    fn test_layout_changes() {
+        let previous = fingerprint(PAGE);
+        let redeployed = fingerprint(
+            &PAGE
+                .replace("abc123", "def456")
+                .replace("GameSummary_profile_info", "GameSummary_details"),
+        );
+        let warning = redeployed.compare(&previous).unwrap();
+        assert!(warning.build_changed);
+        assert_eq!(warning.missing, ["GameSummary_profile_info"]);
+        assert_eq!(warning.added, ["GameSummary_details"]);
+        assert_eq!(
+            redeployed.check().unwrap().missing,
+            ["GameSummary_profile_info"]
+        );
+        // A new build with the same structure is not worth a warning
+        assert_eq!(
+            fingerprint(&PAGE.replace("abc123", "def456")).compare(&previous),
+            None
+        );
+    }
+}
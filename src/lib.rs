@@ -1,11 +1,143 @@
-use headless_chrome::{Browser, LaunchOptions};
-use scraper::{ElementRef, Html, Selector};
+#[cfg(feature = "browser")]
+use guard::BrowserGuard;
+#[cfg(feature = "browser")]
+use headless_chrome::{Browser, LaunchOptions, Tab};
 use serde::Deserialize;
 use serde::Serialize;
+#[cfg(any(feature = "browser", feature = "http"))]
 use std::error::Error;
-use urlencoding::encode;
+#[cfg(feature = "browser")]
+use std::ffi::OsStr;
+use std::hash::{Hash, Hasher};
+use std::iter::Sum;
+use std::ops::Add;
+#[cfg(feature = "browser")]
+use std::path::PathBuf;
+#[cfg(feature = "browser")]
+use std::sync::Arc;
+use std::time::SystemTime;
+#[cfg(feature = "browser")]
+use std::time::{Duration, Instant};
+#[cfg(feature = "browser")]
+use timings::Timings;
+#[cfg(any(feature = "browser", feature = "http"))]
+pub use tokio_util::sync::CancellationToken;
+
+pub mod auth;
+#[cfg(feature = "browser")]
+pub mod browse;
+pub mod cache;
+#[cfg(feature = "browser")]
+pub mod charts;
+#[cfg(any(feature = "browser", feature = "http"))]
+pub mod client;
+pub mod delta;
+pub mod distribution;
+pub mod drift;
+pub mod error;
+pub mod export;
+#[cfg(any(feature = "browser", feature = "http"))]
+pub mod fetch;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(any(
+    feature = "yaml",
+    feature = "toml",
+    feature = "bincode",
+    feature = "msgpack"
+))]
+pub mod formats;
+#[cfg(feature = "browser")]
+pub mod forum;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+#[cfg(feature = "browser")]
+mod guard;
+pub mod history;
+#[cfg(feature = "igdb")]
+pub mod igdb;
+#[cfg(any(feature = "browser", feature = "http"))]
+pub mod integrations;
+pub mod layout;
+pub mod local;
+#[cfg(feature = "node")]
+pub mod node;
+pub mod overrides;
+pub mod pace;
+pub mod parse;
+pub mod platform;
+pub mod prelude;
+pub mod query;
+pub mod release;
+pub mod report;
+pub mod repr;
+#[cfg(feature = "browser")]
+pub mod reviews;
+#[cfg(feature = "serve")]
+pub mod serve;
+#[cfg(feature = "browser")]
+pub mod spotlight;
+#[cfg(feature = "browser")]
+pub mod submit;
+pub mod timings;
+#[cfg(feature = "browser")]
+pub mod trending;
+#[cfg(feature = "browser")]
+pub mod user;
+#[cfg(feature = "http")]
+pub mod watch;
+
+#[cfg(feature = "browser")]
+pub use auth::login;
+pub use auth::Session;
+#[cfg(feature = "browser")]
+pub use browse::{
+    get_platform_games, get_platform_games_with_sandbox, get_platform_page,
+    get_platform_page_with_sandbox,
+};
+#[cfg(feature = "browser")]
+pub use charts::{get_chart, get_chart_with_sandbox, ChartEntry, ChartKind, ChartPeriod};
+#[cfg(all(feature = "audit", any(feature = "browser", feature = "http")))]
+pub use client::read_audit_log;
+#[cfg(feature = "browser")]
+pub use client::HltbSession;
+#[cfg(all(feature = "test-util", any(feature = "browser", feature = "http")))]
+pub use client::MockBackend;
+#[cfg(any(feature = "browser", feature = "http"))]
+pub use client::{
+    global, AmbiguousTitle, AuditEntry, AuditOutcome, Backend, BacklogEntry, BacklogItem,
+    BacklogReport, BatchProgress, BatchReport, Candidate, ClientConfig, GamePage, HltbClient,
+    HltbClientBuilder, LaunchProfile, Observer, PageKind, PlannedRequest, SearchRequest,
+    UnmatchedTitle, VcrMode, WaitStrategy,
+};
+pub use delta::GameDelta;
+pub use error::{BackendAttempt, HltbError};
+#[cfg(feature = "browser")]
+pub use forum::{get_forum_threads, get_forum_threads_with_sandbox, ForumThread};
+pub use parse::SearchResult;
+pub use platform::Platform;
+pub use query::{MatchInfo, MatchStrategy, ResolvedGame, ResultGroup, SearchQuery, SortBy};
+#[cfg(feature = "browser")]
+pub use reviews::{
+    get_review_breakdown, get_review_breakdown_with_sandbox, get_reviews, get_reviews_with_sandbox,
+    Review,
+};
+#[cfg(feature = "browser")]
+pub use spotlight::{get_spotlight, get_spotlight_with_sandbox, HomeModule, SpotlightGame};
+#[cfg(feature = "browser")]
+pub use submit::{add_to_backlog, set_game_list, submit_time};
+#[cfg(feature = "browser")]
+pub use trending::{get_trending, get_trending_with_sandbox, TrendingGame};
+#[cfg(feature = "browser")]
+pub use user::{
+    get_user, get_user_completions, get_user_completions_with_sandbox,
+    get_user_completions_with_session, get_user_list, get_user_list_with_sandbox,
+    get_user_list_with_session, get_user_with_sandbox, get_user_with_session, Completion, GameList,
+    ListEntry, UserProfile,
+};
 
 #[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
 pub struct Styles {
     pub average: Option<f32>,
     pub median: Option<f32>,
@@ -38,6 +170,73 @@ impl Styles {
         }
     }
 
+    /// Returns one of the times
+    ///
+    /// # Arguments
+    ///
+    /// * `kind`:  EstimateKind - Which time to return
+    ///
+    /// returns: Option<f32> - The time in seconds
+    pub fn get(&self, kind: EstimateKind) -> Option<f32> {
+        match kind {
+            EstimateKind::Average => self.average,
+            EstimateKind::Median => self.median,
+            EstimateKind::Rushed => self.rushed,
+            EstimateKind::Leisure => self.leisure,
+        }
+    }
+
+    /// Estimates how long the given share of players takes, e.g. 0.8 for the time 80% of
+    /// players finish within
+    ///
+    /// This is an approximation: the site only gives summary times, so the rushed and leisure
+    /// times are taken as the 10th and 90th percentiles and the median (or the average when
+    /// there is none) as the 50th, the time being interpolated linearly between them and
+    /// extrapolated beyond them.
+    ///
+    /// # Arguments
+    ///
+    /// * `p`:  f32 - The percentile, from 0.0 to 1.0
+    ///
+    /// returns: Option<f32> - The time in seconds, None if p is out of range or fewer than two
+    /// of the times are known
+    pub fn percentile_estimate(&self, p: f32) -> Option<f32> {
+        if !(0.0..=1.0).contains(&p) {
+            return None;
+        }
+        let anchors: Vec<(f32, f32)> = [
+            (0.1, self.rushed),
+            (0.5, self.median.or(self.average)),
+            (0.9, self.leisure),
+        ]
+        .into_iter()
+        .filter_map(|(percentile, time)| Some((percentile, time?)))
+        .collect();
+        // The segment around the percentile, or the nearest one to extrapolate
+        let segment = anchors
+            .windows(2)
+            .find(|segment| p <= segment[1].0)
+            .or_else(|| anchors.windows(2).last())?;
+        let [(p0, t0), (p1, t1)] = [segment[0], segment[1]];
+        let time = t0 + (t1 - t0) * (p - p0) / (p1 - p0);
+        Some(time.max(0.0))
+    }
+
+    /// Returns how much longer leisurely players take than rushed ones
+    ///
+    /// returns: Option<f32> - The leisure time minus the rushed time in seconds
+    pub fn spread(&self) -> Option<f32> {
+        Some(self.leisure? - self.rushed?)
+    }
+
+    /// Returns how far the average is from the median, positive when a few very long
+    /// playthroughs pull the average up
+    ///
+    /// returns: Option<f32> - The average minus the median in seconds
+    pub fn skew(&self) -> Option<f32> {
+        Some(self.average? - self.median?)
+    }
+
     fn empty() -> Styles {
         Styles {
             average: None,
@@ -48,7 +247,50 @@ impl Styles {
     }
 }
 
-#[derive(Deserialize, Debug, PartialEq, Serialize)]
+/// Adds two optional times, a time missing on one side counting as the other one
+fn add_times(a: Option<f32>, b: Option<f32>) -> Option<f32> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, None) | (None, a) => a,
+    }
+}
+
+/// Adds the times field by field, e.g. to total a backlog
+///
+/// Times missing from one of the sides are left out of the sum rather than making it unknown.
+impl Add for Styles {
+    type Output = Styles;
+
+    fn add(self, other: Styles) -> Styles {
+        Styles {
+            average: add_times(self.average, other.average),
+            median: add_times(self.median, other.median),
+            rushed: add_times(self.rushed, other.rushed),
+            leisure: add_times(self.leisure, other.leisure),
+        }
+    }
+}
+
+impl Sum for Styles {
+    fn sum<I: Iterator<Item = Styles>>(iter: I) -> Styles {
+        iter.fold(Styles::empty(), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Styles> for Styles {
+    fn sum<I: Iterator<Item = &'a Styles>>(iter: I) -> Styles {
+        iter.cloned().sum()
+    }
+}
+
+// The binary formats encode fields by position: new fields go at the end
+/// A game and its times
+///
+/// Games are equal, and hash, by their How Long to Beat ID alone, so sets and maps keep one
+/// entry per game even when its times changed between fetches. Compare every field with
+/// [`Game::data_eq`].
+#[derive(Deserialize, Debug, Serialize, Clone)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
 pub struct Game {
     pub hltb_id: u32,
     pub title: String,
@@ -58,6 +300,46 @@ pub struct Game {
     pub all_styles: Option<Styles>,
     pub co_op: Option<Styles>,
     pub vs: Option<Styles>,
+    #[serde(default)]
+    pub platforms: Vec<Platform>,
+    #[serde(default)]
+    pub release_year: Option<i32>,
+    /// Other titles of the game, such as its original Japanese or Russian title
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// The gameplay focus and perspective labels of the game, e.g. single-player or first-person
+    #[serde(default)]
+    pub focus: Vec<Focus>,
+    /// When the game was fetched from the site, None if it was parsed from a page fetched
+    /// by other means
+    #[serde(default, with = "repr::timestamp_string")]
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    pub fetched_at: Option<SystemTime>,
+    /// Whether the ID of the "Submit Your Time" link of the page matches the requested ID,
+    /// None if the page has no such link
+    #[serde(default)]
+    pub id_verified: Option<bool>,
+    /// The requested ID when the site redirected it to this game, e.g. after merging
+    /// duplicate entries, `hltb_id` being the canonical ID
+    #[serde(default)]
+    pub redirected_from: Option<u32>,
+    /// The URL of the search page the game was found on, None if it was looked up by ID
+    #[serde(default)]
+    pub search_url: Option<String>,
+}
+
+impl PartialEq for Game {
+    fn eq(&self, other: &Game) -> bool {
+        self.hltb_id == other.hltb_id
+    }
+}
+
+impl Eq for Game {}
+
+impl Hash for Game {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hltb_id.hash(state);
+    }
 }
 
 impl Game {
@@ -94,48 +376,701 @@ impl Game {
             all_styles,
             co_op,
             vs,
+            platforms: Vec::new(),
+            release_year: None,
+            aliases: Vec::new(),
+            focus: Vec::new(),
+            fetched_at: None,
+            id_verified: None,
+            redirected_from: None,
+            search_url: None,
+        }
+    }
+
+    /// Checks whether two games have the same data, every field included, where `==` only
+    /// compares their IDs
+    ///
+    /// # Arguments
+    ///
+    /// * `other`:  &Game - The game to compare with
+    ///
+    /// returns: bool
+    pub fn data_eq(&self, other: &Game) -> bool {
+        // Destructured so that new fields can't be forgotten here
+        let Game {
+            hltb_id,
+            title,
+            main_story,
+            main_extra,
+            completionist,
+            all_styles,
+            co_op,
+            vs,
+            platforms,
+            release_year,
+            aliases,
+            focus,
+            fetched_at,
+            id_verified,
+            redirected_from,
+            search_url,
+        } = self;
+        *hltb_id == other.hltb_id
+            && *title == other.title
+            && *main_story == other.main_story
+            && *main_extra == other.main_extra
+            && *completionist == other.completionist
+            && *all_styles == other.all_styles
+            && *co_op == other.co_op
+            && *vs == other.vs
+            && *platforms == other.platforms
+            && *release_year == other.release_year
+            && *aliases == other.aliases
+            && *focus == other.focus
+            && *fetched_at == other.fetched_at
+            && *id_verified == other.id_verified
+            && *redirected_from == other.redirected_from
+            && *search_url == other.search_url
+    }
+
+    /// Lists what changed since an older fetch of the game: the times with their old and new
+    /// values, and the other fields
+    ///
+    /// # Arguments
+    ///
+    /// * `older`:  &Game - The previous fetch
+    ///
+    /// returns: GameDelta
+    pub fn diff(&self, older: &Game) -> GameDelta {
+        GameDelta::between(older, self)
+    }
+
+    /// Returns the redirect the site followed to reach the game, as the requested and the
+    /// canonical IDs, so stored IDs can be updated
+    ///
+    /// returns: Option<(u32, u32)> - None if the game was reached by its own ID
+    pub fn redirect(&self) -> Option<(u32, u32)> {
+        self.redirected_from.map(|from| (from, self.hltb_id))
+    }
+
+    /// Checks whether the game is available on a platform
+    ///
+    /// # Arguments
+    ///
+    /// * `platform`:  impl Into<Platform> - The platform, or its name (e.g. "PlayStation 4" or "PS4")
+    ///
+    /// returns: bool
+    pub fn is_on_platform(&self, platform: impl Into<Platform>) -> bool {
+        self.platforms.contains(&platform.into())
+    }
+
+    /// Returns the times of a play style
+    ///
+    /// # Arguments
+    ///
+    /// * `style`:  PlayStyle - The play style
+    ///
+    /// returns: Option<&Styles> - None if nobody submitted a time in the play style
+    pub fn styles(&self, style: PlayStyle) -> Option<&Styles> {
+        match style {
+            PlayStyle::MainStory => self.main_story.as_ref(),
+            PlayStyle::MainExtra => self.main_extra.as_ref(),
+            PlayStyle::Completionist => self.completionist.as_ref(),
+            PlayStyle::AllStyles => self.all_styles.as_ref(),
         }
     }
+
+    /// Returns how long the game takes in a play style
+    ///
+    /// # Arguments
+    ///
+    /// * `style`:  PlayStyle - The play style, e.g. main story
+    /// * `kind`:  EstimateKind - Which of its times, e.g. the median
+    ///
+    /// returns: Option<f32> - The time in seconds
+    pub fn estimate(&self, style: PlayStyle, kind: EstimateKind) -> Option<f32> {
+        self.styles(style).and_then(|styles| styles.get(kind))
+    }
+
+    /// Checks whether the game only has multi-player times, such as an online shooter
+    ///
+    /// The co-op and competitive times of such games are their only estimates, as the site
+    /// has no single-player times for them.
+    ///
+    /// returns: bool
+    pub fn is_multiplayer_only(&self) -> bool {
+        let single_player = [
+            &self.main_story,
+            &self.main_extra,
+            &self.completionist,
+            &self.all_styles,
+        ];
+        single_player.iter().all(|styles| styles.is_none())
+            && (self.co_op.is_some() || self.vs.is_some())
+    }
+
+    /// Describes the game in one line with its average times, e.g.
+    /// "Metal Gear — Main 4h, +Extra 5h, 100% 5½h"
+    ///
+    /// Times are rounded to the half hour, or to the minute under an hour.
+    ///
+    /// returns: String
+    pub fn summary(&self) -> String {
+        let times: Vec<String> = [
+            ("Main", &self.main_story),
+            ("+Extra", &self.main_extra),
+            ("100%", &self.completionist),
+            ("Co-Op", &self.co_op),
+            ("Vs.", &self.vs),
+        ]
+        .iter()
+        .filter_map(|(label, styles)| {
+            let seconds = styles.as_ref()?.average?;
+            Some(format!("{} {}", label, summary_time(seconds)))
+        })
+        .collect();
+        if times.is_empty() {
+            format!("{} — no times yet", self.title)
+        } else {
+            format!("{} — {}", self.title, times.join(", "))
+        }
+    }
+
+    /// Returns the canonical URL of the details page of the game
+    ///
+    /// returns: String
+    pub fn url(&self) -> String {
+        game_url(self.hltb_id)
+    }
+
+    /// Returns the alias of the game a search query matched, when it didn't match the title
+    ///
+    /// Titles are compared after [`parse::normalize_title`], so case, accents, full-width
+    /// characters and punctuation don't matter.
+    ///
+    /// # Arguments
+    ///
+    /// * `query`:  &str - The name the game was searched with
+    ///
+    /// returns: Option<&str> - None if the query matched the title or no title at all
+    pub fn matched_alias(&self, query: &str) -> Option<&str> {
+        let query = parse::normalize_title(query);
+        if parse::normalize_title(&self.title) == query {
+            return None;
+        }
+        self.aliases
+            .iter()
+            .find(|alias| parse::normalize_title(alias) == query)
+            .map(String::as_str)
+    }
+}
+
+/// Writes a time compactly for [`Game::summary`], e.g. "5½h" or "45m"
+///
+/// # Arguments
+///
+/// * `seconds`:  f32 - The time in seconds
+///
+/// returns: String
+fn summary_time(seconds: f32) -> String {
+    if seconds < 3600.0 {
+        return format!("{}m", (seconds / 60.0).round().max(1.0));
+    }
+    let half_hours = (seconds / 1800.0).round() as u64;
+    let fraction = if half_hours % 2 == 1 { "½" } else { "" };
+    format!("{}{}h", half_hours / 2, fraction)
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Clone, Copy)]
+pub enum PlayStyle {
+    MainStory,
+    MainExtra,
+    Completionist,
+    /// Every play style together, which can be read but not submitted
+    AllStyles,
+}
+
+/// A gameplay focus or perspective label of a game, shown next to its title
+#[derive(Deserialize, Debug, PartialEq, Eq, Hash, Serialize, Clone, Copy)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::Enum))]
+pub enum Focus {
+    SinglePlayer,
+    CoOp,
+    Multiplayer,
+    Competitive,
+    FirstPerson,
+    ThirdPerson,
+    TopDown,
+    SideView,
+    Isometric,
+}
+
+impl Focus {
+    /// Reads a label as written on the site, ignoring case, spaces and hyphens
+    ///
+    /// # Arguments
+    ///
+    /// * `label`:  &str - The label, e.g. "Single-Player" or "Co-Op"
+    ///
+    /// returns: Option<Focus> - None if the text isn't a known label
+    pub fn from_label(label: &str) -> Option<Focus> {
+        let label: String = label
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect();
+        match label.as_str() {
+            "singleplayer" => Some(Focus::SinglePlayer),
+            "coop" | "cooperative" => Some(Focus::CoOp),
+            "multiplayer" => Some(Focus::Multiplayer),
+            "competitive" | "vs" | "versus" => Some(Focus::Competitive),
+            "firstperson" => Some(Focus::FirstPerson),
+            "thirdperson" => Some(Focus::ThirdPerson),
+            "topdown" => Some(Focus::TopDown),
+            "side" | "sideview" | "sidescrolling" => Some(Focus::SideView),
+            "isometric" => Some(Focus::Isometric),
+            _ => None,
+        }
+    }
+
+    /// Returns the label of the focus as written on the site
+    ///
+    /// returns: &'static str
+    pub fn label(&self) -> &'static str {
+        match self {
+            Focus::SinglePlayer => "Single-Player",
+            Focus::CoOp => "Co-Op",
+            Focus::Multiplayer => "Multiplayer",
+            Focus::Competitive => "Competitive",
+            Focus::FirstPerson => "First-Person",
+            Focus::ThirdPerson => "Third-Person",
+            Focus::TopDown => "Top-Down",
+            Focus::SideView => "Side",
+            Focus::Isometric => "Isometric",
+        }
+    }
+}
+
+/// Which of the times of a play style to use
+#[derive(Deserialize, Debug, PartialEq, Eq, Hash, Serialize, Clone, Copy, Default)]
+pub enum EstimateKind {
+    #[default]
+    Average,
+    Median,
+    Rushed,
+    Leisure,
 }
 
 const BASE_URL: &str = "https://howlongtobeat.com/";
 
-/// Searches the search page for a game
+/// Returns the canonical URL of the details page of a game, to link users to the site
+///
+/// # Arguments
+///
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+///
+/// returns: String
+pub fn game_url(hltb_id: u32) -> String {
+    format!("{}game/{}", BASE_URL, hltb_id)
+}
+
+/// Returns the canonical URL of the search page of a name
 ///
 /// # Arguments
 ///
 /// * `name`:  &str - The name of the game to search for
+///
+/// returns: String
+pub fn search_url(name: &str) -> String {
+    format!("{}?q={}", BASE_URL, urlencoding::encode(name.trim()))
+}
+#[cfg(any(feature = "browser", feature = "http"))]
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36";
+
+/// Launches a headless browser and opens a tab with the scraper user agent
+///
+/// The browser shuts down when the returned guard is dropped.
+///
+/// # Arguments
+///
 /// * `sandbox`:  bool - Whether to enable sandbox mode for the browser
 ///
-/// returns: Result<u32, Box<dyn Error, Global>>
-async fn search_search_page_for_with_sandbox(
-    name: &str,
-    sandbox: bool,
-) -> Result<u32, Box<dyn Error>> {
-    let url = BASE_URL.to_owned() + "?q=" + &encode(name);
+/// returns: Result<BrowserGuard, Box<dyn Error, Global>>
+#[cfg(feature = "browser")]
+fn launch_tab(sandbox: bool) -> Result<BrowserGuard, Box<dyn Error>> {
+    launch_tab_with_config(&ClientConfig {
+        sandbox,
+        ..ClientConfig::from_env()?
+    })
+}
+
+/// The Chromium revision downloaded when no Chrome is installed (`fetch-chromium` feature)
+#[cfg(feature = "fetch-chromium")]
+const CHROMIUM_REVISION: &str = "1095492";
+
+/// Finds the Chrome executable to launch: the configured one, else the installed one
+///
+/// Without an installed Chrome, returns None with the `fetch-chromium` feature so that a
+/// pinned Chromium revision gets downloaded, and fails otherwise.
+///
+/// # Arguments
+///
+/// * `config`:  &ClientConfig - The configuration of the client
+///
+/// returns: Result<Option<PathBuf>, Box<dyn Error, Global>>
+#[cfg(feature = "browser")]
+fn chrome_executable(config: &ClientConfig) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    if let Some(path) = &config.chrome_path {
+        return Ok(Some(path.clone()));
+    }
+    match headless_chrome::browser::default_executable() {
+        Ok(path) => Ok(Some(path)),
+        #[cfg(feature = "fetch-chromium")]
+        Err(_) => Ok(None),
+        #[cfg(not(feature = "fetch-chromium"))]
+        Err(_) => Err(HltbError::BackendUnavailable(vec![BackendAttempt::new(
+            "browser",
+            "Chrome was not found",
+            "install Chrome or Chromium, set HLTB_CHROME_PATH or enable the fetch-chromium feature",
+        )])
+        .into()),
+    }
+}
+
+/// Launches a headless browser configured by a client configuration and opens a tab
+///
+/// The browser shuts down when the returned guard is dropped.
+///
+/// # Arguments
+///
+/// * `config`:  &ClientConfig - The configuration of the client
+///
+/// returns: Result<BrowserGuard, Box<dyn Error, Global>>
+#[cfg(feature = "browser")]
+fn launch_tab_with_config(config: &ClientConfig) -> Result<BrowserGuard, Box<dyn Error>> {
     let launch_options = LaunchOptions {
         headless: true,
-        sandbox,
+        sandbox: config.launch_profile.sandbox(config.sandbox),
+        args: config
+            .launch_profile
+            .args()
+            .iter()
+            .map(OsStr::new)
+            .collect(),
+        idle_browser_timeout: config.timeout.max(Duration::from_secs(30)),
+        proxy_server: config.proxy.as_deref(),
+        path: chrome_executable(config)?,
+        #[cfg(feature = "fetch-chromium")]
+        fetcher_options: headless_chrome::browser::FetcherOptions::default().with_revision(
+            headless_chrome::browser::Revision::Specific(CHROMIUM_REVISION.to_string()),
+        ),
         ..Default::default()
     };
-    let browser = Browser::new(launch_options)?;
+    let browser = Browser::new(launch_options).map_err(|e| {
+        HltbError::BackendUnavailable(vec![BackendAttempt::new(
+            "browser",
+            &format!("Chrome couldn't be launched: {}", e),
+            "check that HLTB_CHROME_PATH is a working Chrome, or set HLTB_LAUNCH_PROFILE to \
+             \"container\" when running in a container",
+        )])
+    })?;
     let tab = browser.new_tab()?;
-    tab.set_user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36", None, None)?;
-    tab.navigate_to(&url)?;
+    let tab = BrowserGuard::new(browser, tab, config.shutdown_timeout);
+    tab.set_default_timeout(config.timeout);
+    tab.set_user_agent(&config.full_user_agent(), None, None)?;
+    Ok(tab)
+}
+
+/// Loads a page in a headless browser and returns its rendered HTML
+///
+/// # Arguments
+///
+/// * `url`:  &str - The URL of the page
+/// * `wait_selector`:  &str - A selector that only matches once the page is rendered
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser
+///
+/// returns: Result<String, Box<dyn Error, Global>>
+#[cfg(feature = "browser")]
+fn fetch_page_content(
+    url: &str,
+    wait_selector: &str,
+    sandbox: bool,
+) -> Result<String, Box<dyn Error>> {
+    fetch_page_content_with_session(url, wait_selector, sandbox, None)
+}
+
+/// Loads a page in a headless browser, optionally logged in, and returns its rendered HTML
+///
+/// # Arguments
+///
+/// * `url`:  &str - The URL of the page
+/// * `wait_selector`:  &str - A selector that only matches once the page is rendered
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser
+/// * `session`:  Option<&Session> - The session whose cookies are sent with the request
+///
+/// returns: Result<String, Box<dyn Error, Global>>
+#[cfg(feature = "browser")]
+fn fetch_page_content_with_session(
+    url: &str,
+    wait_selector: &str,
+    sandbox: bool,
+    session: Option<&Session>,
+) -> Result<String, Box<dyn Error>> {
+    let config = ClientConfig {
+        sandbox,
+        ..ClientConfig::from_env()?
+    };
+    fetch_page_content_with_config(
+        url,
+        wait_selector,
+        &WaitStrategy::Selector,
+        &[],
+        &config,
+        session,
+        None,
+        None,
+    )
+}
+
+/// Loads a page in a headless browser configured by a client configuration and returns its
+/// rendered HTML
+///
+/// # Arguments
+///
+/// * `url`:  &str - The URL of the page
+/// * `wait_selector`:  &str - A selector that only matches once the page is rendered
+/// * `wait`:  &WaitStrategy - How to decide that the page is rendered
+/// * `subtrees`:  &[&str] - The selectors of the parts of the page to return, all of it if empty
+/// * `config`:  &ClientConfig - The configuration of the client
+/// * `session`:  Option<&Session> - The session whose cookies are sent with the request
+/// * `cancel`:  Option<&CancellationToken> - Aborts the wait for the page and closes the browser
+/// * `timings`:  Option<&mut Timings> - Where to add how long the launch, navigation and wait
+///   took, if anywhere
+///
+/// returns: Result<String, Box<dyn Error, Global>>
+#[cfg(feature = "browser")]
+#[allow(clippy::too_many_arguments)]
+fn fetch_page_content_with_config(
+    url: &str,
+    wait_selector: &str,
+    wait: &WaitStrategy,
+    subtrees: &[&str],
+    config: &ClientConfig,
+    session: Option<&Session>,
+    cancel: Option<&CancellationToken>,
+    timings: Option<&mut Timings>,
+) -> Result<String, Box<dyn Error>> {
+    let mut tab = None;
+    fetch_page_content_in_tab(
+        &mut tab,
+        url,
+        wait_selector,
+        wait,
+        subtrees,
+        config,
+        session,
+        cancel,
+        timings,
+    )
+}
+
+/// Loads a page in the tab of a previous page, or in a new browser when there is none yet,
+/// and returns its rendered HTML
+///
+/// The tab is kept in the slot only when the page loads, so a failed page doesn't leave a
+/// broken tab behind.
+///
+/// # Arguments
+///
+/// * `slot`:  &mut Option<Arc<BrowserGuard>> - The tab to load the page in, filled when empty
+/// * `url`:  &str - The URL of the page
+/// * `wait_selector`:  &str - A selector that only matches once the page is rendered
+/// * `wait`:  &WaitStrategy - How to decide that the page is rendered
+/// * `subtrees`:  &[&str] - The selectors of the parts of the page to return, all of it if empty
+/// * `config`:  &ClientConfig - The configuration of the client
+/// * `session`:  Option<&Session> - The session whose cookies are sent with the request
+/// * `cancel`:  Option<&CancellationToken> - Aborts the wait for the page and closes the browser
+/// * `timings`:  Option<&mut Timings> - Where to add how long the launch, navigation and wait
+///   took, if anywhere
+///
+/// returns: Result<String, Box<dyn Error, Global>>
+#[cfg(feature = "browser")]
+#[allow(clippy::too_many_arguments)]
+fn fetch_page_content_in_tab(
+    slot: &mut Option<Arc<BrowserGuard>>,
+    url: &str,
+    wait_selector: &str,
+    wait: &WaitStrategy,
+    subtrees: &[&str],
+    config: &ClientConfig,
+    session: Option<&Session>,
+    cancel: Option<&CancellationToken>,
+    timings: Option<&mut Timings>,
+) -> Result<String, Box<dyn Error>> {
+    let started = Instant::now();
+    let tab = match slot.take() {
+        Some(tab) => tab,
+        None => {
+            let tab = launch_tab_with_config(config)?;
+            if let Some(session) = session {
+                session.apply(&tab)?;
+            }
+            Arc::new(tab)
+        }
+    };
+    let launched = Instant::now();
+    let _slot = client::host_slot_blocking(url, cancel)?;
+    tab.navigate_to(url)?;
     tab.wait_until_navigated()?;
-    tab.wait_for_element("#search-results-header > ul > li:nth-child(1) > div > div[class*='_search_list_image'] > a")?;
+    let navigated = Instant::now();
+    wait_for_page(&tab, wait_selector, wait, config, cancel)?;
+    let content = page_content(&tab, subtrees)?;
+    if let Some(timings) = timings {
+        timings.launch += launched - started;
+        timings.navigation += navigated - launched;
+        timings.wait += navigated.elapsed();
+    }
+    *slot = Some(tab);
+    Ok(content)
+}
+
+/// Copies the parts of the page matching selectors, with their ancestors, into an otherwise
+/// empty document. The other elements are kept empty so that `:nth-child` selectors still
+/// match, and the scripts, styles and images of the parts are emptied too.
+#[cfg(feature = "browser")]
+const EXTRACT_SUBTREES_SCRIPT: &str = "((selectors) => {
+    const kept = new Set();
+    selectors.forEach(s => document.querySelectorAll(s).forEach(e => kept.add(e)));
+    const heavy = 'svg, style, noscript, iframe, script:not(#__NEXT_DATA__)';
+    const prune = (node) => {
+        if (kept.has(node)) {
+            const clone = node.cloneNode(true);
+            clone.querySelectorAll(heavy).forEach(e => e.replaceChildren());
+            return clone;
+        }
+        const clone = node.cloneNode(false);
+        if ([...kept].some(k => node.contains(k))) {
+            for (const child of node.children) clone.appendChild(prune(child));
+        }
+        return clone;
+    };
+    return prune(document.documentElement).outerHTML;
+})";
+
+/// Returns the HTML of the parts of the page of a tab that match selectors, so that large
+/// pages are neither transferred nor parsed whole
+///
+/// # Arguments
+///
+/// * `tab`:  &Tab - The tab showing the page
+/// * `subtrees`:  &[&str] - The selectors of the parts to return, the whole page if empty
+///
+/// returns: Result<String, Box<dyn Error, Global>>
+#[cfg(feature = "browser")]
+fn page_content(tab: &Tab, subtrees: &[&str]) -> Result<String, Box<dyn Error>> {
+    if subtrees.is_empty() {
+        return Ok(tab.get_content()?);
+    }
+    let script = format!(
+        "{}({})",
+        EXTRACT_SUBTREES_SCRIPT,
+        serde_json::to_string(subtrees)?
+    );
+    match tab.evaluate(&script, false)?.value {
+        Some(serde_json::Value::String(html)) => Ok(html),
+        _ => Ok(tab.get_content()?),
+    }
+}
 
-    let content = tab.get_content()?;
-    let document = Html::parse_document(&content);
-    let selector = Selector::parse("#search-results-header > ul > li:nth-child(1) > div > div[class*='_search_list_image'] > a").unwrap();
+/// Counts the resources a page loaded, -1 until the page itself has loaded
+#[cfg(feature = "browser")]
+const RESOURCE_COUNT_SCRIPT: &str = "document.readyState === 'complete' \
+     ? performance.getEntriesByType('resource').length : -1";
 
-    for element in document.select(&selector) {
-        if let Some(link) = element.value().attr("href") {
-            let id = link.split("/").last().unwrap().parse::<u32>().unwrap();
-            return Ok(id);
+/// Waits for a page to be rendered in a tab, giving up early when the lookup is cancelled
+///
+/// # Arguments
+///
+/// * `tab`:  &Tab - The tab to wait in
+/// * `wait_selector`:  &str - The selector of the element the page is waited for by default
+/// * `wait`:  &WaitStrategy - How to decide that the page is rendered
+/// * `config`:  &ClientConfig - The wait timeout and polling interval
+/// * `cancel`:  Option<&CancellationToken> - The token cancelling the lookup, if any
+///
+/// returns: Result<(), Box<dyn Error, Global>>
+#[cfg(feature = "browser")]
+fn wait_for_page(
+    tab: &Tab,
+    wait_selector: &str,
+    wait: &WaitStrategy,
+    config: &ClientConfig,
+    cancel: Option<&CancellationToken>,
+) -> Result<(), Box<dyn Error>> {
+    let deadline = Instant::now() + config.wait_timeout.unwrap_or(config.timeout);
+    // The resource count of a network idle wait, and since when it hasn't changed
+    let mut resources: Option<(f64, Instant)> = None;
+    loop {
+        if cancel.is_some_and(CancellationToken::is_cancelled) {
+            return Err(HltbError::Cancelled.into());
         }
+        let rendered = match wait {
+            WaitStrategy::Selector => tab.find_element(wait_selector).is_ok(),
+            WaitStrategy::NetworkIdle { quiet } => {
+                let count = tab
+                    .evaluate(RESOURCE_COUNT_SCRIPT, false)?
+                    .value
+                    .and_then(|value| value.as_f64())
+                    .unwrap_or(-1.0);
+                match resources {
+                    Some((last, since)) if last == count && count >= 0.0 => {
+                        since.elapsed() >= *quiet
+                    }
+                    _ => {
+                        resources = Some((count, Instant::now()));
+                        false
+                    }
+                }
+            }
+            WaitStrategy::Function(expression) => {
+                let script = format!("Boolean({})", expression);
+                tab.evaluate(&script, false)?.value == Some(serde_json::Value::Bool(true))
+            }
+        };
+        if rendered {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(match wait {
+                WaitStrategy::Selector => HltbError::ElementNotFound(wait_selector.to_string()),
+                WaitStrategy::NetworkIdle { .. } => {
+                    HltbError::WaitTimedOut("the network to be idle".to_string())
+                }
+                WaitStrategy::Function(expression) => HltbError::WaitTimedOut(expression.clone()),
+            }
+            .into());
+        }
+        std::thread::sleep(config.poll_interval);
     }
-    Err("Element not found".into())
+}
+
+/// Searches the search page for a game
+///
+/// # Arguments
+///
+/// * `name`:  &str - The name of the game to search for
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser
+///
+/// returns: Result<u32, Box<dyn Error, Global>>
+#[cfg(all(test, feature = "browser"))]
+async fn search_search_page_for_with_sandbox(
+    name: &str,
+    sandbox: bool,
+) -> Result<u32, Box<dyn Error>> {
+    HltbClient::builder()
+        .sandbox(sandbox)
+        .build()?
+        .find_id(name)
+        .await
 }
 
 /// Searches the search page for a game (with sandbox enabled by default)
@@ -145,6 +1080,7 @@ async fn search_search_page_for_with_sandbox(
 /// * `name`:  &str - The name of the game to search for
 ///
 /// returns: Result<u32, Box<dyn Error, Global>>
+#[cfg(all(test, feature = "browser"))]
 async fn search_search_page_for(name: &str) -> Result<u32, Box<dyn Error>> {
     search_search_page_for_with_sandbox(name, true).await
 }
@@ -157,76 +1093,16 @@ async fn search_search_page_for(name: &str) -> Result<u32, Box<dyn Error>> {
 /// * `sandbox`:  bool - Whether to enable sandbox mode for the browser
 ///
 /// returns: Result<Game, Box<dyn Error, Global>>
+#[cfg(all(test, feature = "browser"))]
 async fn search_details_page_for_with_sandbox(
     hltb_id: u32,
     sandbox: bool,
 ) -> Result<Game, Box<dyn Error>> {
-    let url = BASE_URL.to_owned() + "game/" + hltb_id.to_string().as_str();
-    let launch_options = LaunchOptions {
-        headless: true,
-        sandbox,
-        ..Default::default()
-    };
-    let browser = Browser::new(launch_options)?;
-    let tab = browser.new_tab()?;
-    tab.set_user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36", None, None)?;
-    tab.navigate_to(&url)?;
-    tab.wait_until_navigated()?;
-
-    tab.wait_for_element("#__next > div > main > div:nth-child(2) > div > div[class*='content'] > div.in.scrollable.scroll_blue.shadow_box.back_primary > table[class*='_game_main_table']")?;
-
-    let content = tab.get_content()?;
-    let document = Html::parse_document(&content);
-    let title_selector = Selector::parse(
-        "#__next > div > main > div:nth-child(1) > div > div > div > div[class*='_profile_header']",
-    )
-    .unwrap();
-    let title = document
-        .select(&title_selector)
-        .next()
-        .unwrap()
-        .inner_html()
-        .trim()
-        .to_string()
-        .replace("<!-- -->", "");
-    let table_selector = Selector::parse("#__next > div > main > div:nth-child(2) > div > div[class*='content'] > div.in.scrollable.scroll_blue.shadow_box.back_primary > table[class*='_game_main_table']").unwrap();
-    let table = document.select(&table_selector).next().unwrap();
-    let tr_selector = Selector::parse("tbody > tr").unwrap();
-    let rows: Vec<_> = table.select(&tr_selector).collect();
-
-    let mut main_story = None;
-    let mut main_extra = None;
-    let mut completionist = None;
-    let mut all_styles = None;
-    let mut co_op = None;
-    let mut vs = None;
-
-    let td_selector = Selector::parse("td").unwrap();
-    for row in rows {
-        if let Some(first_cell) = row.select(&td_selector).next() {
-            let row_type = first_cell.inner_html().trim().to_string();
-            match row_type.as_str() {
-                "Main Story" => main_story = Some(parse_row(row)),
-                "Main + Extra" | "Main + Extras" => main_extra = Some(parse_row(row)),
-                "Completionist" | "Completionists" => completionist = Some(parse_row(row)),
-                "All PlayStyles" => all_styles = Some(parse_row(row)),
-                "Co-Op" => co_op = Some(parse_row(row)),
-                "Competitive" => vs = Some(parse_row(row)),
-                _ => {}
-            }
-        }
-    }
-
-    Ok(Game::new(
-        title,
-        hltb_id,
-        main_story,
-        main_extra,
-        completionist,
-        all_styles,
-        co_op,
-        vs,
-    ))
+    HltbClient::builder()
+        .sandbox(sandbox)
+        .build()?
+        .search_by_id(hltb_id)
+        .await
 }
 
 /// Searches for the details page of a game (with sandbox enabled by default)
@@ -236,29 +1112,11 @@ async fn search_details_page_for_with_sandbox(
 /// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
 ///
 /// returns: Result<Game, Box<dyn Error, Global>>
+#[cfg(all(test, feature = "browser"))]
 async fn search_details_page_for(hltb_id: u32) -> Result<Game, Box<dyn Error>> {
     search_details_page_for_with_sandbox(hltb_id, true).await
 }
 
-/// Parses a row of a table
-///
-/// # Arguments
-///
-/// * `row`:  ElementRef - The row to parse
-///
-/// returns: Styles
-fn parse_row(row: ElementRef) -> Styles {
-    let selector = Selector::parse("td").unwrap();
-    let mut cells = row.select(&selector);
-    cells.next();
-    cells.next();
-    let average = convert_hours_minutes_to_sec_opt(cells.next().unwrap().inner_html().as_str());
-    let median = convert_hours_minutes_to_sec_opt(cells.next().unwrap().inner_html().as_str());
-    let rushed = convert_hours_minutes_to_sec_opt(cells.next().unwrap().inner_html().as_str());
-    let leisure = convert_hours_minutes_to_sec_opt(cells.next().unwrap().inner_html().as_str());
-    Styles::new(average, median, rushed, leisure)
-}
-
 /// Converts a string of hours and minutes to seconds, returning None for empty/invalid values
 ///
 /// # Arguments
@@ -327,6 +1185,7 @@ fn convert_hours_minutes_to_sec(text: &str) -> f32 {
 /// * `name`:  &str - The name of the game to search for
 ///
 /// returns: Result<String, Box<dyn Error, Global>>
+#[cfg(any(feature = "browser", feature = "http"))]
 pub async fn search_by_name(name: &str) -> Result<Game, Box<dyn Error>> {
     search_by_name_with_sandbox(name, true).await
 }
@@ -339,22 +1198,216 @@ pub async fn search_by_name(name: &str) -> Result<Game, Box<dyn Error>> {
 /// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
 ///
 /// returns: Result<String, Box<dyn Error, Global>>
+#[cfg(any(feature = "browser", feature = "http"))]
 pub async fn search_by_name_with_sandbox(
     name: &str,
     sandbox: bool,
 ) -> Result<Game, Box<dyn Error>> {
-    let hltb_id = search_search_page_for_with_sandbox(name, sandbox)
+    HltbClient::builder()
+        .sandbox(sandbox)
+        .build()?
+        .search_by_name(name)
         .await
-        .unwrap();
-    let game = search_details_page_for_with_sandbox(hltb_id, sandbox)
+}
+
+/// Searches for a game by its How Long to Beat ID
+///
+/// # Arguments
+///
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+///
+/// returns: Result<Game, Box<dyn Error, Global>>
+#[cfg(any(feature = "browser", feature = "http"))]
+pub async fn search_by_id(hltb_id: u32) -> Result<Game, Box<dyn Error>> {
+    search_by_id_with_sandbox(hltb_id, true).await
+}
+
+/// Searches for a game by its How Long to Beat ID with custom sandbox setting
+///
+/// # Arguments
+///
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+///
+/// returns: Result<Game, Box<dyn Error, Global>>
+#[cfg(any(feature = "browser", feature = "http"))]
+pub async fn search_by_id_with_sandbox(
+    hltb_id: u32,
+    sandbox: bool,
+) -> Result<Game, Box<dyn Error>> {
+    HltbClient::builder()
+        .sandbox(sandbox)
+        .build()?
+        .search_by_id(hltb_id)
         .await
-        .unwrap();
+}
+
+/// Runs a lookup on the blocking thread pool, since the browser driver is synchronous
+///
+/// Errors are made sendable so the outcome can be sent back across threads, see
+/// [`error::sendable`].
+///
+/// # Arguments
+///
+/// * `lookup`:  F - Creates the lookup future on the blocking thread
+///
+/// returns: Result<Game, Box<dyn Error + Send + Sync, Global>>
+#[cfg(any(feature = "serve", feature = "node"))]
+pub(crate) async fn spawn_lookup<F, Fut>(lookup: F) -> Result<Game, Box<dyn Error + Send + Sync>>
+where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = Result<Game, Box<dyn Error>>>,
+{
+    tokio::task::spawn_blocking(move || {
+        tokio::runtime::Handle::current()
+            .block_on(lookup())
+            .map_err(error::sendable)
+    })
+    .await?
+}
+
+/// Searches for a game by name, only accepting it if it is available on a platform
+///
+/// # Arguments
+///
+/// * `name`:  &str - The name of the game to search for
+/// * `platform`:  impl Into<Platform> - The platform, or its name (e.g. "PlayStation 4")
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser
+///
+/// returns: Result<Game, Box<dyn Error, Global>>
+#[cfg(any(feature = "browser", feature = "http"))]
+pub async fn search_by_name_on_platform_with_sandbox(
+    name: &str,
+    platform: impl Into<Platform>,
+    sandbox: bool,
+) -> Result<Game, Box<dyn Error>> {
+    let platform = platform.into();
+    let game = search_by_name_with_sandbox(name, sandbox).await?;
+    if !game.is_on_platform(&platform) {
+        return Err(HltbError::NotOnPlatform {
+            title: game.title,
+            platform: platform.to_string(),
+        }
+        .into());
+    }
     Ok(game)
 }
 
+/// Resolves every game of a backlog and adds up their average times in a play style
+///
+/// # Arguments
+///
+/// * `entries`:  &[BacklogEntry] - The games, by name or ID
+/// * `style`:  PlayStyle - The play style to add up
+///
+/// returns: Result<BacklogReport, Box<dyn Error, Global>>
+#[cfg(any(feature = "browser", feature = "http"))]
+pub async fn estimate_backlog(
+    entries: &[BacklogEntry],
+    style: PlayStyle,
+) -> Result<BacklogReport, Box<dyn Error>> {
+    Ok(HltbClient::builder()
+        .build()?
+        .estimate_backlog(entries, style)
+        .await)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn test_percentile_estimate() {
+        let styles = Styles::new(Some(15000.0), Some(14400.0), Some(9960.0), Some(25920.0));
+        assert_eq!(styles.percentile_estimate(0.5), Some(14400.0));
+        assert_eq!(styles.percentile_estimate(0.1), Some(9960.0));
+        assert_eq!(styles.percentile_estimate(0.3), Some(12180.0));
+        assert_eq!(styles.percentile_estimate(0.7), Some(20160.0));
+        assert_eq!(styles.percentile_estimate(0.0), Some(8850.0));
+        assert_eq!(styles.percentile_estimate(1.5), None);
+        let average_only = Styles::new(Some(15000.0), None, None, Some(25000.0));
+        assert_eq!(average_only.percentile_estimate(0.7), Some(20000.0));
+        // Extrapolated below the median
+        assert_eq!(
+            average_only.percentile_estimate(0.1).map(f32::round),
+            Some(5000.0)
+        );
+        assert_eq!(
+            Styles::new(Some(15000.0), None, None, None).percentile_estimate(0.5),
+            None
+        );
+    }
+
+    #[test]
+    fn test_styles_arithmetic() {
+        let metal_gear = Styles::new(Some(15000.0), Some(14400.0), Some(9960.0), Some(25920.0));
+        let contra = Styles::new(Some(3600.0), None, Some(1800.0), Some(7200.0));
+        assert_eq!(metal_gear.spread(), Some(15960.0));
+        assert_eq!(metal_gear.skew(), Some(600.0));
+        assert_eq!(contra.skew(), None);
+        let games = [metal_gear.clone(), contra.clone()];
+        let total: Styles = games.iter().sum();
+        assert_eq!(
+            total,
+            Styles::new(Some(18600.0), Some(14400.0), Some(11760.0), Some(33120.0))
+        );
+        assert_eq!(metal_gear + contra, total);
+        assert_eq!(
+            Vec::<Styles>::new().into_iter().sum::<Styles>(),
+            Styles::empty()
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        let styles = |average| Some(Styles::new(Some(average), None, None, None));
+        let mut game = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            styles(15000.0),
+            styles(18000.0),
+            styles(19800.0),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(game.summary(), "Metal Gear — Main 4h, +Extra 5h, 100% 5½h");
+        game.main_extra = None;
+        game.completionist = styles(2700.0);
+        assert_eq!(game.summary(), "Metal Gear — Main 4h, 100% 45m");
+        game.main_story = None;
+        game.completionist = None;
+        assert_eq!(game.summary(), "Metal Gear — no times yet");
+    }
+
+    #[test]
+    fn test_game_identity() {
+        let styles = |average| Some(Styles::new(Some(average), None, None, None));
+        let game = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            styles(15000.0),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let mut refreshed = game.clone();
+        refreshed.main_story = styles(15600.0);
+        assert_eq!(game, refreshed);
+        assert!(!game.data_eq(&refreshed));
+        assert!(game.data_eq(&game.clone()));
+        let games: std::collections::HashSet<Game> = [game, refreshed].into_iter().collect();
+        assert_eq!(games.len(), 1);
+    }
+}
+
+// These tests fetch their pages from the live site. To run them offline, record the pages
+// with `cargo run --bin refresh-fixtures` and replay them with `HLTB_REPLAY=fixtures/hltb.json`.
+#[cfg(all(test, feature = "browser"))]
+mod live_tests {
+    use super::*;
     use tokio;
 
     #[tokio::test]
@@ -410,7 +1463,7 @@ mod tests {
     #[tokio::test]
     async fn test_search_by_name() {
         let game = search_by_name("Metal Gear").await.unwrap();
-        let expected = Game::new(
+        let mut expected = Game::new(
             "Metal Gear".to_string(),
             5900,
             Some(Styles::new(
@@ -440,7 +1493,11 @@ mod tests {
             None,
             None,
         );
-        assert_eq!(game, expected);
+        assert!(game.is_on_platform("NES"));
+        expected.platforms = game.platforms.clone();
+        expected.release_year = Some(1987);
+        expected.fetched_at = game.fetched_at;
+        assert!(game.data_eq(&expected), "{:?} != {:?}", game, expected);
     }
 
     #[tokio::test]
@@ -475,7 +1532,7 @@ mod tests {
     #[tokio::test]
     async fn test_search_by_name_coopvs() {
         let game = search_by_name("Helldivers 2").await.unwrap();
-        let expected = Game::new(
+        let mut expected = Game::new(
             "Helldivers 2".to_string(),
             129232,
             None,
@@ -495,7 +1552,12 @@ mod tests {
                 Some(convert_hours_minutes_to_sec("43 Hours")),
             )),
         );
-        assert_eq!(game, expected);
+        assert!(game.is_on_platform("PC"));
+        assert!(game.is_on_platform("PlayStation 5"));
+        expected.platforms = game.platforms.clone();
+        expected.release_year = Some(2024);
+        expected.fetched_at = game.fetched_at;
+        assert!(game.data_eq(&expected), "{:?} != {:?}", game, expected);
     }
 
     #[tokio::test]
@@ -0,0 +1,215 @@
+//! Titles of games found in local libraries.
+//!
+//! Folder names, executables and shortcuts of scanned libraries rarely carry the
+//! title of their game as the site writes it: "Hollow_Knight_v1.5.78 [GOG]",
+//! "DOOM (1993)" or "C:\Games\Portal2\portal2.exe". [`LocalTitle::parse`] splits
+//! such a name into words, drops the release tags, versions and launcher words
+//! around the title, and keeps a year written next to it as a hint, so searches
+//! of local titles find their game far more often.
+
+use serde::{Deserialize, Serialize};
+
+/// The extensions stripped from the last component of a path
+const EXTENSIONS: &[&str] = &[
+    "exe", "lnk", "url", "desktop", "app", "bat", "sh", "iso", "zip", "7z", "rar",
+];
+
+/// Words of local names that are never part of a title, lowercase
+const STOP_WORDS: &[&str] = &[
+    "setup", "launcher", "launch", "shortcut", "gog", "steam", "epic", "repack", "portable", "x64",
+    "x86", "win64", "win32", "64bit", "32bit", "dx9", "dx11", "dx12", "vulkan", "goty",
+];
+
+/// A title cleaned up from a local name, with the year written next to it, if any
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct LocalTitle {
+    /// The title to search for
+    pub name: String,
+    /// The release year written in the local name, e.g. "DOOM (1993)"
+    pub year: Option<i32>,
+}
+
+impl LocalTitle {
+    /// Cleans up the title of a game from a local name: a folder, an executable or a shortcut
+    ///
+    /// Only the last component of a path is read, without its extension. Underscores and
+    /// dots separate words, as do case changes ("HollowKnight") and digits following
+    /// letters ("Portal2"). Bracketed tags, versions ("v1.5.78") and launcher or release words
+    /// ("Setup", "x64", "GOTY") are dropped, and a year between 1970 and 2039 is kept as a
+    /// hint.
+    ///
+    /// # Arguments
+    ///
+    /// * `raw`:  &str - The local name, e.g. "Hollow_Knight_v1.5.78 [GOG]"
+    ///
+    /// returns: LocalTitle
+    pub fn parse(raw: &str) -> LocalTitle {
+        let file = raw.rsplit(['/', '\\']).next().unwrap_or(raw).trim();
+        let file = match file.rsplit_once('.') {
+            Some((stem, extension))
+                if EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str()) =>
+            {
+                stem
+            }
+            _ => file,
+        };
+        let mut year = None;
+        let mut words: Vec<String> = Vec::new();
+        for (text, bracketed) in split_brackets(file) {
+            for word in split_words(&text) {
+                // A year opening the name is part of the title, e.g. "1979 Revolution"
+                if let Some(hint) = as_year(&word).filter(|_| bracketed || !words.is_empty()) {
+                    year = year.or(Some(hint));
+                } else if !bracketed && !is_noise(&word) {
+                    words.push(word);
+                }
+            }
+        }
+        LocalTitle {
+            name: words.join(" "),
+            year,
+        }
+    }
+}
+
+/// Splits a name into its parts outside and inside brackets, flagging the latter
+fn split_brackets(name: &str) -> Vec<(String, bool)> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    for c in name.chars() {
+        match c {
+            '(' | '[' | '{' => {
+                parts.push((std::mem::take(&mut current), depth > 0));
+                depth += 1;
+            }
+            ')' | ']' | '}' if depth > 0 => {
+                parts.push((std::mem::take(&mut current), true));
+                depth -= 1;
+            }
+            c => current.push(c),
+        }
+    }
+    parts.push((current, depth > 0));
+    parts
+}
+
+/// Splits text into words at spaces, underscores, dots outside versions, case changes and
+/// digits following letters
+///
+/// Tokens that are noise as a whole ("x64", "v1.5.78") are kept whole, and hyphenated
+/// tokens are only split when one of their parts is noise ("x64-Setup", not "Half-Life").
+fn split_words(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        let previous = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1).copied();
+        // Dots inside versions ("1.5.78") are kept, the others separate words
+        let separator = c.is_whitespace()
+            || c == '_'
+            || (c == '.'
+                && !(previous.is_some_and(|p| p.is_ascii_digit())
+                    && next.is_some_and(|n| n.is_ascii_digit())));
+        if separator {
+            tokens.push(std::mem::take(&mut token));
+        } else {
+            token.push(c);
+        }
+    }
+    tokens.push(token);
+    let mut words = Vec::new();
+    for token in tokens {
+        let token = token.trim_matches(['-', ',', ':']);
+        if token.contains('-') && token.split('-').any(is_noise) {
+            words.extend(token.split('-').flat_map(split_case));
+        } else {
+            words.extend(split_case(token));
+        }
+    }
+    words.retain(|word| !word.is_empty());
+    words
+}
+
+/// Splits a token at case changes ("HollowKnight") and digits following letters ("Portal2"),
+/// unless it is noise or a year as a whole
+fn split_case(token: &str) -> Vec<String> {
+    if is_noise(token) || as_year(token).is_some() {
+        return vec![token.to_string()];
+    }
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut previous: Option<char> = None;
+    for c in token.chars() {
+        let boundary = previous.is_some_and(|p| {
+            (p.is_lowercase() && c.is_uppercase()) || (p.is_alphabetic() && c.is_ascii_digit())
+        });
+        if boundary {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(c);
+        previous = Some(c);
+    }
+    words.push(word);
+    words
+}
+
+/// Reads a word as a release year
+fn as_year(word: &str) -> Option<i32> {
+    let year: i32 = word.parse().ok()?;
+    (word.len() == 4 && (1970..=2039).contains(&year)).then_some(year)
+}
+
+/// Checks whether a word is a stop word or a version
+fn is_noise(word: &str) -> bool {
+    let lowercase = word.to_lowercase();
+    if STOP_WORDS.contains(&lowercase.as_str()) {
+        return true;
+    }
+    let version = lowercase.strip_prefix('v').unwrap_or(&lowercase);
+    // "1.5.78" or "v2", but not a plain number, which may be part of the title
+    version.contains('.') && version.split('.').all(|part| part.parse::<u32>().is_ok())
+        || (version.len() < lowercase.len()
+            && !version.is_empty()
+            && version.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(raw: &str) -> (String, Option<i32>) {
+        let title = LocalTitle::parse(raw);
+        (title.name, title.year)
+    }
+
+    #[test]
+    fn test_parse_local_title() {
+        assert_eq!(
+            parsed("Hollow_Knight_v1.5.78 [GOG]"),
+            ("Hollow Knight".to_string(), None)
+        );
+        assert_eq!(parsed("DOOM (1993)"), ("DOOM".to_string(), Some(1993)));
+        assert_eq!(
+            parsed(r"C:\Games\Portal2\portal2.exe"),
+            ("portal 2".to_string(), None)
+        );
+        assert_eq!(
+            parsed("/home/me/Desktop/HollowKnight.desktop"),
+            ("Hollow Knight".to_string(), None)
+        );
+        assert_eq!(
+            parsed("The.Witcher.3.Wild.Hunt.GOTY.2016.x64-Setup"),
+            ("The Witcher 3 Wild Hunt".to_string(), Some(2016))
+        );
+        assert_eq!(
+            parsed("1979 Revolution Black Friday"),
+            ("1979 Revolution Black Friday".to_string(), None)
+        );
+        assert_eq!(
+            parsed("Half-Life 2 Launcher.lnk"),
+            ("Half-Life 2".to_string(), None)
+        );
+    }
+}
@@ -0,0 +1,45 @@
+//! Node.js bindings built with napi-rs.
+//!
+//! Build with `cargo build --release --features node` and load the resulting
+//! library (renamed to `howlongtobeat.node`) from Node or Electron:
+//!
+//! ```js
+//! const hltb = require('./howlongtobeat.node');
+//! const game = await hltb.searchByName('Metal Gear');
+//! ```
+
+use crate::{search_by_id_with_sandbox, search_by_name_with_sandbox, spawn_lookup, Game};
+use napi::{Error, Result};
+use napi_derive::napi;
+use serde_json::Value;
+
+/// Converts the outcome of a lookup to a JavaScript value or exception
+///
+/// # Arguments
+///
+/// * `result`:  Result<Game, Box<dyn Error + Send + Sync>> - The outcome of the lookup
+///
+/// returns: Result<Value, Error>
+fn to_js(
+    result: std::result::Result<Game, Box<dyn std::error::Error + Send + Sync>>,
+) -> Result<Value> {
+    let game = result.map_err(|e| Error::from_reason(e.to_string()))?;
+    serde_json::to_value(game).map_err(|e| Error::from_reason(e.to_string()))
+}
+
+/// Searches for a game by name, resolving to the game object
+#[napi]
+pub async fn search_by_name(name: String, sandbox: Option<bool>) -> Result<Value> {
+    let sandbox = sandbox.unwrap_or(true);
+    to_js(
+        spawn_lookup(move || async move { search_by_name_with_sandbox(&name, sandbox).await })
+            .await,
+    )
+}
+
+/// Fetches a game by its How Long to Beat ID, resolving to the game object
+#[napi]
+pub async fn search_by_id(hltb_id: u32, sandbox: Option<bool>) -> Result<Value> {
+    let sandbox = sandbox.unwrap_or(true);
+    to_js(spawn_lookup(move || search_by_id_with_sandbox(hltb_id, sandbox)).await)
+}
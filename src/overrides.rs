@@ -0,0 +1,118 @@
+//! Manual mapping of titles to games.
+//!
+//! Some titles never resolve to the right game: the search picks a remake, a
+//! homonym or nothing at all. A [`TitleOverrides`] table maps such titles to the
+//! ID of their game on How Long to Beat, and a client built with one skips the
+//! search for them, so batch jobs give the same answer every time once their
+//! problematic titles are mapped. Titles are compared once normalized, so
+//! "Pokémon: Let's Go" and "pokemon lets go" share an entry.
+
+use crate::parse::normalize_title;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// A table of titles mapped to the ID of their game on How Long to Beat
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TitleOverrides {
+    /// The IDs of the games by normalized title
+    entries: HashMap<String, u32>,
+}
+
+impl TitleOverrides {
+    /// Creates an empty table
+    ///
+    /// returns: TitleOverrides
+    pub fn new() -> TitleOverrides {
+        TitleOverrides::default()
+    }
+
+    /// Reads a table from a JSON object of titles and IDs, e.g. `{"Doom": 2708}`
+    ///
+    /// # Arguments
+    ///
+    /// * `content`:  &str - The JSON object
+    ///
+    /// returns: Result<TitleOverrides, Box<dyn Error, Global>>
+    pub fn from_json(content: &str) -> Result<TitleOverrides, Box<dyn Error>> {
+        let mapping: HashMap<String, u32> = serde_json::from_str(content)?;
+        let mut overrides = TitleOverrides::new();
+        for (title, hltb_id) in mapping {
+            overrides.insert(&title, hltb_id);
+        }
+        Ok(overrides)
+    }
+
+    /// Reads a table from a JSON file, see [`TitleOverrides::from_json`]
+    ///
+    /// # Arguments
+    ///
+    /// * `path`:  impl AsRef<Path> - The path of the file
+    ///
+    /// returns: Result<TitleOverrides, Box<dyn Error, Global>>
+    pub fn load(path: impl AsRef<Path>) -> Result<TitleOverrides, Box<dyn Error>> {
+        TitleOverrides::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Maps a title to a game, replacing its previous entry if any
+    ///
+    /// # Arguments
+    ///
+    /// * `title`:  &str - The title, as it is searched
+    /// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+    ///
+    /// returns: Option<u32> - The ID the title was mapped to before, if any
+    pub fn insert(&mut self, title: &str, hltb_id: u32) -> Option<u32> {
+        self.entries.insert(normalize_title(title), hltb_id)
+    }
+
+    /// Returns the ID of the game a title is mapped to, if any
+    ///
+    /// # Arguments
+    ///
+    /// * `title`:  &str - The title, in any spelling [`normalize_title`] folds
+    ///
+    /// returns: Option<u32>
+    pub fn get(&self, title: &str) -> Option<u32> {
+        self.entries.get(&normalize_title(title)).copied()
+    }
+
+    /// Adds the entries of another table, which win over the entries of this one
+    ///
+    /// # Arguments
+    ///
+    /// * `other`:  TitleOverrides - The other table
+    pub fn merge(&mut self, other: TitleOverrides) {
+        self.entries.extend(other.entries);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_overrides() {
+        let mut overrides =
+            TitleOverrides::from_json(r#"{"Pokémon: Let's Go": 61592, "Doom": 2708}"#).unwrap();
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides.get("pokemon lets go"), Some(61592));
+        assert_eq!(overrides.get("DOOM"), Some(2708));
+        assert_eq!(overrides.get("Doom 2"), None);
+
+        let mut newer = TitleOverrides::new();
+        newer.insert("doom", 2709);
+        overrides.merge(newer);
+        assert_eq!(overrides.get("Doom"), Some(2709));
+        assert!(TitleOverrides::from_json(r#"{"Doom": "2708"}"#).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+//! Personal pace calibration.
+//!
+//! The times of the site are those of other players. A [`PaceModel`] is fitted
+//! on a few games the user has finished, comparing their actual times with the
+//! site's, and scales the site's times to the user's pace ("you take 1.3× the
+//! average").
+
+use crate::{EstimateKind, Game, HltbError, PlayStyle};
+use std::error::Error;
+use std::fmt;
+
+/// A game the user has finished, with how long it took them
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    pub game: Game,
+    /// The play style the game was finished in
+    pub style: PlayStyle,
+    /// How long it took the user in seconds
+    pub seconds: f32,
+}
+
+/// How much slower or faster than other players the user is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaceModel {
+    /// The factor applied to the times of the site, e.g. 1.3 for a user taking 30% longer
+    pub multiplier: f32,
+    /// Which of the times of the site the user is compared with
+    pub kind: EstimateKind,
+    /// The number of completions the model was fitted on
+    pub samples: usize,
+}
+
+impl PaceModel {
+    /// Fits a model on the completions of the user
+    ///
+    /// The multiplier is the geometric mean of the ratios between the user's times and the
+    /// site's, so taking twice as long on one game and half as long on another evens out.
+    /// Completions without a time on the site, or with a time of zero, are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `completions`:  &[Completion] - The games the user has finished
+    /// * `kind`:  EstimateKind - Which of the times of the site to compare with
+    ///
+    /// returns: Result<PaceModel, Box<dyn Error, Global>> - An error if no completion can be used
+    pub fn fit(
+        completions: &[Completion],
+        kind: EstimateKind,
+    ) -> Result<PaceModel, Box<dyn Error>> {
+        let log_ratios: Vec<f32> = completions
+            .iter()
+            .filter(|completion| completion.seconds > 0.0)
+            .filter_map(|completion| {
+                let estimate = completion.game.estimate(completion.style, kind)?;
+                (estimate > 0.0).then(|| (completion.seconds / estimate).ln())
+            })
+            .collect();
+        if log_ratios.is_empty() {
+            return Err(HltbError::MissingData("Completed game with a time on the site").into());
+        }
+        let mean = log_ratios.iter().sum::<f32>() / log_ratios.len() as f32;
+        Ok(PaceModel {
+            multiplier: mean.exp(),
+            kind,
+            samples: log_ratios.len(),
+        })
+    }
+
+    /// Predicts how long a game will take the user
+    ///
+    /// # Arguments
+    ///
+    /// * `game`:  &Game - The game
+    /// * `style`:  PlayStyle - The play style the user will play it in
+    ///
+    /// returns: Option<f32> - The time in seconds, None if the site has no time for the play style
+    pub fn predict(&self, game: &Game, style: PlayStyle) -> Option<f32> {
+        game.estimate(style, self.kind)
+            .map(|seconds| seconds * self.multiplier)
+    }
+}
+
+/// The model of a user playing at the pace of the site's average
+impl Default for PaceModel {
+    fn default() -> Self {
+        PaceModel {
+            multiplier: 1.0,
+            kind: EstimateKind::Average,
+            samples: 0,
+        }
+    }
+}
+
+impl fmt::Display for PaceModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = format!("{:?}", self.kind).to_lowercase();
+        write!(f, "{:.1}× the {}", self.multiplier, kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Styles;
+
+    fn completion(main_story: Option<f32>, seconds: f32) -> Completion {
+        let styles = main_story.map(|average| Styles::new(Some(average), None, None, None));
+        Completion {
+            game: Game::new("Game".to_string(), 1, styles, None, None, None, None, None),
+            style: PlayStyle::MainStory,
+            seconds,
+        }
+    }
+
+    #[test]
+    fn test_fit_and_predict() {
+        let model = PaceModel::fit(
+            &[
+                completion(Some(3600.0), 7200.0),
+                completion(Some(7200.0), 3600.0),
+                completion(Some(3600.0), 3600.0),
+                completion(None, 3600.0),
+            ],
+            EstimateKind::Average,
+        )
+        .unwrap();
+        assert_eq!(model.samples, 3);
+        assert!((model.multiplier - 1.0).abs() < 1e-5);
+
+        let model =
+            PaceModel::fit(&[completion(Some(3600.0), 4680.0)], EstimateKind::Average).unwrap();
+        assert_eq!(model.to_string(), "1.3× the average");
+        let predicted = model.predict(&completion(Some(36000.0), 0.0).game, PlayStyle::MainStory);
+        assert!((predicted.unwrap() - 46800.0).abs() < 1.0);
+
+        assert!(PaceModel::fit(&[completion(None, 3600.0)], EstimateKind::Average).is_err());
+    }
+}
@@ -0,0 +1,1232 @@
+//! Parsing of fetched pages.
+//!
+//! These functions only work on HTML, without any network access, so pages can be
+//! fetched by any means (the browser or HTTP backends, a cache, a test fixture)
+//! and parsed here.
+
+use crate::platform::Platform;
+use crate::release::{ReleaseDate, ReleaseDates};
+use crate::{convert_hours_minutes_to_sec_opt, Focus, Game, HltbError, Styles};
+use scraper::{ElementRef, Html, Selector};
+use std::error::Error;
+
+/// The first result of the search page, rendered once the search has completed
+pub(crate) const SEARCH_RESULT_SELECTOR: &str =
+    "#search-results-header > ul > li:nth-child(1) > div > div[class*='_search_list_image'] > a";
+
+/// The header of the search results, rendered once the search has completed, with or
+/// without results
+pub(crate) const SEARCH_HEADER_SELECTOR: &str = "#search-results-header";
+
+/// The container of the search results, which also states the spelling the site suggests
+/// for a search without results
+#[cfg(feature = "browser")]
+pub(crate) const SEARCH_SUGGESTION_SELECTOR: &str = "div:has(> #search-results-header)";
+
+/// The header of a details page, with the title and its focus labels
+pub(crate) const HEADER_SELECTOR: &str = "#__next > div > main > div:nth-child(1)";
+
+/// The table of times of a details page
+pub(crate) const DETAILS_TABLE_SELECTOR: &str = "#__next > div > main > div:nth-child(2) > div > div[class*='content'] > div.in.scrollable.scroll_blue.shadow_box.back_primary > table[class*='_game_main_table']";
+
+/// The "Submit Your Time" link of a details page, which embeds the ID of the game
+pub(crate) const SUBMIT_LINK_SELECTOR: &str = "a[href*='submit?gid=']";
+
+/// The links to the details pages of games
+pub(crate) const GAME_LINK_SELECTOR: &str = "a[href*='/game/']";
+
+/// Parses the ID of the game a link to its details page leads to, e.g. 5900 for "/game/5900"
+///
+/// # Arguments
+///
+/// * `href`:  &str - The target of the link
+///
+/// returns: Option<u32>
+pub fn game_id_from_href(href: &str) -> Option<u32> {
+    let path = href.split(['?', '#']).next()?;
+    path.trim_end_matches('/').rsplit('/').next()?.parse().ok()
+}
+
+/// A game linked from a part of a page
+#[cfg(feature = "browser")]
+pub(crate) struct GameCard<'a> {
+    pub(crate) hltb_id: u32,
+    pub(crate) title: String,
+    /// The first link to the game
+    pub(crate) link: ElementRef<'a>,
+}
+
+/// Lists the games linked from a part of a page, in the order of their first link, each once
+///
+/// Cards link their image and their title, only the latter having text, so a game is titled
+/// by the first of its links with a text or a `title` attribute.
+///
+/// # Arguments
+///
+/// * `root`:  ElementRef - The part of the page
+/// * `link_selector`:  &Selector - The links to the games, e.g. [`GAME_LINK_SELECTOR`]
+///
+/// returns: Vec<GameCard, Global>
+#[cfg(feature = "browser")]
+pub(crate) fn game_cards<'a>(root: ElementRef<'a>, link_selector: &Selector) -> Vec<GameCard<'a>> {
+    let mut cards: Vec<GameCard> = Vec::new();
+    for link in root.select(link_selector) {
+        let Some(hltb_id) = link.value().attr("href").and_then(game_id_from_href) else {
+            continue;
+        };
+        let text = link.text().collect::<String>().trim().to_string();
+        let title = link
+            .value()
+            .attr("title")
+            .map(str::to_string)
+            .unwrap_or(text);
+        match cards.iter_mut().find(|card| card.hltb_id == hltb_id) {
+            Some(card) if card.title.is_empty() => card.title = title,
+            Some(_) => {}
+            None => cards.push(GameCard {
+                hltb_id,
+                title,
+                link,
+            }),
+        }
+    }
+    cards
+}
+
+/// Parses the ID of the first result of a rendered search page
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the search page
+///
+/// returns: Result<u32, Box<dyn Error, Global>>
+pub fn search_result(content: &str) -> Result<u32, Box<dyn Error>> {
+    let document = Html::parse_document(content);
+    let selector = Selector::parse(SEARCH_RESULT_SELECTOR).unwrap();
+
+    for element in document.select(&selector) {
+        if let Some(link) = element.value().attr("href") {
+            return game_id_from_href(link).ok_or_else(|| {
+                HltbError::Unexpected(format!("the first result links to {}", link)).into()
+            });
+        }
+    }
+    Err(HltbError::ElementNotFound(SEARCH_RESULT_SELECTOR.to_string()).into())
+}
+
+/// The list of results of the search page
+pub(crate) const SEARCH_RESULTS_SELECTOR: &str = "#search-results-header > ul > li";
+
+/// Parses the total number of results of a rendered search page, as its header states it
+///
+/// The header reads e.g. "We Found 1,024 Games for ...": the site paginates the results,
+/// so the count is usually larger than the number of results of the page. Pages without
+/// a count in their header are counted by their results, none for a search without results.
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the search page
+///
+/// returns: Result<u32, Box<dyn Error, Global>>
+pub fn result_count(content: &str) -> Result<u32, Box<dyn Error>> {
+    let document = Html::parse_document(content);
+    let header_selector = Selector::parse(SEARCH_HEADER_SELECTOR).unwrap();
+    let heading_selector = Selector::parse("h1, h2, h3, h4, h5").unwrap();
+    let Some(header) = document.select(&header_selector).next() else {
+        return Err(HltbError::ElementNotFound(SEARCH_HEADER_SELECTOR.to_string()).into());
+    };
+    let count = header
+        .select(&heading_selector)
+        .map(|heading| heading.text().collect::<String>())
+        .filter(|text| text.to_lowercase().contains("found"))
+        .find_map(|text| {
+            text.split_whitespace()
+                .find_map(|word| word.replace(',', "").parse::<u32>().ok())
+        });
+    if let Some(count) = count {
+        return Ok(count);
+    }
+    let result_selector = Selector::parse(SEARCH_RESULTS_SELECTOR).unwrap();
+    let link_selector = Selector::parse(GAME_LINK_SELECTOR).unwrap();
+    Ok(document
+        .select(&result_selector)
+        .filter(|result| result.select(&link_selector).next().is_some())
+        .count() as u32)
+}
+
+/// Parses the spelling the site suggests on a search page without results, e.g. "Did you
+/// mean Metal Gear?"
+///
+/// The suggestion is the first link of the element stating it, or the text after
+/// "did you mean" when it has no link.
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the search page
+///
+/// returns: Option<String>
+pub fn suggestion(content: &str) -> Option<String> {
+    const PROMPT: &str = "did you mean";
+    let document = Html::parse_document(content);
+    let selector = Selector::parse("body *").unwrap();
+    let link_selector = Selector::parse("a").unwrap();
+    // The innermost element with the whole prompt, elements coming after their ancestors
+    let element = document.select(&selector).rfind(|element| {
+        let text = element.text().collect::<String>().to_lowercase();
+        text.contains(PROMPT)
+    })?;
+    let suggestion = match element.select(&link_selector).next() {
+        Some(link) => link.text().collect::<String>(),
+        None => {
+            let text = element.text().collect::<String>();
+            let start = text.to_lowercase().find(PROMPT)? + PROMPT.len();
+            text.get(start..)?.to_string()
+        }
+    };
+    let suggestion = suggestion
+        .trim()
+        .trim_end_matches('?')
+        .trim_matches(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '“' | '”'));
+    (!suggestion.is_empty()).then(|| suggestion.to_string())
+}
+
+/// A game listed on the search page
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct SearchResult {
+    pub hltb_id: u32,
+    pub title: String,
+    /// The URL of the search page the result was listed on, when fetched by a client
+    #[serde(default)]
+    pub search_url: Option<String>,
+}
+
+impl SearchResult {
+    /// Returns the canonical URL of the details page of the game
+    ///
+    /// returns: String
+    pub fn url(&self) -> String {
+        crate::game_url(self.hltb_id)
+    }
+}
+
+/// Parses every result of a rendered search page, in the order of the page
+///
+/// Results without a link to a game are skipped.
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the search page
+///
+/// returns: Vec<SearchResult>
+pub fn search_results(content: &str) -> Vec<SearchResult> {
+    search_results_with_cleaning(content, &TitleCleaning::default())
+}
+
+/// Parses every result of a rendered search page, cleaning their titles as configured
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the search page
+/// * `cleaning`:  &TitleCleaning - How to clean the titles
+///
+/// returns: Vec<SearchResult>
+pub fn search_results_with_cleaning(content: &str, cleaning: &TitleCleaning) -> Vec<SearchResult> {
+    let document = Html::parse_document(content);
+    let result_selector = Selector::parse(SEARCH_RESULTS_SELECTOR).unwrap();
+    let link_selector = Selector::parse(GAME_LINK_SELECTOR).unwrap();
+    let title_selector = Selector::parse("h2 a, h3 a").unwrap();
+    document
+        .select(&result_selector)
+        .filter_map(|result| {
+            let link = result.select(&link_selector).next()?;
+            let hltb_id = game_id_from_href(link.value().attr("href")?)?;
+            let title = result
+                .select(&title_selector)
+                .next()
+                .map(|title| title.text().collect::<String>())
+                .or_else(|| link.value().attr("title").map(str::to_string))
+                .unwrap_or_else(|| link.text().collect());
+            Some(SearchResult {
+                hltb_id,
+                title: cleaning.clean(&title),
+                search_url: None,
+            })
+        })
+        .collect()
+}
+
+/// How titles are cleaned when they are parsed
+///
+/// Every step is enabled by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct TitleCleaning {
+    /// Removes the trademark symbols ™, ® and ℠, e.g. "DOOM™ Eternal" becomes "DOOM Eternal"
+    pub strip_trademarks: bool,
+    /// Removes leftover markup such as HTML comments ("<!-- -->") and tags
+    pub strip_markup: bool,
+    /// Trims the title and turns runs of whitespace, including non-breaking spaces, into a
+    /// single space
+    pub collapse_whitespace: bool,
+}
+
+impl Default for TitleCleaning {
+    fn default() -> Self {
+        TitleCleaning {
+            strip_trademarks: true,
+            strip_markup: true,
+            collapse_whitespace: true,
+        }
+    }
+}
+
+impl TitleCleaning {
+    /// Keeps titles as they are on the page, only trimmed
+    ///
+    /// returns: TitleCleaning
+    pub fn none() -> TitleCleaning {
+        TitleCleaning {
+            strip_trademarks: false,
+            strip_markup: false,
+            collapse_whitespace: false,
+        }
+    }
+
+    /// Cleans a title
+    ///
+    /// # Arguments
+    ///
+    /// * `title`:  &str - The title as found on the page
+    ///
+    /// returns: String
+    pub fn clean(&self, title: &str) -> String {
+        let mut title = title.to_string();
+        if self.strip_markup {
+            title = strip_markup(&title);
+        }
+        if self.strip_trademarks {
+            title.retain(|c| !matches!(c, '™' | '®' | '℠'));
+        }
+        if self.collapse_whitespace {
+            title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+        }
+        title.trim().to_string()
+    }
+}
+
+/// Removes HTML comments and tags from a text, leaving other uses of "<" alone (e.g. "<3")
+fn strip_markup(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        result.push_str(&rest[..start]);
+        let markup = &rest[start..];
+        let end = if markup.starts_with("<!--") {
+            markup.find("-->").map(|end| end + 3)
+        } else if markup[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/') {
+            markup.find('>').map(|end| end + 1)
+        } else {
+            None
+        };
+        match end {
+            Some(end) => rest = &markup[end..],
+            None => {
+                result.push('<');
+                rest = &markup[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// How strictly pages are parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+pub enum ParseMode {
+    /// Parses what it can and leaves the rest as `None`, for production apps
+    #[default]
+    Lenient,
+    /// Fails on any unexpected structure (unknown rows, missing cells, unreadable times), to
+    /// notice when the site changes
+    Strict,
+}
+
+/// Parses the details page of a game, leniently
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the details page
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+///
+/// returns: Result<Game, Box<dyn Error, Global>>
+pub fn game(content: &str, hltb_id: u32) -> Result<Game, Box<dyn Error>> {
+    game_with_mode(content, hltb_id, ParseMode::Lenient)
+}
+
+/// Parses the details page of a game
+///
+/// The title and the table of times are required in both modes.
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the details page
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+/// * `mode`:  ParseMode - How to handle the parts of the page that can't be parsed
+///
+/// returns: Result<Game, Box<dyn Error, Global>>
+pub fn game_with_mode(
+    content: &str,
+    hltb_id: u32,
+    mode: ParseMode,
+) -> Result<Game, Box<dyn Error>> {
+    game_with_cleaning(content, hltb_id, mode, &TitleCleaning::default())
+}
+
+/// Parses the details page of a game, cleaning its title as configured
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the details page
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+/// * `mode`:  ParseMode - How to handle the parts of the page that can't be parsed
+/// * `cleaning`:  &TitleCleaning - How to clean the title
+///
+/// returns: Result<Game, Box<dyn Error, Global>>
+pub fn game_with_cleaning(
+    content: &str,
+    hltb_id: u32,
+    mode: ParseMode,
+    cleaning: &TitleCleaning,
+) -> Result<Game, Box<dyn Error>> {
+    let document = Html::parse_document(content);
+    let title_selector = Selector::parse(&format!(
+        "{} > div > div > div > div[class*='_profile_header']",
+        HEADER_SELECTOR
+    ))
+    .unwrap();
+    // The text rather than the HTML, so entities ("&amp;") are decoded and comments dropped,
+    // without the focus labels shown next to the title
+    let title = document
+        .select(&title_selector)
+        .next()
+        .ok_or(HltbError::MissingData("Game title"))?
+        .text()
+        .filter(|text| Focus::from_label(text).is_none())
+        .collect::<String>();
+    let title = cleaning.clean(&title);
+    let table_selector = Selector::parse(DETAILS_TABLE_SELECTOR).unwrap();
+    // Games with single-player and multi-player modes have one table of each, while
+    // multiplayer-only games only have the multi-player one
+    let tables: Vec<_> = document.select(&table_selector).collect();
+    if tables.is_empty() {
+        return Err(HltbError::MissingData("Game times").into());
+    }
+    let tr_selector = Selector::parse("tbody > tr").unwrap();
+    let rows: Vec<_> = tables
+        .iter()
+        .flat_map(|table| table.select(&tr_selector))
+        .collect();
+
+    let mut main_story = None;
+    let mut main_extra = None;
+    let mut completionist = None;
+    let mut all_styles = None;
+    let mut co_op = None;
+    let mut vs = None;
+
+    if mode == ParseMode::Strict && rows.is_empty() {
+        return Err(HltbError::Unexpected("the table of times has no rows".to_string()).into());
+    }
+
+    let td_selector = Selector::parse("td").unwrap();
+    for row in rows {
+        if let Some(first_cell) = row.select(&td_selector).next() {
+            let row_type = first_cell.inner_html().trim().to_string();
+            match row_type.as_str() {
+                "Main Story" => main_story = Some(parse_row(row, mode)?),
+                "Main + Extra" | "Main + Extras" => main_extra = Some(parse_row(row, mode)?),
+                "Completionist" | "Completionists" => completionist = Some(parse_row(row, mode)?),
+                "All PlayStyles" => all_styles = Some(parse_row(row, mode)?),
+                "Co-Op" => co_op = Some(parse_row(row, mode)?),
+                "Competitive" => vs = Some(parse_row(row, mode)?),
+                _ if mode == ParseMode::Strict => {
+                    let row = format!("unknown row \"{}\"", row_type);
+                    return Err(HltbError::Unexpected(row).into());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut game = Game::new(
+        title,
+        hltb_id,
+        main_story,
+        main_extra,
+        completionist,
+        all_styles,
+        co_op,
+        vs,
+    );
+    let info = parse_profile_info(&document);
+    game.platforms = parse_platforms(&info);
+    game.release_year = parse_release_year(&info);
+    game.aliases = parse_aliases(&info);
+    game.focus = parse_focus(&document);
+    let page_id = submit_id(&document);
+    game.id_verified = page_id.map(|id| id == hltb_id);
+    // Merged entries redirect to the canonical one, whose ID the game takes
+    if let Some(id) = page_id.filter(|id| *id != hltb_id) {
+        game.hltb_id = id;
+        game.redirected_from = Some(hltb_id);
+    }
+    Ok(game)
+}
+
+/// Parses the ID embedded in the "Submit Your Time" link of a details page
+///
+/// It is the ID the site has for the game, which differs from the requested one when the
+/// site served the page of another entry, e.g. after merging duplicates.
+///
+/// # Arguments
+///
+/// * `document`:  &Html - The details page
+///
+/// returns: Option<u32> - None if the page has no submit link
+fn submit_id(document: &Html) -> Option<u32> {
+    let selector = Selector::parse(SUBMIT_LINK_SELECTOR).unwrap();
+    document.select(&selector).find_map(|link| {
+        let (_, query) = link.value().attr("href")?.split_once('?')?;
+        query
+            .split('&')
+            .find_map(|param| param.strip_prefix("gid="))?
+            .parse()
+            .ok()
+    })
+}
+
+/// Extracts the `__NEXT_DATA__` JSON that Next.js embeds in a page, with the data it was
+/// rendered from
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the page
+///
+/// returns: Option<Value>
+pub fn next_data(content: &str) -> Option<serde_json::Value> {
+    let document = Html::parse_document(content);
+    let selector = Selector::parse("script#__NEXT_DATA__").unwrap();
+    let script = document.select(&selector).next()?;
+    serde_json::from_str(&script.text().collect::<String>()).ok()
+}
+
+/// Reads the URL of the cover image of a details page, from its `__NEXT_DATA__` JSON
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the details page
+///
+/// returns: Option<String> - None if the page has no cover
+pub fn cover_url(content: &str) -> Option<String> {
+    let data = next_data(content)?;
+    let image = data["props"]["pageProps"]["game"]["data"]["game"][0]["game_image"]
+        .as_str()?
+        .trim();
+    if image.is_empty() {
+        None
+    } else if image.starts_with("https://") || image.starts_with("http://") {
+        Some(image.to_string())
+    } else {
+        Some(format!(
+            "{}games/{}",
+            crate::BASE_URL,
+            urlencoding::encode(image)
+        ))
+    }
+}
+
+/// The regions the site gives release dates for
+const RELEASE_REGIONS: &[&str] = &["NA", "EU", "JP", "WW"];
+
+/// Parses the release dates of a details page, by region and by platform
+///
+/// Regional dates come from the profile info ("NA: June 1988"). Platform dates come from
+/// profile info labelled with one of the platforms of the game, and from tables with a
+/// "Release" column whose first column is the platform.
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the details page
+///
+/// returns: ReleaseDates
+pub fn release_dates(content: &str) -> ReleaseDates {
+    let document = Html::parse_document(content);
+    let info = parse_profile_info(&document);
+    let platforms = parse_platforms(&info);
+    let mut dates = ReleaseDates::default();
+    for (label, value) in &info {
+        let Some(date) = ReleaseDate::parse(value) else {
+            continue;
+        };
+        if RELEASE_REGIONS.contains(&label.as_str()) {
+            dates.by_region.insert(label.clone(), date);
+        } else if platforms.contains(&Platform::from(label.as_str())) {
+            dates
+                .by_platform
+                .insert(Platform::from(label.as_str()), date);
+        }
+    }
+
+    let table_selector = Selector::parse("table").unwrap();
+    let header_selector = Selector::parse("thead tr").unwrap();
+    let row_selector = Selector::parse("tbody > tr").unwrap();
+    let cell_selector = Selector::parse("td, th").unwrap();
+    let cell_text = |cell: ElementRef| cell.text().collect::<String>().trim().to_string();
+    for table in document.select(&table_selector) {
+        let Some(header) = table.select(&header_selector).next() else {
+            continue;
+        };
+        let Some(column) = header
+            .select(&cell_selector)
+            .position(|cell| cell_text(cell).starts_with("Release"))
+        else {
+            continue;
+        };
+        for row in table.select(&row_selector) {
+            let cells: Vec<String> = row.select(&cell_selector).map(cell_text).collect();
+            let date = cells.get(column).and_then(|cell| ReleaseDate::parse(cell));
+            if let (Some(platform), Some(date)) = (cells.first(), date) {
+                dates
+                    .by_platform
+                    .insert(Platform::from(platform.as_str()), date);
+            }
+        }
+    }
+    dates
+}
+
+/// Parses the label/value pairs of the profile info of a details page (e.g. "Platforms", "NA")
+///
+/// # Arguments
+///
+/// * `document`:  &Html - The details page
+///
+/// returns: Vec<(String, String)>
+fn parse_profile_info(document: &Html) -> Vec<(String, String)> {
+    let selector = Selector::parse("div[class*='_profile_info']").unwrap();
+    document
+        .select(&selector)
+        .filter_map(|element| {
+            let text = element.text().collect::<String>();
+            let (label, value) = text.split_once(':')?;
+            Some((label.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Parses the focus labels shown in the header of a details page
+///
+/// Labels are the elements of the header without child elements whose text is a known label.
+///
+/// # Arguments
+///
+/// * `document`:  &Html - The details page
+///
+/// returns: Vec<Focus>
+fn parse_focus(document: &Html) -> Vec<Focus> {
+    let selector = Selector::parse(&format!("{} *", HEADER_SELECTOR)).unwrap();
+    let mut focus = Vec::new();
+    for element in document.select(&selector) {
+        if element.children().any(|child| child.value().is_element()) {
+            continue;
+        }
+        let text = element.text().collect::<String>();
+        match Focus::from_label(&text) {
+            Some(label) if !focus.contains(&label) => focus.push(label),
+            _ => {}
+        }
+    }
+    focus
+}
+
+/// Parses the list of platforms from the profile info of a details page
+///
+/// # Arguments
+///
+/// * `info`:  &[(String, String)] - The profile info of the details page
+///
+/// returns: Vec<Platform>
+fn parse_platforms(info: &[(String, String)]) -> Vec<Platform> {
+    info.iter()
+        .find(|(label, _)| label.starts_with("Platform"))
+        .map(|(_, value)| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(Platform::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parses the other titles of a game from the profile info of a details page
+///
+/// # Arguments
+///
+/// * `info`:  &[(String, String)] - The profile info of the details page
+///
+/// returns: Vec<String>
+fn parse_aliases(info: &[(String, String)]) -> Vec<String> {
+    info.iter()
+        .find(|(label, _)| label.starts_with("Alias"))
+        .map(|(_, value)| {
+            value
+                .split(',')
+                .map(|a| a.trim().to_string())
+                .filter(|a| !a.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parses the earliest release year from the regional release dates of a details page
+///
+/// # Arguments
+///
+/// * `info`:  &[(String, String)] - The profile info of the details page
+///
+/// returns: Option<i32>
+fn parse_release_year(info: &[(String, String)]) -> Option<i32> {
+    info.iter()
+        .filter(|(label, _)| matches!(label.as_str(), "NA" | "EU" | "JP"))
+        .filter_map(|(_, value)| {
+            value
+                .split(|c: char| !c.is_ascii_digit())
+                .filter(|part| part.len() == 4)
+                .find_map(|part| part.parse::<i32>().ok())
+        })
+        .min()
+}
+
+/// Latin letters with diacritics, by the letter they fold to
+const DIACRITICS: &[(&str, char)] = &[
+    ("àáâãäåāăą", 'a'),
+    ("çćĉċč", 'c'),
+    ("ďđ", 'd'),
+    ("èéêëēĕėęě", 'e'),
+    ("ĝğġģ", 'g'),
+    ("ĥħ", 'h'),
+    ("ìíîïĩīĭįı", 'i'),
+    ("ĵ", 'j'),
+    ("ķ", 'k'),
+    ("ĺļľŀł", 'l'),
+    ("ñńņňŉ", 'n'),
+    ("òóôõöøōŏő", 'o'),
+    ("ŕŗř", 'r'),
+    ("śŝşšș", 's'),
+    ("ţťŧț", 't'),
+    ("ùúûüũūŭůűų", 'u'),
+    ("ŵ", 'w'),
+    ("ýÿŷ", 'y'),
+    ("źżž", 'z'),
+];
+
+/// Normalizes a title to compare it with other spellings of the same title
+///
+/// The title is lowercased, full-width forms are folded to ASCII, accents are removed from
+/// Latin letters, and punctuation and whitespace are collapsed to single spaces. Other
+/// scripts (Japanese, Cyrillic...) are kept as they are.
+///
+/// # Arguments
+///
+/// * `title`:  &str - The title to normalize, e.g. "Pokémon: Let's Go"
+///
+/// returns: String
+pub fn normalize_title(title: &str) -> String {
+    let mut normalized = String::new();
+    for c in title.chars().map(fold_width).flat_map(char::to_lowercase) {
+        match c {
+            'ß' => normalized.push_str("ss"),
+            'æ' => normalized.push_str("ae"),
+            'œ' => normalized.push_str("oe"),
+            '\'' | '’' => {}
+            c if c.is_alphanumeric() => normalized.push(fold_diacritic(c)),
+            _ if !normalized.is_empty() && !normalized.ends_with(' ') => normalized.push(' '),
+            _ => {}
+        }
+    }
+    normalized.trim_end().to_string()
+}
+
+/// Scores how similar two titles are, after [`normalize_title`]
+///
+/// The score is one minus the edit distance between the normalized titles divided by the
+/// length of the longer one, so 1.0 means the same title and 0.0 nothing in common.
+///
+/// # Arguments
+///
+/// * `a`:  &str - A title
+/// * `b`:  &str - Another title
+///
+/// returns: f32 - The similarity, between 0.0 and 1.0
+pub fn title_similarity(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = normalize_title(a).chars().collect();
+    let b: Vec<char> = normalize_title(b).chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    // Levenshtein distance, keeping one row of the table
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    1.0 - row[b.len()] as f32 / longest as f32
+}
+
+/// Folds a full-width character (as typed with a Japanese or Chinese input method) to ASCII
+fn fold_width(c: char) -> char {
+    match c {
+        '\u{3000}' => ' ',
+        '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// Removes the diacritic of a lowercase Latin letter
+fn fold_diacritic(c: char) -> char {
+    DIACRITICS
+        .iter()
+        .find(|(letters, _)| letters.contains(c))
+        .map_or(c, |(_, base)| *base)
+}
+
+/// Parses a time as written in the table of times, e.g. "26h 21m", "83 Hours" or "59½ Hours"
+///
+/// # Arguments
+///
+/// * `text`:  &str - The text of the cell
+///
+/// returns: Option<f32> - The time in seconds, or None for empty ("--") or unreadable times
+pub fn time_cell(text: &str) -> Option<f32> {
+    convert_hours_minutes_to_sec_opt(text)
+}
+
+/// Parses a row of the table of times, e.g.
+/// `<tr><td>Main Story</td><td>1.2K</td><td>4h 10m</td><td>4h</td><td>2h 46m</td><td>7h 12m</td></tr>`
+///
+/// # Arguments
+///
+/// * `html`:  &str - The HTML of the row
+/// * `mode`:  ParseMode - Whether missing cells and unreadable times are errors
+///
+/// returns: Result<Styles, Box<dyn Error, Global>>
+pub fn time_row(html: &str, mode: ParseMode) -> Result<Styles, Box<dyn Error>> {
+    let table = Html::parse_fragment(&format!("<table><tbody>{}</tbody></table>", html));
+    let selector = Selector::parse("tr").unwrap();
+    let row = table
+        .select(&selector)
+        .next()
+        .ok_or(HltbError::MissingData("Row of times"))?;
+    parse_row(row, mode)
+}
+
+/// Parses a row of a table
+///
+/// # Arguments
+///
+/// * `row`:  ElementRef - The row to parse
+/// * `mode`:  ParseMode - Whether missing cells and unreadable times are errors
+///
+/// returns: Result<Styles, Box<dyn Error, Global>>
+fn parse_row(row: ElementRef, mode: ParseMode) -> Result<Styles, Box<dyn Error>> {
+    let selector = Selector::parse("td").unwrap();
+    let cells: Vec<String> = row
+        .select(&selector)
+        .map(|cell| cell.inner_html())
+        .collect();
+    if mode == ParseMode::Strict && cells.len() < 6 {
+        let message = format!("a row of times has {} cells instead of 6", cells.len());
+        return Err(HltbError::Unexpected(message).into());
+    }
+    let mut times = [None; 4];
+    for (time, cell) in times.iter_mut().zip(cells.iter().skip(2)) {
+        *time = time_cell(cell);
+        let blank = matches!(cell.trim(), "" | "--" | "-");
+        if mode == ParseMode::Strict && time.is_none() && !blank {
+            let message = format!("unreadable time \"{}\"", cell.trim());
+            return Err(HltbError::Unexpected(message).into());
+        }
+    }
+    let [average, median, rushed, leisure] = times;
+    Ok(Styles::new(average, median, rushed, leisure))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DETAILS: &str = r#"<html><body><div id="__next"><div><main>
+        <div><div><div><div>
+            <div class="GameHeader_profile_header__q_PID">Metal Gear</div>
+        </div></div></div></div>
+        <div><div><div class="GameSummary_content__x1">
+            <div class="GameSummary_profile_info__e935c">Platforms: NES, MSX2</div>
+            <div class="GameSummary_profile_info__e935c">NA: June 1988</div>
+            <div class="GameSummary_profile_info__e935c">JP: July 13th, 1987</div>
+            <div class="in scrollable scroll_blue shadow_box back_primary">
+                <table class="GameTimeTable_game_main_table__7uN3H"><tbody>
+                    <tr><td>Main Story</td><td>1.2K</td><td>4h 10m</td><td>4h</td><td>2h 46m</td><td>7h 12m</td></tr>
+                    <tr><td>Co-Op</td><td>3</td><td>--</td><td>--</td><td>--</td><td>--</td></tr>
+                </tbody></table>
+            </div>
+        </div></div></div>
+    </main></div></div></body></html>"#;
+
+    #[test]
+    fn test_game() {
+        let game = game(DETAILS, 5900).unwrap();
+        assert_eq!(game.title, "Metal Gear");
+        assert_eq!(game.hltb_id, 5900);
+        assert_eq!(
+            game.main_story,
+            Some(Styles::new(
+                Some(15000.0),
+                Some(14400.0),
+                Some(9960.0),
+                Some(25920.0)
+            ))
+        );
+        assert_eq!(game.co_op, Some(Styles::new(None, None, None, None)));
+        assert_eq!(game.main_extra, None);
+        assert_eq!(game.platforms, vec![Platform::Nes, Platform::Msx2]);
+        assert_eq!(game.release_year, Some(1987));
+        assert!(super::game("<html></html>", 5900).is_err());
+    }
+
+    #[test]
+    fn test_id_verification() {
+        assert_eq!(game(DETAILS, 5900).unwrap().id_verified, None);
+        let content = DETAILS.replace(
+            "</main>",
+            r#"<a href="/submit?gid=5900">Submit Your Time</a></main>"#,
+        );
+        assert_eq!(game(&content, 5900).unwrap().id_verified, Some(true));
+        let redirected = game(&content, 5901).unwrap();
+        assert_eq!(redirected.id_verified, Some(false));
+        assert_eq!(redirected.hltb_id, 5900);
+        assert_eq!(redirected.redirect(), Some((5901, 5900)));
+    }
+
+    #[test]
+    fn test_game_with_non_ascii_titles() {
+        let content = DETAILS
+            .replace(">Metal Gear<", ">Pokémon Red &amp;<!-- --> Blue<")
+            .replace(
+                "Platforms: NES, MSX2",
+                "Aliases: ポケットモンスター 赤・緑, Покемон Красный",
+            );
+        let game = game(&content, 6994).unwrap();
+        assert_eq!(game.title, "Pokémon Red & Blue");
+        assert_eq!(
+            game.aliases,
+            vec!["ポケットモンスター 赤・緑", "Покемон Красный"]
+        );
+        assert_eq!(
+            game.matched_alias("ポケットモンスター　赤・緑"),
+            Some("ポケットモンスター 赤・緑")
+        );
+        assert_eq!(
+            game.matched_alias("покемон красный"),
+            Some("Покемон Красный")
+        );
+        assert_eq!(game.matched_alias("pokemon red & blue"), None);
+        assert_eq!(game.matched_alias("Metal Gear"), None);
+    }
+
+    #[test]
+    fn test_normalize_title() {
+        assert_eq!(
+            normalize_title("Pokémon: Let's Go, Pikachu!"),
+            "pokemon lets go pikachu"
+        );
+        assert_eq!(
+            normalize_title("ＦＩＮＡＬ　ＦＡＮＴＡＳＹ"),
+            "final fantasy"
+        );
+        assert_eq!(normalize_title("  Ōkami  "), "okami");
+        assert_eq!(
+            normalize_title("Сталкер: Тень Чернобыля"),
+            "сталкер тень чернобыля"
+        );
+        assert_eq!(normalize_title("ゼルダの伝説"), "ゼルダの伝説");
+    }
+
+    #[test]
+    fn test_time_row() {
+        assert_eq!(time_cell("59½ Hours"), Some(214200.0));
+        assert_eq!(time_cell("--"), None);
+        let row = "<tr><td>Main Story</td><td>1.2K</td><td>4h</td><td>--</td><td>1h 30m</td><td>abc</td></tr>";
+        assert_eq!(
+            time_row(row, ParseMode::Lenient).unwrap(),
+            Styles::new(Some(14400.0), None, Some(5400.0), None)
+        );
+        assert!(time_row(row, ParseMode::Strict).is_err());
+        assert!(time_row("<p>Main Story</p>", ParseMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn test_multiplayer_tables() {
+        let multi = r#"<table class="GameTimeTable_game_main_table__7uN3H"><tbody>
+            <tr><td>Co-Op</td><td>1.5K</td><td>83 Hours</td><td>59½ Hours</td><td>38½ Hours</td><td>205 Hours</td></tr>
+            <tr><td>Competitive</td><td>416</td><td>31 Hours</td><td>31 Hours</td><td>19 Hours</td><td>43 Hours</td></tr>
+        </tbody></table>"#;
+        let main = DETAILS.replace(
+            "<tr><td>Co-Op</td><td>3</td><td>--</td><td>--</td><td>--</td><td>--</td></tr>",
+            "",
+        );
+        let both = main.replace("</table>", &format!("</table>{}", multi));
+        let game = game_with_mode(&both, 5900, ParseMode::Strict).unwrap();
+        assert!(game.main_story.is_some());
+        assert!(!game.is_multiplayer_only());
+        assert_eq!(game.vs.unwrap().average, Some(111600.0));
+
+        let start = main.find("<table").unwrap();
+        let end = main.find("</table>").unwrap() + "</table>".len();
+        let only = format!("{}{}{}", &main[..start], multi, &main[end..]);
+        let game = game_with_mode(&only, 129232, ParseMode::Strict).unwrap();
+        assert!(game.is_multiplayer_only());
+        assert_eq!(game.main_story, None);
+        assert_eq!(game.co_op.unwrap().median, Some(214200.0));
+    }
+
+    #[test]
+    fn test_focus_labels() {
+        let labelled = DETAILS.replace(
+            ">Metal Gear</div>",
+            ">Metal Gear<span class=\"GameHeader_tag__x\">Single-Player</span> <span>Top-Down</span></div>",
+        );
+        let game = game(&labelled, 5900).unwrap();
+        assert_eq!(game.title, "Metal Gear");
+        assert_eq!(game.focus, vec![Focus::SinglePlayer, Focus::TopDown]);
+        assert!(super::game(DETAILS, 5900).unwrap().focus.is_empty());
+    }
+
+    #[test]
+    fn test_title_cleaning() {
+        let cleaning = TitleCleaning::default();
+        assert_eq!(cleaning.clean("DOOM™ Eternal"), "DOOM Eternal");
+        assert_eq!(
+            cleaning.clean("Tom Clancy's Rainbow Six® Siege"),
+            "Tom Clancy's Rainbow Six Siege"
+        );
+        assert_eq!(
+            cleaning.clean("Halo™: The Master Chief Collection"),
+            "Halo: The Master Chief Collection"
+        );
+        assert_eq!(
+            cleaning.clean("Pokémon Red &<!-- --> Blue"),
+            "Pokémon Red & Blue"
+        );
+        assert_eq!(
+            cleaning.clean(" Metal\u{a0}Gear <i>Solid</i>\n"),
+            "Metal Gear Solid"
+        );
+        assert_eq!(cleaning.clean("I <3 Zelda"), "I <3 Zelda");
+        assert_eq!(
+            TitleCleaning::none().clean(" DOOM™  Eternal "),
+            "DOOM™  Eternal"
+        );
+
+        let branded = DETAILS.replace(">Metal Gear<", ">Metal  Gear®<");
+        assert_eq!(game(&branded, 5900).unwrap().title, "Metal Gear");
+        let raw = game_with_cleaning(&branded, 5900, ParseMode::Lenient, &TitleCleaning::none());
+        assert_eq!(raw.unwrap().title, "Metal  Gear®");
+    }
+
+    #[test]
+    fn test_release_dates() {
+        let table = r#"<table><thead><tr><td>Platform</td><td>Released</td></tr></thead><tbody>
+            <tr><td>MSX2</td><td>July 13th, 1987</td></tr>
+            <tr><td>Mobile</td><td>TBA</td></tr>
+        </tbody></table>"#;
+        let page = DETAILS
+            .replace("NA: June 1988", "NES: June 1988")
+            .replace("</main>", &format!("{}</main>", table));
+        let dates = release_dates(&page);
+        assert_eq!(dates.by_region.keys().collect::<Vec<_>>(), ["JP"]);
+        assert_eq!(dates.by_platform[&Platform::Nes].to_string(), "1988-06");
+        assert_eq!(dates.by_platform[&Platform::Msx2].to_string(), "1987-07-13");
+        assert_eq!(dates.by_platform.len(), 2);
+        assert_eq!(dates.for_platform("nes").unwrap().to_string(), "1988-06");
+        assert_eq!(dates.for_platform("PC").unwrap().to_string(), "1987-07-13");
+    }
+
+    #[test]
+    fn test_parse_modes() {
+        let content = DETAILS
+            .replace("<td>4h</td>", "<td>soon</td>")
+            .replace("<td>3</td><td>--</td>", "<td>3</td>");
+        let game = game_with_mode(&content, 5900, ParseMode::Lenient).unwrap();
+        assert_eq!(
+            game.main_story,
+            Some(Styles::new(
+                Some(15000.0),
+                None,
+                Some(9960.0),
+                Some(25920.0)
+            ))
+        );
+        assert_eq!(game.co_op, Some(Styles::new(None, None, None, None)));
+
+        let error = game_with_mode(&content, 5900, ParseMode::Strict).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unexpected page structure: unreadable time \"soon\""
+        );
+        let content = DETAILS.replace("Co-Op", "Speedrun");
+        assert!(game_with_mode(&content, 5900, ParseMode::Lenient).is_ok());
+        let error = game_with_mode(&content, 5900, ParseMode::Strict).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unexpected page structure: unknown row \"Speedrun\""
+        );
+        assert!(game_with_mode(DETAILS, 5900, ParseMode::Strict).is_ok());
+    }
+
+    #[test]
+    fn test_next_data() {
+        let content = r#"<html><body><script id="__NEXT_DATA__" type="application/json">
+            {"props": {"pageProps": {"game": {"data": {"game": [{"game_id": 5900}]}}}}}
+        </script></body></html>"#;
+        let data = next_data(content).unwrap();
+        assert_eq!(
+            data["props"]["pageProps"]["game"]["data"]["game"][0]["game_id"],
+            5900
+        );
+        assert_eq!(next_data(DETAILS), None);
+        assert_eq!(cover_url(content), None);
+        let content = content.replace(
+            r#""game_id": 5900"#,
+            r#""game_id": 5900, "game_image": "250px-Metal Gear.jpg""#,
+        );
+        assert_eq!(
+            cover_url(&content).unwrap(),
+            "https://howlongtobeat.com/games/250px-Metal%20Gear.jpg"
+        );
+    }
+
+    #[test]
+    fn test_game_id_from_href() {
+        assert_eq!(game_id_from_href("/game/5900"), Some(5900));
+        assert_eq!(
+            game_id_from_href("https://howlongtobeat.com/game/5900/"),
+            Some(5900)
+        );
+        assert_eq!(game_id_from_href("/game/5900?tab=reviews#top"), Some(5900));
+        assert_eq!(game_id_from_href("/game/metal-gear"), None);
+    }
+
+    #[cfg(feature = "browser")]
+    #[test]
+    fn test_game_cards() {
+        let content = r#"<html><body><ul>
+            <li><a href="/game/5900"><img></a><a href="/game/5900">Metal Gear</a></li>
+            <li><a href="/game/4102" title="Contra"><img></a></li>
+            <li><a href="/game/forum">Forum</a></li>
+        </ul></body></html>"#;
+        let document = Html::parse_document(content);
+        let link_selector = Selector::parse(GAME_LINK_SELECTOR).unwrap();
+        let cards: Vec<(u32, String)> = game_cards(document.root_element(), &link_selector)
+            .into_iter()
+            .map(|card| (card.hltb_id, card.title))
+            .collect();
+        assert_eq!(
+            cards,
+            [
+                (5900, "Metal Gear".to_string()),
+                (4102, "Contra".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_result() {
+        let content = r#"<html><body><div id="search-results-header"><ul><li><div>
+            <div class="GameCard_search_list_image__1"><a href="/game/5900">Metal Gear</a></div>
+        </div></li></ul></div></body></html>"#;
+        assert_eq!(search_result(content).unwrap(), 5900);
+        assert!(search_result("<html></html>").is_err());
+    }
+
+    #[test]
+    fn test_result_count() {
+        let content = r#"<html><body><div id="search-results-header">
+            <h3>We Found 1,024 Games for "Mario"</h3><ul><li><div>
+            <div class="GameCard_search_list_image__1"><a href="/game/5900">Metal Gear</a></div>
+        </div></li></ul></div></body></html>"#;
+        assert_eq!(result_count(content).unwrap(), 1024);
+        assert_eq!(
+            result_count(&content.replace("<h3>We Found 1,024 Games for \"Mario\"</h3>", ""))
+                .unwrap(),
+            1
+        );
+        assert!(result_count("<html></html>").is_err());
+        let content = r#"<html><body><div id="search-results-header">
+            <h3>No results for "Metal Gear Sold"</h3></div></body></html>"#;
+        assert_eq!(result_count(content).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_suggestion() {
+        let content = r#"<html><body><div id="search-results-header">
+            <h3>No results for "Metal Gear Sold"</h3>
+            <div>Did you mean <a href="/?q=Metal%20Gear%20Solid">Metal Gear Solid</a>?</div>
+        </div></body></html>"#;
+        assert_eq!(suggestion(content).as_deref(), Some("Metal Gear Solid"));
+        let content = r#"<html><body><p>Did you mean "Contra"?</p></body></html>"#;
+        assert_eq!(suggestion(content).as_deref(), Some("Contra"));
+        assert_eq!(
+            suggestion("<html><body><p>No results</p></body></html>"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_search_results() {
+        let content = r#"<html><body><div id="search-results-header"><ul>
+            <li><div><div class="GameCard_search_list_image__1"><a href="/game/5900" title="Metal Gear">
+                <img alt="Metal Gear"></a></div>
+                <div><h2><a href="/game/5900">Metal Gear</a></h2></div></div></li>
+            <li><div><div class="GameCard_search_list_image__1"><a href="/game/5901" title="Metal Gear 2: Solid Snake">
+                <img></a></div></div></li>
+            <li><div>Sponsored</div></li>
+        </ul></div></body></html>"#;
+        assert_eq!(
+            search_results(content),
+            vec![
+                SearchResult {
+                    hltb_id: 5900,
+                    title: "Metal Gear".to_string(),
+                    search_url: None,
+                },
+                SearchResult {
+                    hltb_id: 5901,
+                    title: "Metal Gear 2: Solid Snake".to_string(),
+                    search_url: None,
+                },
+            ]
+        );
+        assert_eq!(
+            search_results(content)[0].url(),
+            "https://howlongtobeat.com/game/5900"
+        );
+        assert!(search_results("<html></html>").is_empty());
+    }
+}
@@ -0,0 +1,290 @@
+//! Gaming platforms.
+//!
+//! The site names platforms in its own way ("PlayStation 4", "Xbox Series X/S",
+//! "Super Nintendo"), while other sources use abbreviations ("PS4", "XSX",
+//! "SNES"). [`Platform`] parses both to the same value and displays as the site
+//! does, so filters, per-platform tables and integrations compare platforms
+//! reliably. Platforms the crate doesn't know are kept as [`Platform::Other`].
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// A platform games are released on
+///
+/// It serializes as its HLTB name, so it reads and writes the same JSON as a plain string.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Platform {
+    Pc,
+    Mac,
+    Linux,
+    Android,
+    Ios,
+    Browser,
+    Arcade,
+    PlayStation,
+    PlayStation2,
+    PlayStation3,
+    PlayStation4,
+    PlayStation5,
+    PlayStationPortable,
+    PlayStationVita,
+    Xbox,
+    Xbox360,
+    XboxOne,
+    XboxSeries,
+    Nes,
+    SuperNintendo,
+    Nintendo64,
+    GameCube,
+    Wii,
+    WiiU,
+    NintendoSwitch,
+    NintendoSwitch2,
+    GameBoy,
+    GameBoyColor,
+    GameBoyAdvance,
+    NintendoDs,
+    Nintendo3ds,
+    VirtualBoy,
+    SegaMasterSystem,
+    SegaMegaDrive,
+    SegaCd,
+    Sega32x,
+    SegaSaturn,
+    Dreamcast,
+    GameGear,
+    TurboGrafx16,
+    NeoGeo,
+    Atari2600,
+    AtariJaguar,
+    AtariLynx,
+    AtariSt,
+    Amiga,
+    Commodore64,
+    Msx,
+    Msx2,
+    ZxSpectrum,
+    AmstradCpc,
+    Dos,
+    Stadia,
+    /// A platform the crate doesn't know, with its name as written
+    Other(String),
+}
+
+/// Every known platform with its HLTB name
+const NAMES: &[(Platform, &str)] = &[
+    (Platform::Pc, "PC"),
+    (Platform::Mac, "Mac"),
+    (Platform::Linux, "Linux"),
+    (Platform::Android, "Android"),
+    (Platform::Ios, "iOS"),
+    (Platform::Browser, "Browser"),
+    (Platform::Arcade, "Arcade"),
+    (Platform::PlayStation, "PlayStation"),
+    (Platform::PlayStation2, "PlayStation 2"),
+    (Platform::PlayStation3, "PlayStation 3"),
+    (Platform::PlayStation4, "PlayStation 4"),
+    (Platform::PlayStation5, "PlayStation 5"),
+    (Platform::PlayStationPortable, "PlayStation Portable"),
+    (Platform::PlayStationVita, "PlayStation Vita"),
+    (Platform::Xbox, "Xbox"),
+    (Platform::Xbox360, "Xbox 360"),
+    (Platform::XboxOne, "Xbox One"),
+    (Platform::XboxSeries, "Xbox Series X/S"),
+    (Platform::Nes, "NES"),
+    (Platform::SuperNintendo, "Super Nintendo"),
+    (Platform::Nintendo64, "Nintendo 64"),
+    (Platform::GameCube, "Nintendo GameCube"),
+    (Platform::Wii, "Nintendo Wii"),
+    (Platform::WiiU, "Nintendo Wii U"),
+    (Platform::NintendoSwitch, "Nintendo Switch"),
+    (Platform::NintendoSwitch2, "Nintendo Switch 2"),
+    (Platform::GameBoy, "Game Boy"),
+    (Platform::GameBoyColor, "Game Boy Color"),
+    (Platform::GameBoyAdvance, "Game Boy Advance"),
+    (Platform::NintendoDs, "Nintendo DS"),
+    (Platform::Nintendo3ds, "Nintendo 3DS"),
+    (Platform::VirtualBoy, "Virtual Boy"),
+    (Platform::SegaMasterSystem, "Sega Master System"),
+    (Platform::SegaMegaDrive, "Sega Mega Drive/Genesis"),
+    (Platform::SegaCd, "Sega CD"),
+    (Platform::Sega32x, "Sega 32X"),
+    (Platform::SegaSaturn, "Sega Saturn"),
+    (Platform::Dreamcast, "Dreamcast"),
+    (Platform::GameGear, "Game Gear"),
+    (Platform::TurboGrafx16, "TurboGrafx-16"),
+    (Platform::NeoGeo, "Neo Geo"),
+    (Platform::Atari2600, "Atari 2600"),
+    (Platform::AtariJaguar, "Atari Jaguar"),
+    (Platform::AtariLynx, "Atari Lynx"),
+    (Platform::AtariSt, "Atari ST"),
+    (Platform::Amiga, "Amiga"),
+    (Platform::Commodore64, "Commodore 64"),
+    (Platform::Msx, "MSX"),
+    (Platform::Msx2, "MSX2"),
+    (Platform::ZxSpectrum, "ZX Spectrum"),
+    (Platform::AmstradCpc, "Amstrad CPC"),
+    (Platform::Dos, "DOS"),
+    (Platform::Stadia, "Google Stadia"),
+];
+
+/// Other spellings of the platforms, lowercase, as used by stores and achievement trackers
+const ALIASES: &[(&str, Platform)] = &[
+    ("win", Platform::Pc),
+    ("windows", Platform::Pc),
+    ("win 10", Platform::Pc),
+    ("macos", Platform::Mac),
+    ("ps1", Platform::PlayStation),
+    ("psx", Platform::PlayStation),
+    ("ps2", Platform::PlayStation2),
+    ("ps3", Platform::PlayStation3),
+    ("ps4", Platform::PlayStation4),
+    ("ps5", Platform::PlayStation5),
+    ("psp", Platform::PlayStationPortable),
+    ("psvita", Platform::PlayStationVita),
+    ("ps vita", Platform::PlayStationVita),
+    ("vita", Platform::PlayStationVita),
+    ("x360", Platform::Xbox360),
+    ("xb1", Platform::XboxOne),
+    ("xsx", Platform::XboxSeries),
+    ("xbox series", Platform::XboxSeries),
+    ("xbox series x|s", Platform::XboxSeries),
+    ("snes", Platform::SuperNintendo),
+    ("n64", Platform::Nintendo64),
+    ("gamecube", Platform::GameCube),
+    ("wii", Platform::Wii),
+    ("wii u", Platform::WiiU),
+    ("switch", Platform::NintendoSwitch),
+    ("switch 2", Platform::NintendoSwitch2),
+    ("gb", Platform::GameBoy),
+    ("gbc", Platform::GameBoyColor),
+    ("gba", Platform::GameBoyAdvance),
+    ("nds", Platform::NintendoDs),
+    ("3ds", Platform::Nintendo3ds),
+    ("mega drive", Platform::SegaMegaDrive),
+    ("genesis", Platform::SegaMegaDrive),
+    ("sega genesis", Platform::SegaMegaDrive),
+    ("pc engine", Platform::TurboGrafx16),
+    ("c64", Platform::Commodore64),
+];
+
+impl Platform {
+    /// Returns the name of the platform on How Long to Beat, e.g. "PlayStation 4"
+    ///
+    /// returns: &str
+    pub fn name(&self) -> &str {
+        match self {
+            Platform::Other(name) => name,
+            platform => NAMES
+                .iter()
+                .find(|(known, _)| known == platform)
+                .map(|(_, name)| *name)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Checks whether the crate knows the platform
+    ///
+    /// returns: bool
+    pub fn is_known(&self) -> bool {
+        !matches!(self, Platform::Other(_))
+    }
+}
+
+/// Parses an HLTB name or a known abbreviation, ignoring case and surrounding spaces
+///
+/// Unknown names parse to [`Platform::Other`] with the trimmed name.
+impl From<&str> for Platform {
+    fn from(name: &str) -> Platform {
+        let name = name.trim();
+        let known = NAMES
+            .iter()
+            .find(|(_, known)| known.eq_ignore_ascii_case(name))
+            .map(|(platform, _)| platform)
+            .or_else(|| {
+                let key = name.to_lowercase();
+                ALIASES
+                    .iter()
+                    .find(|(alias, _)| *alias == key)
+                    .map(|(_, platform)| platform)
+            });
+        known
+            .cloned()
+            .unwrap_or_else(|| Platform::Other(name.to_string()))
+    }
+}
+
+/// Parses like [`Platform::from`], never failing
+impl FromStr for Platform {
+    type Err = Infallible;
+
+    fn from_str(name: &str) -> Result<Platform, Infallible> {
+        Ok(Platform::from(name))
+    }
+}
+
+impl From<String> for Platform {
+    fn from(name: String) -> Platform {
+        Platform::from(name.as_str())
+    }
+}
+
+impl From<&Platform> for Platform {
+    fn from(platform: &Platform) -> Platform {
+        platform.clone()
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Serialize for Platform {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Platform {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Platform, D::Error> {
+        Ok(Platform::from(String::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(feature = "graphql")]
+async_graphql::scalar!(Platform, "Platform", "A platform, by its HLTB name");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_platform() {
+        assert_eq!(Platform::from("PS4"), Platform::PlayStation4);
+        assert_eq!(Platform::from(" playstation 4 "), Platform::PlayStation4);
+        assert_eq!(Platform::from(" Xbox Series X|S "), Platform::XboxSeries);
+        assert_eq!(
+            Platform::from("Sharp X68000"),
+            Platform::Other("Sharp X68000".to_string())
+        );
+        assert_eq!(Platform::XboxSeries.to_string(), "Xbox Series X/S");
+        for (platform, name) in NAMES {
+            assert_eq!(Platform::from(*name), *platform);
+        }
+    }
+
+    #[test]
+    fn test_platform_serde() {
+        let platforms = vec![Platform::Nes, Platform::Other("FM Towns".to_string())];
+        let json = serde_json::to_string(&platforms).unwrap();
+        assert_eq!(json, r#"["NES","FM Towns"]"#);
+        assert_eq!(
+            serde_json::from_str::<Vec<Platform>>(&json).unwrap(),
+            platforms
+        );
+    }
+}
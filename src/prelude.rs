@@ -0,0 +1,29 @@
+//! The types needed for typical usage, in a single import.
+//!
+//! ```no_run
+//! use howlongtobeat_scraper::prelude::*;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = HltbClient::builder().timeout(Duration::from_secs(30)).build()?;
+//! let game = client.search("Metal Gear").await?;
+//! println!("{}", game.title);
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(any(feature = "browser", feature = "http"))]
+pub use crate::client::{
+    global, Backend, BacklogEntry, BacklogItem, BacklogReport, BatchProgress, ClientConfig,
+    GamePage, HltbClient, HltbClientBuilder, LaunchProfile, Observer, PageKind, SearchRequest,
+    VcrMode, WaitStrategy,
+};
+pub use crate::pace::{Completion, PaceModel};
+pub use crate::parse::{ParseMode, SearchResult, TitleCleaning};
+pub use crate::platform::Platform;
+pub use crate::query::{MatchInfo, MatchStrategy, ResolvedGame, SearchQuery, SortBy};
+pub use crate::release::{ReleaseDate, ReleaseDates};
+pub use crate::repr::{format_duration, parse_duration};
+#[cfg(any(feature = "browser", feature = "http"))]
+pub use crate::CancellationToken;
+pub use crate::{EstimateKind, Focus, Game, HltbError, PlayStyle, Session, Styles};
+pub use std::time::Duration;
@@ -0,0 +1,547 @@
+//! Search queries.
+//!
+//! A [`SearchQuery`] gathers everything that shapes a search (the name, the
+//! platform filter, the order and window of the results and how the game is
+//! picked among them), so the search functions take one value instead of a
+//! growing list of arguments. A plain `&str` converts to a query of that name
+//! with the default options.
+
+use crate::parse::{normalize_title, title_similarity, SearchResult};
+use crate::platform::Platform;
+use crate::Game;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The order of the search results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum SortBy {
+    /// The order of the site, by relevance
+    #[default]
+    Relevance,
+    /// Alphabetical order of the titles
+    Title,
+}
+
+/// How a search picks its game among the results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum MatchStrategy {
+    /// The first result
+    #[default]
+    FirstResult,
+    /// The first result whose title is the searched name, compared with
+    /// [`normalize_title`](crate::parse::normalize_title)
+    ExactTitle,
+    /// The result whose title is the most similar to the searched name, compared with
+    /// [`title_similarity`](crate::parse::title_similarity), the first one on ties
+    BestTitle,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+pub struct SearchQuery {
+    pub name: String,
+    /// The platform the game must be available on, checked once its details are fetched
+    /// since search results don't list platforms
+    pub platform: Option<Platform>,
+    pub sort: SortBy,
+    pub offset: usize,
+    pub limit: Option<usize>,
+    pub strategy: MatchStrategy,
+    /// Whether results listed twice, or editions of a game listed next to it, are collapsed
+    /// into one result, see [`group_editions`]
+    #[serde(default)]
+    pub dedup: bool,
+}
+
+impl SearchQuery {
+    /// Creates a query for a name with the default options
+    ///
+    /// # Arguments
+    ///
+    /// * `name`:  &str - The name of the game to search for
+    ///
+    /// returns: SearchQuery
+    pub fn new(name: &str) -> SearchQuery {
+        SearchQuery {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Only accepts games available on a platform, or its name (e.g. "PlayStation 4")
+    pub fn platform(mut self, platform: impl Into<Platform>) -> SearchQuery {
+        self.platform = Some(platform.into());
+        self
+    }
+
+    /// Sets the order of the results
+    pub fn sort(mut self, sort: SortBy) -> SearchQuery {
+        self.sort = sort;
+        self
+    }
+
+    /// Skips the first results, after sorting
+    pub fn offset(mut self, offset: usize) -> SearchQuery {
+        self.offset = offset;
+        self
+    }
+
+    /// Keeps at most a number of results, after the offset
+    pub fn limit(mut self, limit: usize) -> SearchQuery {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets how the game is picked among the results
+    pub fn strategy(mut self, strategy: MatchStrategy) -> SearchQuery {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Collapses the results listed twice and the editions of a game into one result
+    pub fn dedup(mut self, dedup: bool) -> SearchQuery {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Checks whether a game passes the filters of the query
+    ///
+    /// # Arguments
+    ///
+    /// * `game`:  &Game - The game the query resolved to
+    ///
+    /// returns: bool
+    pub fn accepts(&self, game: &Game) -> bool {
+        self.platform
+            .as_ref()
+            .is_none_or(|platform| game.is_on_platform(platform))
+    }
+
+    /// Whether the query picks the first result of its name as is, its filters aside, so
+    /// its pick can be shared with the other queries of the name
+    ///
+    /// returns: bool
+    #[cfg(any(feature = "browser", feature = "http"))]
+    pub(crate) fn is_plain(&self) -> bool {
+        let mut plain = SearchQuery::new(&self.name);
+        plain.platform.clone_from(&self.platform);
+        *self == plain
+    }
+
+    /// Sorts the results of the search page, collapses duplicates if asked, and applies the
+    /// offset and limit
+    ///
+    /// # Arguments
+    ///
+    /// * `results`:  Vec<SearchResult> - The results, in the order of the page
+    ///
+    /// returns: Vec<SearchResult>
+    pub fn arrange(&self, mut results: Vec<SearchResult>) -> Vec<SearchResult> {
+        if self.dedup {
+            results = group_editions(results)
+                .into_iter()
+                .map(|group| group.primary)
+                .collect();
+        }
+        if self.sort == SortBy::Title {
+            results.sort_by_cached_key(|result| normalize_title(&result.title));
+        }
+        results
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+
+    /// Returns other spellings of the name, which searches try when the name has no results
+    ///
+    /// Each spelling swaps one of "and" and "&", or ":" and " -", e.g. "Ratchet & Clank"
+    /// for "Ratchet and Clank".
+    ///
+    /// returns: Vec<String>
+    pub fn spelling_variants(&self) -> Vec<String> {
+        let mut variants: Vec<String> = Vec::new();
+        for (from, to) in SPELLING_SUBSTITUTIONS {
+            let variant = replace_ignore_case(&self.name, from, to);
+            if variant != self.name && !variants.contains(&variant) {
+                variants.push(variant);
+            }
+        }
+        variants
+    }
+
+    /// Picks the result of the search page the query resolves to
+    ///
+    /// # Arguments
+    ///
+    /// * `results`:  Vec<SearchResult> - The results, in the order of the page
+    ///
+    /// returns: Option<SearchResult>
+    pub fn select(&self, results: Vec<SearchResult>) -> Option<SearchResult> {
+        let mut results = self.arrange(results).into_iter();
+        match self.strategy {
+            MatchStrategy::FirstResult => results.next(),
+            MatchStrategy::ExactTitle => {
+                let name = normalize_title(&self.name);
+                results.find(|result| normalize_title(&result.title) == name)
+            }
+            MatchStrategy::BestTitle => results
+                .map(|result| (title_similarity(&self.name, &result.title), result))
+                .fold(
+                    None,
+                    |best: Option<(f32, SearchResult)>, (similarity, result)| match best {
+                        Some(best) if best.0 >= similarity => Some(best),
+                        _ => Some((similarity, result)),
+                    },
+                )
+                .map(|(_, result)| result),
+        }
+    }
+}
+
+/// A game of a search with the other editions of it the search listed
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ResultGroup {
+    /// The plain game when it is listed, otherwise the first listed edition
+    pub primary: SearchResult,
+    /// The other editions, in the order of the results
+    pub editions: Vec<SearchResult>,
+}
+
+/// Edition suffixes of titles, normalized, longest first where one ends another
+const EDITION_SUFFIXES: &[&str] = &[
+    "game of the year edition",
+    "goty edition",
+    "goty",
+    "definitive edition",
+    "complete edition",
+    "deluxe edition",
+    "gold edition",
+    "special edition",
+    "enhanced edition",
+    "anniversary edition",
+    "ultimate edition",
+    "collectors edition",
+    "directors cut",
+    "remastered",
+    "hd",
+];
+
+/// Returns the title of a game without its edition suffixes, normalized
+///
+/// # Arguments
+///
+/// * `title`:  &str - The title, e.g. "The Witcher 3: Wild Hunt - Game of the Year Edition"
+///
+/// returns: String - e.g. "the witcher 3 wild hunt"
+pub fn edition_base(title: &str) -> String {
+    let mut base = normalize_title(title);
+    while let Some(stripped) = EDITION_SUFFIXES.iter().find_map(|suffix| {
+        base.strip_suffix(suffix)
+            .filter(|rest| rest.ends_with(' '))
+            .map(|rest| rest.trim_end().to_string())
+    }) {
+        base = stripped;
+    }
+    base
+}
+
+/// Groups search results by game: results with the same ID are listed once, and the
+/// editions of a game (same title but for an edition suffix, see [`edition_base`]) are
+/// grouped under it
+///
+/// Groups are in the order their first result was listed in.
+///
+/// # Arguments
+///
+/// * `results`:  Vec<SearchResult> - The results, in the order of the page
+///
+/// returns: Vec<ResultGroup>
+pub fn group_editions(results: Vec<SearchResult>) -> Vec<ResultGroup> {
+    let mut groups: Vec<(String, Vec<SearchResult>)> = Vec::new();
+    for result in results {
+        if groups
+            .iter()
+            .flat_map(|(_, members)| members)
+            .any(|member| member.hltb_id == result.hltb_id)
+        {
+            continue;
+        }
+        let base = edition_base(&result.title);
+        match groups
+            .iter_mut()
+            .find(|(group_base, _)| *group_base == base)
+        {
+            Some((_, members)) => members.push(result),
+            None => groups.push((base, vec![result])),
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(base, mut members)| {
+            let primary = members
+                .iter()
+                .position(|member| normalize_title(&member.title) == base)
+                .unwrap_or(0);
+            ResultGroup {
+                primary: members.remove(primary),
+                editions: members,
+            }
+        })
+        .collect()
+}
+
+/// The substitutions of [`SearchQuery::spelling_variants`], each applied on its own
+const SPELLING_SUBSTITUTIONS: &[(&str, &str)] = &[
+    (" and ", " & "),
+    (" & ", " and "),
+    (": ", " - "),
+    (" - ", ": "),
+];
+
+/// Replaces every occurrence of an ASCII pattern in a text, ignoring its case
+fn replace_ignore_case(text: &str, from: &str, to: &str) -> String {
+    // ASCII lowercasing keeps the byte offsets of the text
+    let lowercase = text.to_ascii_lowercase();
+    let mut replaced = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, _) in lowercase.match_indices(from) {
+        replaced.push_str(&text[last..start]);
+        replaced.push_str(to);
+        last = start + from.len();
+    }
+    replaced.push_str(&text[last..]);
+    replaced
+}
+
+/// Below this similarity, a match is worth a manual review
+const LOW_CONFIDENCE_SIMILARITY: f32 = 0.8;
+
+/// How a search by name resolved to its game, to flag doubtful matches
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MatchInfo {
+    /// The searched name
+    pub query: String,
+    /// The alias of the game the name matched best, None if it was the title
+    pub matched_alias: Option<String>,
+    /// How similar the name is to the title or alias it matched best, from 0.0 to 1.0
+    pub similarity: f32,
+    /// How many results the search page listed, the game included
+    pub candidates: usize,
+    /// The spelling that found the game when the searched name had no results, e.g. the
+    /// suggestion of the site or [`SearchQuery::spelling_variants`], None for the name itself
+    #[serde(default)]
+    pub variant: Option<String>,
+    /// Whether the name is mapped to the game by [`TitleOverrides`](crate::overrides::TitleOverrides),
+    /// without searching
+    #[serde(default)]
+    pub overridden: bool,
+}
+
+impl MatchInfo {
+    /// Describes the match of a searched name with the game it resolved to
+    ///
+    /// # Arguments
+    ///
+    /// * `query`:  &str - The searched name
+    /// * `game`:  &Game - The game the search resolved to
+    /// * `candidates`:  usize - The number of results of the search page
+    ///
+    /// returns: MatchInfo
+    pub fn new(query: &str, game: &Game, candidates: usize) -> MatchInfo {
+        let mut matched_alias = None;
+        let mut similarity = title_similarity(query, &game.title);
+        for alias in &game.aliases {
+            let alias_similarity = title_similarity(query, alias);
+            if alias_similarity > similarity {
+                similarity = alias_similarity;
+                matched_alias = Some(alias.clone());
+            }
+        }
+        MatchInfo {
+            query: query.to_string(),
+            matched_alias,
+            similarity,
+            candidates,
+            variant: None,
+            overridden: false,
+        }
+    }
+
+    /// Whether the match is doubtful: the name is far from the game's titles, or it isn't
+    /// an exact match among several candidates. Overridden matches never are.
+    ///
+    /// returns: bool
+    pub fn is_low_confidence(&self) -> bool {
+        !self.overridden
+            && (self.similarity < LOW_CONFIDENCE_SIMILARITY
+                || (self.candidates > 1 && self.similarity < 1.0))
+    }
+}
+
+/// A game found by a search by name, with how it was matched
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ResolvedGame {
+    pub game: Game,
+    pub info: MatchInfo,
+}
+
+impl From<&str> for SearchQuery {
+    fn from(name: &str) -> SearchQuery {
+        SearchQuery::new(name)
+    }
+}
+
+impl From<String> for SearchQuery {
+    fn from(name: String) -> SearchQuery {
+        SearchQuery {
+            name,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spelling_variants() {
+        assert_eq!(
+            SearchQuery::new("Ratchet And Clank: Rift Apart").spelling_variants(),
+            [
+                "Ratchet & Clank: Rift Apart",
+                "Ratchet And Clank - Rift Apart"
+            ]
+        );
+        assert_eq!(
+            SearchQuery::new("Ratchet & Clank").spelling_variants(),
+            ["Ratchet and Clank"]
+        );
+        assert!(SearchQuery::new("Metal Gear")
+            .spelling_variants()
+            .is_empty());
+    }
+
+    fn results() -> Vec<SearchResult> {
+        [
+            (5901, "Metal Gear 2: Solid Snake"),
+            (5900, "Metal Gear"),
+            (5903, "Metal Gear Solid"),
+        ]
+        .iter()
+        .map(|(hltb_id, title)| SearchResult {
+            hltb_id: *hltb_id,
+            title: title.to_string(),
+            search_url: None,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_group_editions() {
+        let result = |hltb_id: u32, title: &str| SearchResult {
+            hltb_id,
+            title: title.to_string(),
+            search_url: None,
+        };
+        let results = vec![
+            result(10270, "The Witcher 3: Wild Hunt - Game of the Year Edition"),
+            result(10269, "The Witcher 3: Wild Hunt"),
+            result(10270, "The Witcher 3: Wild Hunt - Game of the Year Edition"),
+            result(7264, "The Witcher 2: Assassins of Kings Enhanced Edition"),
+            result(38050, "The Witcher 3: Wild Hunt - Complete Edition"),
+        ];
+        assert_eq!(
+            edition_base("The Witcher 3: Wild Hunt - Complete Edition"),
+            "the witcher 3 wild hunt"
+        );
+        let groups = group_editions(results.clone());
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].primary.hltb_id, 10269);
+        assert_eq!(
+            groups[0]
+                .editions
+                .iter()
+                .map(|edition| edition.hltb_id)
+                .collect::<Vec<_>>(),
+            [10270, 38050]
+        );
+        assert_eq!(groups[1].primary.hltb_id, 7264);
+        let query = SearchQuery::new("the witcher").dedup(true);
+        let ids: Vec<u32> = query.arrange(results).iter().map(|r| r.hltb_id).collect();
+        assert_eq!(ids, [10269, 7264]);
+    }
+
+    #[test]
+    fn test_select() {
+        let query = SearchQuery::new("metal gear");
+        assert_eq!(query.select(results()).unwrap().hltb_id, 5901);
+        let query = query.strategy(MatchStrategy::ExactTitle);
+        assert_eq!(query.select(results()).unwrap().hltb_id, 5900);
+        assert_eq!(query.offset(2).select(results()), None);
+        let query = SearchQuery::new("metal gear solid").strategy(MatchStrategy::BestTitle);
+        assert_eq!(query.select(results()).unwrap().hltb_id, 5903);
+    }
+
+    #[test]
+    fn test_platform_filter() {
+        let mut game = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        game.platforms = vec![Platform::Nes];
+        let query = SearchQuery::new("metal gear").platform("NES");
+        assert!(query.accepts(&game));
+        assert!(!query.clone().platform("PC").accepts(&game));
+        assert!(SearchQuery::new("metal gear").accepts(&game));
+        #[cfg(any(feature = "browser", feature = "http"))]
+        {
+            assert!(query.is_plain());
+            assert!(!query.limit(1).is_plain());
+        }
+    }
+
+    #[test]
+    fn test_match_info() {
+        let mut game = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        game.aliases = vec!["Metal Gear (MSX2)".to_string()];
+        let info = MatchInfo::new("metal gear", &game, 3);
+        assert_eq!(
+            (info.similarity, info.matched_alias.as_deref()),
+            (1.0, None)
+        );
+        assert!(!info.is_low_confidence());
+
+        let info = MatchInfo::new("Metal Gear MSX", &game, 1);
+        assert_eq!(info.matched_alias.as_deref(), Some("Metal Gear (MSX2)"));
+        assert!(info.similarity > 0.9);
+        assert!(!info.is_low_confidence());
+        assert!(MatchInfo::new("Metal Gear MSX", &game, 3).is_low_confidence());
+        assert!(MatchInfo::new("Zelda", &game, 1).is_low_confidence());
+    }
+
+    #[test]
+    fn test_arrange() {
+        let query = SearchQuery::new("metal gear")
+            .sort(SortBy::Title)
+            .offset(1)
+            .limit(1);
+        let ids: Vec<u32> = query.arrange(results()).iter().map(|r| r.hltb_id).collect();
+        assert_eq!(ids, [5901]);
+    }
+}
@@ -0,0 +1,151 @@
+//! Release dates of games.
+//!
+//! Details pages give the release dates of a game by region ("NA", "EU",
+//! "JP") and, for some games, by platform. [`ReleaseDates`] keeps both, so an
+//! app can show the date of the user's platform and fall back to the first
+//! release otherwise.
+
+use crate::platform::Platform;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A release date, as precise as the site gives it
+///
+/// Dates order chronologically, a date without a month or day coming before the more
+/// precise dates of the same year or month.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub struct ReleaseDate {
+    pub year: i32,
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+}
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+impl ReleaseDate {
+    /// Parses a date as written on the site, e.g. "July 13th, 1987", "June 1988" or "1987"
+    ///
+    /// # Arguments
+    ///
+    /// * `text`:  &str - The date
+    ///
+    /// returns: Option<ReleaseDate> - None if the text has no year
+    pub fn parse(text: &str) -> Option<ReleaseDate> {
+        let mut date = ReleaseDate {
+            year: 0,
+            month: None,
+            day: None,
+        };
+        let mut has_year = false;
+        for word in text.split(|c: char| c.is_whitespace() || c == ',') {
+            let word = word.trim().to_lowercase();
+            if word.len() >= 3 {
+                if let Some(month) = MONTHS.iter().position(|month| month.starts_with(&word)) {
+                    date.month = Some(month as u8 + 1);
+                    continue;
+                }
+            }
+            let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+            match (digits.len(), digits.parse::<i32>()) {
+                (4, Ok(year)) => {
+                    date.year = year;
+                    has_year = true;
+                }
+                (1 | 2, Ok(day)) if (1..=31).contains(&day) => date.day = Some(day as u8),
+                _ => {}
+            }
+        }
+        if date.month.is_none() {
+            date.day = None;
+        }
+        has_year.then_some(date)
+    }
+}
+
+/// Writes the date in ISO 8601 form, e.g. "1987-07-13", "1988-06" or "1987"
+impl fmt::Display for ReleaseDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}", self.year)?;
+        if let Some(month) = self.month {
+            write!(f, "-{:02}", month)?;
+            if let Some(day) = self.day {
+                write!(f, "-{:02}", day)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The release dates of a game by platform and by region
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct ReleaseDates {
+    /// Dates by platform
+    pub by_platform: BTreeMap<Platform, ReleaseDate>,
+    /// Dates by region, e.g. "NA"
+    pub by_region: BTreeMap<String, ReleaseDate>,
+}
+
+impl ReleaseDates {
+    /// Returns the release date of the game on a platform, or its first release when the site
+    /// has no date for the platform
+    ///
+    /// # Arguments
+    ///
+    /// * `platform`:  impl Into<Platform> - The platform, or its name (e.g. "PlayStation 4")
+    ///
+    /// returns: Option<ReleaseDate>
+    pub fn for_platform(&self, platform: impl Into<Platform>) -> Option<ReleaseDate> {
+        self.by_platform
+            .get(&platform.into())
+            .copied()
+            .or_else(|| self.first())
+    }
+
+    /// Returns the earliest release date, on any platform or in any region
+    ///
+    /// returns: Option<ReleaseDate>
+    pub fn first(&self) -> Option<ReleaseDate> {
+        self.by_platform
+            .values()
+            .chain(self.by_region.values())
+            .min()
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_release_date() {
+        let date = ReleaseDate::parse("July 13th, 1987").unwrap();
+        assert_eq!(date.to_string(), "1987-07-13");
+        assert_eq!(
+            ReleaseDate::parse("June 1988").unwrap().to_string(),
+            "1988-06"
+        );
+        assert_eq!(
+            ReleaseDate::parse("Sep 2nd 2020").unwrap().to_string(),
+            "2020-09-02"
+        );
+        assert_eq!(ReleaseDate::parse("1987").unwrap().to_string(), "1987");
+        assert_eq!(ReleaseDate::parse("TBA"), None);
+        assert!(ReleaseDate::parse("1987").unwrap() < date);
+    }
+}
@@ -0,0 +1,464 @@
+//! Human-readable reports of games.
+//!
+//! [`markdown`] renders a table of games with their average hours per play style
+//! and the totals, ready to paste into GitHub issues, Notion pages or blog posts.
+//! [`html`] renders the same table as a single self-contained HTML file, with the
+//! covers of the games embedded and columns sorted by clicking their header, to
+//! share with people who don't use the crate. [`discord_embed`] describes a game
+//! as a Discord embed, for bots answering "how long is this game?".
+
+use crate::repr::format_duration;
+use crate::Game;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Reads the time of a column out of a game
+type ColumnTime = fn(&Game) -> Option<f32>;
+
+/// The play styles of the report columns, with their headers
+const COLUMNS: [(&str, ColumnTime); 3] = [
+    ("Main Story", |game| game.main_story.as_ref()?.average),
+    ("Main + Extras", |game| game.main_extra.as_ref()?.average),
+    ("Completionist", |game| game.completionist.as_ref()?.average),
+];
+
+/// Formats a time in seconds as hours with one decimal, e.g. "4.2"
+fn hours(seconds: f32) -> String {
+    format!("{:.1}", seconds / 3600.0)
+}
+
+/// Escapes the characters of a title that Markdown would read as table or link syntax
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '|' | '[' | ']' | '*' | '_' | '`' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Renders games as a Markdown table, with their titles linked to How Long to Beat, their
+/// average hours per play style and a line of totals
+///
+/// Missing times are shown as "–" and left out of the totals.
+///
+/// # Arguments
+///
+/// * `games`:  &[Game] - The games, in the order of the table
+///
+/// returns: String
+pub fn markdown(games: &[Game]) -> String {
+    let mut table = String::from("| Game |");
+    for (header, _) in COLUMNS {
+        table.push_str(&format!(" {} (h) |", header));
+    }
+    table.push_str("\n| --- |");
+    table.push_str(&" ---: |".repeat(COLUMNS.len()));
+    table.push('\n');
+    let mut totals = [0.0; COLUMNS.len()];
+    for game in games {
+        table.push_str(&format!(
+            "| [{}]({}) |",
+            escape_markdown(&game.title),
+            game.url()
+        ));
+        for (total, (_, time)) in totals.iter_mut().zip(COLUMNS) {
+            match time(game) {
+                Some(seconds) => {
+                    *total += seconds;
+                    table.push_str(&format!(" {} |", hours(seconds)));
+                }
+                None => table.push_str(" – |"),
+            }
+        }
+        table.push('\n');
+    }
+    table.push_str("| **Total** |");
+    for total in totals {
+        table.push_str(&format!(" **{}** |", hours(total)));
+    }
+    table.push('\n');
+    table
+}
+
+/// The cover image of a game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cover {
+    /// The media type of the image, e.g. "image/jpeg"
+    pub mime: String,
+    pub bytes: Vec<u8>,
+}
+
+impl Cover {
+    /// Returns the image as a `data:` URI, to embed it in a page
+    ///
+    /// A media type that isn't a plain image type, e.g. one sent by a misbehaving server, is
+    /// replaced by "image/jpeg" so it can't break out of the attribute the URI is written to.
+    ///
+    /// returns: String
+    pub fn data_uri(&self) -> String {
+        let mime = if is_image_type(&self.mime) {
+            self.mime.as_str()
+        } else {
+            "image/jpeg"
+        };
+        format!("data:{};base64,{}", mime, base64(&self.bytes))
+    }
+}
+
+/// Checks whether a media type is an image type without parameters, e.g. "image/svg+xml"
+///
+/// # Arguments
+///
+/// * `mime`:  &str - The media type
+///
+/// returns: bool
+pub(crate) fn is_image_type(mime: &str) -> bool {
+    mime.strip_prefix("image/").is_some_and(|subtype| {
+        !subtype.is_empty()
+            && subtype
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'+' | b'-'))
+    })
+}
+
+/// Encodes bytes in standard base64, with padding
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| {
+            group | (*byte as u32) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Escapes text for HTML content and attribute values
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const HTML_STYLE: &str = "body{font-family:sans-serif;margin:2em}\
+table{border-collapse:collapse}\
+th,td{padding:.4em .8em;border-bottom:1px solid #ddd;text-align:right}\
+th:nth-child(2),td:nth-child(2){text-align:left}\
+th[data-sort]{cursor:pointer;user-select:none}\
+img{width:60px;display:block}";
+
+/// Sorts the rows of the table by the `data-value` of the clicked column, toggling the order
+const HTML_SCRIPT: &str = r#"document.querySelectorAll("th[data-sort]").forEach(function (th) {
+  th.addEventListener("click", function () {
+    var body = th.closest("table").tBodies[0], index = th.cellIndex;
+    var numeric = th.dataset.sort === "number", ascending = th.dataset.order !== "asc";
+    th.dataset.order = ascending ? "asc" : "desc";
+    var value = function (row) {
+      var v = row.cells[index].dataset.value;
+      return numeric ? (v === "" ? Infinity : Number(v)) : v.toLowerCase();
+    };
+    Array.from(body.rows).sort(function (a, b) {
+      var x = value(a), y = value(b);
+      return (x < y ? -1 : x > y ? 1 : 0) * (ascending ? 1 : -1);
+    }).forEach(function (row) { body.appendChild(row); });
+  });
+});"#;
+
+/// Renders games as a self-contained HTML page: a table with their covers, their titles
+/// linked to How Long to Beat, their average hours per play style and a line of totals
+///
+/// The covers are embedded in the page, and clicking the header of a column sorts the
+/// games by it. Missing times are shown as "–" and left out of the totals.
+///
+/// # Arguments
+///
+/// * `games`:  &[Game] - The games, in the initial order of the table
+/// * `covers`:  &HashMap<u32, Cover> - The covers of the games by ID, e.g. from
+///   `HltbClient::fetch_cover`; games without one have an empty cell
+///
+/// returns: String
+pub fn html(games: &[Game], covers: &HashMap<u32, Cover>) -> String {
+    let mut page = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>How Long to Beat report</title>\n<style>{}</style>\n</head>\n<body>\n\
+         <table>\n<thead>\n<tr><th></th><th data-sort=\"text\">Game</th>",
+        HTML_STYLE
+    );
+    for (header, _) in COLUMNS {
+        page.push_str(&format!("<th data-sort=\"number\">{} (h)</th>", header));
+    }
+    page.push_str("</tr>\n</thead>\n<tbody>\n");
+    let mut totals = [0.0; COLUMNS.len()];
+    for game in games {
+        let title = escape_html(&game.title);
+        let cover = covers
+            .get(&game.hltb_id)
+            .map(|cover| format!("<img src=\"{}\" alt=\"\">", cover.data_uri()))
+            .unwrap_or_default();
+        page.push_str(&format!(
+            "<tr><td>{}</td><td data-value=\"{}\"><a href=\"{}\">{}</a></td>",
+            cover,
+            title,
+            game.url(),
+            title
+        ));
+        for (total, (_, time)) in totals.iter_mut().zip(COLUMNS) {
+            match time(game) {
+                Some(seconds) => {
+                    *total += seconds;
+                    page.push_str(&format!(
+                        "<td data-value=\"{}\">{}</td>",
+                        seconds,
+                        hours(seconds)
+                    ));
+                }
+                None => page.push_str("<td data-value=\"\">–</td>"),
+            }
+        }
+        page.push_str("</tr>\n");
+    }
+    page.push_str("</tbody>\n<tfoot>\n<tr><td></td><th>Total</th>");
+    for total in totals {
+        page.push_str(&format!("<th>{}</th>", hours(total)));
+    }
+    page.push_str(&format!(
+        "</tr>\n</tfoot>\n</table>\n<script>\n{}\n</script>\n</body>\n</html>\n",
+        HTML_SCRIPT
+    ));
+    page
+}
+
+/// The color of the side bar of the embeds, the blue of the site
+const DISCORD_COLOR: u32 = 0x287fc2;
+
+/// The maximum length of the title of a Discord embed, in characters
+const DISCORD_TITLE_LENGTH: usize = 256;
+
+/// A Discord embed, serialized as the JSON the Discord API and webhooks expect
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct DiscordEmbed {
+    pub title: String,
+    pub url: String,
+    pub color: u32,
+    /// The platforms and the release year of the game
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<DiscordImage>,
+    /// One field per play style with a time
+    pub fields: Vec<DiscordField>,
+    pub footer: DiscordFooter,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct DiscordImage {
+    pub url: String,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct DiscordField {
+    pub name: String,
+    pub value: String,
+    pub inline: bool,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct DiscordFooter {
+    pub text: String,
+}
+
+impl DiscordEmbed {
+    /// Sets the thumbnail of the embed, e.g. to the URL of the cover of the game
+    pub fn thumbnail(mut self, url: impl Into<String>) -> DiscordEmbed {
+        self.thumbnail = Some(DiscordImage { url: url.into() });
+        self
+    }
+}
+
+/// Describes a game as a Discord embed: its title linked to How Long to Beat, its platforms
+/// and release year, and a field with the average time of every play style it has
+///
+/// Add the cover with [`DiscordEmbed::thumbnail`], and send the embed by serializing it, e.g.
+/// `serde_json::json!({ "embeds": [embed] })` for a webhook.
+///
+/// # Arguments
+///
+/// * `game`:  &Game - The game
+///
+/// returns: DiscordEmbed
+pub fn discord_embed(game: &Game) -> DiscordEmbed {
+    let styles = [
+        ("Main Story", &game.main_story),
+        ("Main + Extras", &game.main_extra),
+        ("Completionist", &game.completionist),
+        ("All Styles", &game.all_styles),
+        ("Co-Op", &game.co_op),
+        ("Vs.", &game.vs),
+    ];
+    let fields = styles
+        .into_iter()
+        .filter_map(|(name, styles)| {
+            let seconds = styles.as_ref()?.average?;
+            Some(DiscordField {
+                name: name.to_string(),
+                // Minutes are precise enough, and seconds would only clutter the embed
+                value: format_duration((seconds / 60.0).round() * 60.0),
+                inline: true,
+            })
+        })
+        .collect();
+    let mut details: Vec<String> = Vec::new();
+    if !game.platforms.is_empty() {
+        details.push(
+            game.platforms
+                .iter()
+                .map(|platform| platform.name())
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+    }
+    if let Some(year) = game.release_year {
+        details.push(year.to_string());
+    }
+    DiscordEmbed {
+        title: game.title.chars().take(DISCORD_TITLE_LENGTH).collect(),
+        url: game.url(),
+        color: DISCORD_COLOR,
+        description: (!details.is_empty()).then(|| details.join(" · ")),
+        thumbnail: None,
+        fields,
+        footer: DiscordFooter {
+            text: "How Long to Beat".to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Styles;
+
+    #[test]
+    fn test_markdown() {
+        let styles = |average| Some(Styles::new(Some(average), None, None, None));
+        let games = [
+            Game::new(
+                "Metal Gear".to_string(),
+                5900,
+                styles(15000.0),
+                styles(18000.0),
+                styles(19800.0),
+                None,
+                None,
+                None,
+            ),
+            Game::new(
+                "Hack|Slash [Remastered]".to_string(),
+                1,
+                styles(3600.0),
+                None,
+                styles(7200.0),
+                None,
+                None,
+                None,
+            ),
+        ];
+        assert_eq!(
+            markdown(&games),
+            "| Game | Main Story (h) | Main + Extras (h) | Completionist (h) |\n\
+             | --- | ---: | ---: | ---: |\n\
+             | [Metal Gear](https://howlongtobeat.com/game/5900) | 4.2 | 5.0 | 5.5 |\n\
+             | [Hack\\|Slash \\[Remastered\\]](https://howlongtobeat.com/game/1) | 1.0 | – | 2.0 |\n\
+             | **Total** | **5.2** | **5.0** | **7.5** |\n"
+        );
+    }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn test_html() {
+        let game = Game::new(
+            "Ratchet & Clank".to_string(),
+            7654,
+            Some(Styles::new(Some(36000.0), None, None, None)),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let cover = Cover {
+            mime: "image/png".to_string(),
+            bytes: b"png".to_vec(),
+        };
+        let forged = Cover {
+            mime: "image/png\"><script>".to_string(),
+            bytes: b"png".to_vec(),
+        };
+        assert_eq!(forged.data_uri(), "data:image/jpeg;base64,cG5n");
+        let page = html(&[game], &HashMap::from([(7654, cover)]));
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains(
+            "<tr><td><img src=\"data:image/png;base64,cG5n\" alt=\"\"></td>\
+             <td data-value=\"Ratchet &amp; Clank\">\
+             <a href=\"https://howlongtobeat.com/game/7654\">Ratchet &amp; Clank</a></td>\
+             <td data-value=\"36000\">10.0</td><td data-value=\"\">–</td>\
+             <td data-value=\"\">–</td></tr>"
+        ));
+        assert!(page.contains("<tr><td></td><th>Total</th><th>10.0</th><th>0.0</th><th>0.0</th>"));
+        assert!(!page.contains("http://") && !page.contains("<link"));
+    }
+
+    #[test]
+    fn test_discord_embed() {
+        let mut game = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            Some(Styles::new(Some(15010.0), None, None, None)),
+            None,
+            None,
+            None,
+            None,
+            Some(Styles::new(Some(3600.0), None, None, None)),
+        );
+        game.platforms = vec![crate::Platform::Nes, crate::Platform::Msx2];
+        game.release_year = Some(1987);
+        let embed =
+            discord_embed(&game).thumbnail("https://howlongtobeat.com/games/metal_gear.jpg");
+        assert_eq!(
+            serde_json::to_value(&embed).unwrap(),
+            serde_json::json!({
+                "title": "Metal Gear",
+                "url": "https://howlongtobeat.com/game/5900",
+                "color": 0x287fc2,
+                "description": "NES, MSX2 · 1987",
+                "thumbnail": {"url": "https://howlongtobeat.com/games/metal_gear.jpg"},
+                "fields": [
+                    {"name": "Main Story", "value": "4h 10m", "inline": true},
+                    {"name": "Vs.", "value": "1h", "inline": true}
+                ],
+                "footer": {"text": "How Long to Beat"}
+            })
+        );
+        game.platforms.clear();
+        game.release_year = None;
+        assert_eq!(discord_embed(&game).description, None);
+    }
+}
@@ -0,0 +1,515 @@
+//! Alternative serde representations of the scraped data.
+//!
+//! [`Game`] serializes with snake_case field names and times in seconds; the
+//! types here carry the same data in the shapes other consumers expect, and
+//! convert to and from [`Game`].
+
+use crate::{Focus, Game, Platform, Styles};
+use serde::Deserialize;
+use serde::Serialize;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A [`Game`] serialized with camelCase field names (`hltbId`, `mainStory`...), for JS frontends
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CamelCaseGame {
+    pub hltb_id: u32,
+    pub title: String,
+    pub main_story: Option<Styles>,
+    pub main_extra: Option<Styles>,
+    pub completionist: Option<Styles>,
+    pub all_styles: Option<Styles>,
+    pub co_op: Option<Styles>,
+    pub vs: Option<Styles>,
+    #[serde(default)]
+    pub platforms: Vec<Platform>,
+    #[serde(default)]
+    pub release_year: Option<i32>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub focus: Vec<Focus>,
+    #[serde(default, with = "timestamp_string")]
+    pub fetched_at: Option<SystemTime>,
+    #[serde(default)]
+    pub id_verified: Option<bool>,
+    #[serde(default)]
+    pub redirected_from: Option<u32>,
+    #[serde(default)]
+    pub search_url: Option<String>,
+}
+
+impl From<Game> for CamelCaseGame {
+    fn from(game: Game) -> CamelCaseGame {
+        CamelCaseGame {
+            hltb_id: game.hltb_id,
+            title: game.title,
+            main_story: game.main_story,
+            main_extra: game.main_extra,
+            completionist: game.completionist,
+            all_styles: game.all_styles,
+            co_op: game.co_op,
+            vs: game.vs,
+            platforms: game.platforms,
+            release_year: game.release_year,
+            aliases: game.aliases,
+            focus: game.focus,
+            fetched_at: game.fetched_at,
+            id_verified: game.id_verified,
+            redirected_from: game.redirected_from,
+            search_url: game.search_url,
+        }
+    }
+}
+
+impl From<CamelCaseGame> for Game {
+    fn from(game: CamelCaseGame) -> Game {
+        Game {
+            hltb_id: game.hltb_id,
+            title: game.title,
+            main_story: game.main_story,
+            main_extra: game.main_extra,
+            completionist: game.completionist,
+            all_styles: game.all_styles,
+            co_op: game.co_op,
+            vs: game.vs,
+            platforms: game.platforms,
+            release_year: game.release_year,
+            aliases: game.aliases,
+            focus: game.focus,
+            fetched_at: game.fetched_at,
+            id_verified: game.id_verified,
+            redirected_from: game.redirected_from,
+            search_url: game.search_url,
+        }
+    }
+}
+
+/// Formats a time in seconds as a human readable duration, e.g. "4h 8m"
+///
+/// # Arguments
+///
+/// * `seconds`:  f32 - The time in seconds
+///
+/// returns: String
+pub fn format_duration(seconds: f32) -> String {
+    let total = seconds.max(0.0).round() as u64;
+    let (hours, minutes, seconds) = (total / 3600, total % 3600 / 60, total % 60);
+    let parts: Vec<String> = [(hours, "h"), (minutes, "m"), (seconds, "s")]
+        .iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect();
+    if parts.is_empty() {
+        "0s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// Parses a duration written by [`format_duration`] back to seconds
+///
+/// # Arguments
+///
+/// * `text`:  &str - The duration, e.g. "4h 8m"
+///
+/// returns: Option<f32>
+pub fn parse_duration(text: &str) -> Option<f32> {
+    let mut total = 0.0;
+    let mut parts = text.split_whitespace().peekable();
+    parts.peek()?;
+    for part in parts {
+        let unit = match part.chars().last()? {
+            'h' => 3600.0,
+            'm' => 60.0,
+            's' => 1.0,
+            _ => return None,
+        };
+        total += part[..part.len() - 1].parse::<f32>().ok()? * unit;
+    }
+    Some(total)
+}
+
+/// Serializes an optional time in seconds as a duration string such as "4h 8m"
+///
+/// Use with `#[serde(with = "howlongtobeat_scraper::repr::duration_string")]` on an
+/// `Option<f32>` field.
+pub mod duration_string {
+    use super::{format_duration, parse_duration};
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        seconds: &Option<f32>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match seconds {
+            Some(seconds) => serializer.serialize_some(&format_duration(*seconds)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<f32>, D::Error> {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(text) => parse_duration(&text)
+                .map(Some)
+                .ok_or_else(|| D::Error::custom(format!("invalid duration: {}", text))),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Formats a point in time as an RFC 3339 timestamp in UTC, e.g. "2024-02-08T13:45:00Z"
+///
+/// Fractions of a second are only written when there are some.
+///
+/// # Arguments
+///
+/// * `time`:  SystemTime - The point in time, clamped to the Unix epoch
+///
+/// returns: String
+pub fn format_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs() as i64;
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86400));
+    let second_of_day = seconds.rem_euclid(86400);
+    let mut text = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        second_of_day / 3600,
+        second_of_day % 3600 / 60,
+        second_of_day % 60
+    );
+    let nanos = since_epoch.subsec_nanos();
+    if nanos > 0 {
+        text.push_str(format!(".{:09}", nanos).trim_end_matches('0'));
+    }
+    text.push('Z');
+    text
+}
+
+/// Parses an RFC 3339 timestamp, e.g. "2024-02-08T13:45:00Z" or "2024-02-08T14:45:00.5+01:00"
+///
+/// # Arguments
+///
+/// * `text`:  &str - The timestamp
+///
+/// returns: Option<SystemTime> - None if the timestamp is invalid or before the Unix epoch
+pub fn parse_timestamp(text: &str) -> Option<SystemTime> {
+    let text = text.trim();
+    let number = |range: std::ops::Range<usize>| text.get(range)?.parse::<i64>().ok();
+    let separators = [(4, b'-'), (7, b'-'), (13, b':'), (16, b':')];
+    if text.len() < 20
+        || !separators.iter().all(|(i, c)| text.as_bytes()[*i] == *c)
+        || !matches!(text.as_bytes()[10], b'T' | b't' | b' ')
+    {
+        return None;
+    }
+    let (month, day) = (number(5..7)?, number(8..10)?);
+    let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+    // A leap second (":60") is read as the first second of the next minute
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+    let mut rest = &text[19..];
+    let mut nanos = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(fraction.len());
+        if digits == 0 {
+            return None;
+        }
+        let padded = format!("{:0<9}", &fraction[..digits.min(9)]);
+        nanos = padded.parse::<u32>().ok()?;
+        rest = &fraction[digits..];
+    }
+    let offset = match rest {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes().first()? {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            // Two digits each, so the offset can't overflow the seconds
+            let (hours, minutes) = rest[1..].split_once(':')?;
+            let two_digits =
+                |part: &str| part.len() == 2 && part.bytes().all(|b| b.is_ascii_digit());
+            if !two_digits(hours) || !two_digits(minutes) {
+                return None;
+            }
+            let (hours, minutes) = (hours.parse::<u8>().ok()?, minutes.parse::<u8>().ok()?);
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            sign * (i64::from(hours) * 3600 + i64::from(minutes) * 60)
+        }
+    };
+    let days = days_from_civil(number(0..4)?, month, day);
+    let seconds = days * 86400 + hour * 3600 + minute * 60 + second - offset;
+    let seconds = u64::try_from(seconds).ok()?;
+    Some(UNIX_EPOCH + Duration::new(seconds, nanos))
+}
+
+/// Returns the number of days between the Unix epoch and a date of the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Returns the date of the proleptic Gregorian calendar a number of days after the Unix epoch
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Serializes an optional point in time as an RFC 3339 timestamp such as "2024-02-08T13:45:00Z"
+///
+/// Use with `#[serde(with = "howlongtobeat_scraper::repr::timestamp_string")]` on an
+/// `Option<SystemTime>` field.
+pub mod timestamp_string {
+    use super::{format_timestamp, parse_timestamp};
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::SystemTime;
+
+    pub fn serialize<S: Serializer>(
+        time: &Option<SystemTime>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match time {
+            Some(time) => serializer.serialize_some(&format_timestamp(*time)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<SystemTime>, D::Error> {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(text) => parse_timestamp(&text)
+                .map(Some)
+                .ok_or_else(|| D::Error::custom(format!("invalid timestamp: {}", text))),
+            None => Ok(None),
+        }
+    }
+}
+
+/// [`Styles`] with its times written as duration strings
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+pub struct HumanReadableStyles {
+    #[serde(with = "duration_string")]
+    pub average: Option<f32>,
+    #[serde(with = "duration_string")]
+    pub median: Option<f32>,
+    #[serde(with = "duration_string")]
+    pub rushed: Option<f32>,
+    #[serde(with = "duration_string")]
+    pub leisure: Option<f32>,
+}
+
+impl From<Styles> for HumanReadableStyles {
+    fn from(styles: Styles) -> HumanReadableStyles {
+        HumanReadableStyles {
+            average: styles.average,
+            median: styles.median,
+            rushed: styles.rushed,
+            leisure: styles.leisure,
+        }
+    }
+}
+
+impl From<HumanReadableStyles> for Styles {
+    fn from(styles: HumanReadableStyles) -> Styles {
+        Styles::new(styles.average, styles.median, styles.rushed, styles.leisure)
+    }
+}
+
+/// A [`Game`] with its times written as duration strings (e.g. "4h 8m"), for config files and spreadsheets
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+pub struct HumanReadableGame {
+    pub hltb_id: u32,
+    pub title: String,
+    pub main_story: Option<HumanReadableStyles>,
+    pub main_extra: Option<HumanReadableStyles>,
+    pub completionist: Option<HumanReadableStyles>,
+    pub all_styles: Option<HumanReadableStyles>,
+    pub co_op: Option<HumanReadableStyles>,
+    pub vs: Option<HumanReadableStyles>,
+    #[serde(default)]
+    pub platforms: Vec<Platform>,
+    #[serde(default)]
+    pub release_year: Option<i32>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub focus: Vec<Focus>,
+    #[serde(default, with = "timestamp_string")]
+    pub fetched_at: Option<SystemTime>,
+    #[serde(default)]
+    pub id_verified: Option<bool>,
+    #[serde(default)]
+    pub redirected_from: Option<u32>,
+    #[serde(default)]
+    pub search_url: Option<String>,
+}
+
+impl From<Game> for HumanReadableGame {
+    fn from(game: Game) -> HumanReadableGame {
+        HumanReadableGame {
+            hltb_id: game.hltb_id,
+            title: game.title,
+            main_story: game.main_story.map(Into::into),
+            main_extra: game.main_extra.map(Into::into),
+            completionist: game.completionist.map(Into::into),
+            all_styles: game.all_styles.map(Into::into),
+            co_op: game.co_op.map(Into::into),
+            vs: game.vs.map(Into::into),
+            platforms: game.platforms,
+            release_year: game.release_year,
+            aliases: game.aliases,
+            focus: game.focus,
+            fetched_at: game.fetched_at,
+            id_verified: game.id_verified,
+            redirected_from: game.redirected_from,
+            search_url: game.search_url,
+        }
+    }
+}
+
+impl From<HumanReadableGame> for Game {
+    fn from(game: HumanReadableGame) -> Game {
+        Game {
+            hltb_id: game.hltb_id,
+            title: game.title,
+            main_story: game.main_story.map(Into::into),
+            main_extra: game.main_extra.map(Into::into),
+            completionist: game.completionist.map(Into::into),
+            all_styles: game.all_styles.map(Into::into),
+            co_op: game.co_op.map(Into::into),
+            vs: game.vs.map(Into::into),
+            platforms: game.platforms,
+            release_year: game.release_year,
+            aliases: game.aliases,
+            focus: game.focus,
+            fetched_at: game.fetched_at,
+            id_verified: game.id_verified,
+            redirected_from: game.redirected_from,
+            search_url: game.search_url,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_camel_case_round_trip() {
+        let mut game = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            Some(Styles::new(Some(15000.0), None, None, None)),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        game.release_year = Some(1987);
+        let json = serde_json::to_value(CamelCaseGame::from(game.clone())).unwrap();
+        assert_eq!(json["hltbId"], 5900);
+        assert_eq!(json["mainStory"]["average"], 15000.0);
+        assert_eq!(json["releaseYear"], 1987);
+        assert!(json.get("hltb_id").is_none());
+
+        let parsed: CamelCaseGame = serde_json::from_value(json).unwrap();
+        assert!(Game::from(parsed).data_eq(&game));
+    }
+
+    #[test]
+    fn test_format_and_parse_duration() {
+        assert_eq!(format_duration(14880.0), "4h 8m");
+        assert_eq!(format_duration(3725.0), "1h 2m 5s");
+        assert_eq!(format_duration(0.0), "0s");
+        assert_eq!(parse_duration("4h 8m"), Some(14880.0));
+        assert_eq!(parse_duration("1h 2m 5s"), Some(3725.0));
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("4 hours"), None);
+    }
+
+    #[test]
+    fn test_format_and_parse_timestamp() {
+        let time = UNIX_EPOCH + Duration::from_secs(1707399900);
+        assert_eq!(format_timestamp(time), "2024-02-08T13:45:00Z");
+        assert_eq!(parse_timestamp("2024-02-08T13:45:00Z"), Some(time));
+        assert_eq!(parse_timestamp("2024-02-08T14:45:00+01:00"), Some(time));
+        let precise = time + Duration::from_millis(250);
+        assert_eq!(format_timestamp(precise), "2024-02-08T13:45:00.25Z");
+        assert_eq!(parse_timestamp(&format_timestamp(precise)), Some(precise));
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        assert_eq!(
+            parse_timestamp("2000-02-29T00:00:00Z"),
+            Some(UNIX_EPOCH + Duration::from_secs(951782400))
+        );
+        assert_eq!(parse_timestamp("2024-13-08T13:45:00Z"), None);
+        assert_eq!(parse_timestamp("yesterday"), None);
+        assert_eq!(
+            parse_timestamp("2024-02-08T13:45:00+9999999999999999:00"),
+            None
+        );
+        assert_eq!(parse_timestamp("2024-02-08T13:45:00+24:00"), None);
+        assert_eq!(parse_timestamp("2024-02-08T13:45:00++1:00"), None);
+    }
+
+    #[test]
+    fn test_human_readable_round_trip() {
+        let game = Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            Some(Styles::new(Some(14880.0), None, None, None)),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let json = serde_json::to_value(HumanReadableGame::from(game.clone())).unwrap();
+        assert_eq!(json["main_story"]["average"], "4h 8m");
+        assert!(json["main_story"]["median"].is_null());
+
+        let parsed: HumanReadableGame = serde_json::from_value(json).unwrap();
+        assert!(Game::from(parsed).data_eq(&game));
+        assert!(serde_json::from_str::<HumanReadableStyles>(
+            r#"{"average": "soon", "median": null, "rushed": null, "leisure": null}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,243 @@
+//! User reviews of a game.
+
+use crate::{fetch_page_content, BASE_URL};
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+/// The maximum number of review pages read for a game, as a guard against endless pagination
+const MAX_REVIEW_PAGES: u32 = 200;
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone, Default)]
+pub struct Review {
+    /// The name of the user who wrote the review
+    pub author: String,
+    /// The score given by the user, out of 100
+    pub score: Option<u8>,
+    /// The aspects the review is tagged with (e.g. "Gameplay", "Story")
+    pub tags: Vec<String>,
+    pub text: String,
+}
+
+/// Fetches every review of a game, following pagination
+///
+/// # Arguments
+///
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+///
+/// returns: Result<Vec<Review>, Box<dyn Error, Global>>
+pub async fn get_reviews_with_sandbox(
+    hltb_id: u32,
+    sandbox: bool,
+) -> Result<Vec<Review>, Box<dyn Error>> {
+    let mut reviews: Vec<Review> = Vec::new();
+    for page in 1..=MAX_REVIEW_PAGES {
+        let url = format!("{}game/{}/reviews/latest/{}", BASE_URL, hltb_id, page);
+        let content = match fetch_page_content(&url, "div[class*='_review_box']", sandbox) {
+            Ok(content) => content,
+            Err(e) if page == 1 => return Err(e),
+            Err(_) => break,
+        };
+        let page_reviews = parse_reviews(&content);
+        // Past the last page the site keeps serving the last one
+        if page_reviews.is_empty() || reviews.ends_with(&page_reviews) {
+            break;
+        }
+        reviews.extend(page_reviews);
+    }
+    Ok(reviews)
+}
+
+/// Fetches every review of a game, following pagination (with sandbox enabled by default)
+///
+/// # Arguments
+///
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+///
+/// returns: Result<Vec<Review>, Box<dyn Error, Global>>
+pub async fn get_reviews(hltb_id: u32) -> Result<Vec<Review>, Box<dyn Error>> {
+    get_reviews_with_sandbox(hltb_id, true).await
+}
+
+/// Fetches the per-category review scores shown on the page of a game
+///
+/// # Arguments
+///
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+///
+/// returns: Result<BTreeMap<String, u8>, Box<dyn Error, Global>>
+pub async fn get_review_breakdown_with_sandbox(
+    hltb_id: u32,
+    sandbox: bool,
+) -> Result<BTreeMap<String, u8>, Box<dyn Error>> {
+    let url = format!("{}game/{}", BASE_URL, hltb_id);
+    let content = fetch_page_content(&url, "div[class*='_review_breakdown']", sandbox)?;
+    Ok(parse_review_breakdown(&content))
+}
+
+/// Fetches the per-category review scores shown on the page of a game (with sandbox enabled by default)
+///
+/// # Arguments
+///
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+///
+/// returns: Result<BTreeMap<String, u8>, Box<dyn Error, Global>>
+pub async fn get_review_breakdown(hltb_id: u32) -> Result<BTreeMap<String, u8>, Box<dyn Error>> {
+    get_review_breakdown_with_sandbox(hltb_id, true).await
+}
+
+/// Parses a review score such as "85%" or "8.5/10" into a score out of 100
+///
+/// # Arguments
+///
+/// * `text`:  &str - The score as displayed
+///
+/// returns: Option<u8>
+fn parse_score(text: &str) -> Option<u8> {
+    let text = text.trim();
+    let score = match text.split_once('/') {
+        Some((score, scale)) => {
+            score.trim().parse::<f32>().ok()? * 100.0 / scale.trim().parse::<f32>().ok()?
+        }
+        None => text.trim_end_matches('%').trim().parse::<f32>().ok()?,
+    };
+    (0.0..=100.0).contains(&score).then(|| score.round() as u8)
+}
+
+/// Returns the trimmed text of the first element of a review matching a selector
+///
+/// # Arguments
+///
+/// * `review`:  ElementRef - The review
+/// * `selector`:  &Selector - The selector of the element
+///
+/// returns: Option<String>
+fn select_text(review: ElementRef, selector: &Selector) -> Option<String> {
+    review
+        .select(selector)
+        .next()
+        .map(|e| e.text().collect::<String>().trim().to_string())
+}
+
+/// Parses the reviews of a review page
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the review page
+///
+/// returns: Vec<Review>
+fn parse_reviews(content: &str) -> Vec<Review> {
+    let document = Html::parse_document(content);
+    let review_selector = Selector::parse("div[class*='_review_box']").unwrap();
+    let author_selector = Selector::parse("a[href*='/user/']").unwrap();
+    let score_selector = Selector::parse("[class*='_review_score']").unwrap();
+    let tag_selector = Selector::parse("[class*='_review_tag']").unwrap();
+    let text_selector = Selector::parse("[class*='_review_text']").unwrap();
+
+    document
+        .select(&review_selector)
+        .filter_map(|review| {
+            let text = select_text(review, &text_selector).unwrap_or_default();
+            if text.is_empty() {
+                return None;
+            }
+            Some(Review {
+                author: select_text(review, &author_selector).unwrap_or_default(),
+                score: select_text(review, &score_selector).and_then(|s| parse_score(&s)),
+                tags: review
+                    .select(&tag_selector)
+                    .map(|t| t.text().collect::<String>().trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .collect(),
+                text,
+            })
+        })
+        .collect()
+}
+
+/// Parses the review breakdown of a game page into a map of category to score out of 100
+///
+/// Every category is a block holding a label and a score (e.g. "Gameplay" and "82%").
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the game page
+///
+/// returns: BTreeMap<String, u8>
+fn parse_review_breakdown(content: &str) -> BTreeMap<String, u8> {
+    let document = Html::parse_document(content);
+    let selector = Selector::parse("div[class*='_review_breakdown'] > *").unwrap();
+    document
+        .select(&selector)
+        .filter_map(|element| {
+            let parts: Vec<String> = element
+                .text()
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect();
+            let (score, label) = parts.split_last()?;
+            Some((label.join(" "), parse_score(score)?))
+        })
+        .filter(|(label, _)| !label.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reviews() {
+        let content = r#"<html><body>
+            <div class="GameReviews_review_box__a1B2c">
+                <a href="/user/someone">someone</a>
+                <span class="GameReviews_review_score__d3E4f">85%</span>
+                <span class="GameReviews_review_tag__g5H6i">Gameplay</span>
+                <span class="GameReviews_review_tag__g5H6i">Story</span>
+                <p class="GameReviews_review_text__j7K8l"> A classic. </p>
+            </div>
+            <div class="GameReviews_review_box__a1B2c">
+                <a href="/user/other">other</a>
+                <p class="GameReviews_review_text__j7K8l">Too short.</p>
+            </div>
+        </body></html>"#;
+        let reviews = parse_reviews(content);
+        assert_eq!(
+            reviews[0],
+            Review {
+                author: "someone".to_string(),
+                score: Some(85),
+                tags: vec!["Gameplay".to_string(), "Story".to_string()],
+                text: "A classic.".to_string(),
+            }
+        );
+        assert_eq!(reviews[1].score, None);
+        assert!(reviews[1].tags.is_empty());
+    }
+
+    #[test]
+    fn test_parse_review_breakdown() {
+        let content = r#"<html><body>
+            <div class="GameSummary_review_breakdown__m9N0o">
+                <div><span>Gameplay</span><span>82%</span></div>
+                <div><span>Story</span><span>7.5/10</span></div>
+                <div><span>Graphics</span><span>--</span></div>
+            </div>
+        </body></html>"#;
+        let breakdown = parse_review_breakdown(content);
+        assert_eq!(breakdown.len(), 2);
+        assert_eq!(breakdown.get("Gameplay"), Some(&82));
+        assert_eq!(breakdown.get("Story"), Some(&75));
+    }
+
+    #[test]
+    fn test_parse_score() {
+        assert_eq!(parse_score("85%"), Some(85));
+        assert_eq!(parse_score("8.5/10"), Some(85));
+        assert_eq!(parse_score("NR"), None);
+    }
+}
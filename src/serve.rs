@@ -0,0 +1,282 @@
+//! Embeddable REST service exposing the scraper over HTTP.
+//!
+//! Endpoints:
+//!
+//! * `GET /search?q=<name>` - searches a game by name
+//! * `GET /game/:id` - fetches a game by its How Long to Beat ID
+//! * `POST /batch` - resolves several names and IDs at once
+//! * `POST /graphql` - GraphQL schema over the same lookups (`graphql` feature)
+//! * `GET /metrics` - Prometheus metrics of the service
+//!
+//! Every lookup goes through a single [`HltbClient`], configured from the
+//! environment, so lookups share its cache, rate limit and request slots, and
+//! through a concurrency limiter. Games that can't be found are answered with a
+//! 404, timeouts with a 504 and other failures with a 502.
+
+use crate::{spawn_lookup, Game, HltbClient, HltbError};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde::Serialize;
+use std::error::Error;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+#[cfg(feature = "graphql")]
+pub mod graphql;
+mod metrics;
+
+use metrics::{Metrics, MetricsObserver};
+
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// Whether to enable sandbox mode for the browser
+    pub sandbox: bool,
+    /// The maximum number of lookups running at the same time
+    pub max_concurrent: usize,
+    /// The directory of the cache of the lookups, `HLTB_CACHE_DIR` when None
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            sandbox: true,
+            max_concurrent: 2,
+            cache_dir: None,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct AppState {
+    client: Arc<HltbClient>,
+    limiter: Arc<Semaphore>,
+    metrics: Arc<Metrics>,
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    q: String,
+}
+
+#[derive(Deserialize, Default)]
+struct BatchRequest {
+    #[serde(default)]
+    names: Vec<String>,
+    #[serde(default)]
+    ids: Vec<u32>,
+}
+
+#[derive(Serialize)]
+struct BatchEntry {
+    query: String,
+    game: Option<Game>,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl From<Box<dyn Error + Send + Sync>> for ApiError {
+    fn from(error: Box<dyn Error + Send + Sync>) -> ApiError {
+        let status = match error.downcast_ref::<HltbError>() {
+            Some(HltbError::NoMatch(_) | HltbError::NotOnPlatform { .. }) => StatusCode::NOT_FOUND,
+            Some(HltbError::WaitTimedOut(_)) => StatusCode::GATEWAY_TIMEOUT,
+            _ => StatusCode::BAD_GATEWAY,
+        };
+        ApiError {
+            status,
+            message: error.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (
+            self.status,
+            Json(ErrorBody {
+                error: self.message,
+            }),
+        )
+            .into_response()
+    }
+}
+
+impl AppState {
+    fn new(config: &ServerConfig) -> Result<AppState, Box<dyn Error>> {
+        let metrics = Arc::new(Metrics::default());
+        let mut builder = HltbClient::builder()
+            .sandbox(config.sandbox)
+            .observer(MetricsObserver(metrics.clone()));
+        if let Some(dir) = &config.cache_dir {
+            builder = builder.cache_dir(dir);
+        }
+        Ok(AppState {
+            client: Arc::new(builder.build()?),
+            limiter: Arc::new(Semaphore::new(config.max_concurrent.max(1))),
+            metrics,
+        })
+    }
+
+    async fn by_name(&self, name: &str) -> Result<Game, ApiError> {
+        let (client, name) = (self.client.clone(), name.to_string());
+        self.lookup(move || async move { client.search_by_name(&name).await })
+            .await
+    }
+
+    async fn by_id(&self, hltb_id: u32) -> Result<Game, ApiError> {
+        let client = self.client.clone();
+        self.lookup(move || async move { client.search_by_id(hltb_id).await })
+            .await
+    }
+
+    /// Runs a lookup within the concurrency limit, recording its failure if it fails
+    ///
+    /// # Arguments
+    ///
+    /// * `lookup`:  F - Creates the lookup future, see [`spawn_lookup`]
+    ///
+    /// returns: Result<Game, ApiError>
+    async fn lookup<F, Fut>(&self, lookup: F) -> Result<Game, ApiError>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = Result<Game, Box<dyn Error>>>,
+    {
+        let _permit = self.limiter.acquire().await.map_err(|e| ApiError {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            message: e.to_string(),
+        })?;
+        let result = spawn_lookup(lookup).await;
+        if let Err(e) = &result {
+            self.metrics.failure(e.as_ref());
+        }
+        Ok(result?)
+    }
+}
+
+async fn search(
+    State(state): State<AppState>,
+    Query(params): Query<SearchParams>,
+) -> Result<Json<Game>, ApiError> {
+    state.metrics.request("search");
+    state.by_name(&params.q).await.map(Json)
+}
+
+async fn game(
+    State(state): State<AppState>,
+    Path(hltb_id): Path<u32>,
+) -> Result<Json<Game>, ApiError> {
+    state.metrics.request("game");
+    state.by_id(hltb_id).await.map(Json)
+}
+
+async fn batch(
+    State(state): State<AppState>,
+    Json(request): Json<BatchRequest>,
+) -> Json<Vec<BatchEntry>> {
+    state.metrics.request("batch");
+    let mut entries = Vec::new();
+    for name in request.names {
+        let result = state.by_name(&name).await;
+        entries.push(BatchEntry::new(name, result));
+    }
+    for hltb_id in request.ids {
+        let result = state.by_id(hltb_id).await;
+        entries.push(BatchEntry::new(hltb_id.to_string(), result));
+    }
+    Json(entries)
+}
+
+async fn prometheus(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [("content-type", "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    )
+}
+
+impl BatchEntry {
+    fn new(query: String, result: Result<Game, ApiError>) -> BatchEntry {
+        match result {
+            Ok(game) => BatchEntry {
+                query,
+                game: Some(game),
+                error: None,
+            },
+            Err(error) => BatchEntry {
+                query,
+                game: None,
+                error: Some(error.message),
+            },
+        }
+    }
+}
+
+/// Builds the router of the service, to be nested into an existing axum application
+///
+/// # Arguments
+///
+/// * `config`:  ServerConfig - The configuration of the service
+///
+/// returns: Result<Router, Box<dyn Error, Global>> - An error if the client can't be built
+pub fn router(config: ServerConfig) -> Result<Router, Box<dyn Error>> {
+    let state = AppState::new(&config)?;
+    let router = Router::new()
+        .route("/search", get(search))
+        .route("/game/{id}", get(game))
+        .route("/batch", post(batch))
+        .route("/metrics", get(prometheus))
+        .with_state(state.clone());
+    #[cfg(feature = "graphql")]
+    let router = router.merge(graphql::router(state));
+    Ok(router)
+}
+
+/// Starts the service and serves requests until the process stops
+///
+/// # Arguments
+///
+/// * `addr`:  SocketAddr - The address to listen on
+/// * `config`:  ServerConfig - The configuration of the service
+///
+/// returns: Result<(), Box<dyn Error, Global>>
+pub async fn serve(addr: SocketAddr, config: ServerConfig) -> Result<(), Box<dyn Error>> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router(config)?).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_error_status() {
+        let status = |error: HltbError| ApiError::from(Box::new(error) as Box<_>).status;
+        assert_eq!(
+            status(HltbError::NoMatch("Metal Gear".to_string())),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status(HltbError::WaitTimedOut("the results".to_string())),
+            StatusCode::GATEWAY_TIMEOUT
+        );
+        assert_eq!(
+            status(HltbError::BackendUnavailable(Vec::new())),
+            StatusCode::BAD_GATEWAY
+        );
+    }
+}
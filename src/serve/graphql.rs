@@ -0,0 +1,79 @@
+//! GraphQL endpoint of the service, served at `POST /graphql`.
+//!
+//! Resolvers share the client and concurrency limiter of the REST endpoints, and
+//! clients select only the fields they need:
+//!
+//! ```graphql
+//! { search(name: "Metal Gear") { hltbId title mainStory { average } } }
+//! ```
+
+use super::AppState;
+use crate::Game;
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema};
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use axum::extract::State;
+use axum::routing::post;
+use axum::Router;
+
+pub type HltbSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Searches a game by name
+    async fn search(&self, ctx: &Context<'_>, name: String) -> async_graphql::Result<Game> {
+        let state = ctx.data::<AppState>()?;
+        state
+            .by_name(&name)
+            .await
+            .map_err(|e| async_graphql::Error::new(e.message))
+    }
+
+    /// Fetches a game by its How Long to Beat ID
+    async fn game(&self, ctx: &Context<'_>, id: u32) -> async_graphql::Result<Game> {
+        let state = ctx.data::<AppState>()?;
+        state
+            .by_id(id)
+            .await
+            .map_err(|e| async_graphql::Error::new(e.message))
+    }
+
+    /// Searches several games by name, returning null for the ones that can't be found
+    async fn batch(
+        &self,
+        ctx: &Context<'_>,
+        names: Vec<String>,
+    ) -> async_graphql::Result<Vec<Option<Game>>> {
+        let state = ctx.data::<AppState>()?;
+        let mut games = Vec::new();
+        for name in names {
+            games.push(state.by_name(&name).await.ok());
+        }
+        Ok(games)
+    }
+}
+
+async fn handler(
+    State((schema, state)): State<(HltbSchema, AppState)>,
+    request: GraphQLRequest,
+) -> GraphQLResponse {
+    state.metrics.request("graphql");
+    schema.execute(request.into_inner()).await.into()
+}
+
+/// Builds the GraphQL router on top of the state shared with the REST endpoints
+///
+/// # Arguments
+///
+/// * `state`:  AppState - The state of the service
+///
+/// returns: Router
+pub(super) fn router(state: AppState) -> Router {
+    let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(state.clone())
+        .finish();
+    Router::new()
+        .route("/graphql", post(handler))
+        .with_state((schema, state))
+}
@@ -0,0 +1,210 @@
+//! Prometheus metrics of the service, served at `GET /metrics` in the text
+//! exposition format.
+//!
+//! Cache hits and scrapes are reported by the client of the service through a
+//! [`MetricsObserver`], failures by the lookups themselves.
+
+use crate::client::Observer;
+use crate::parse::game_id_from_href;
+use crate::HltbError;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Upper bounds, in seconds, of the scrape latency histogram buckets
+const LATENCY_BUCKETS: [f64; 8] = [0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 30.0, 60.0];
+
+#[derive(Default)]
+pub(super) struct Metrics {
+    requests: Mutex<BTreeMap<&'static str, u64>>,
+    failures: Mutex<BTreeMap<&'static str, u64>>,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    latency_buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+    latency_count: AtomicU64,
+    latency_sum_micros: AtomicU64,
+}
+
+impl Metrics {
+    pub(super) fn request(&self, endpoint: &'static str) {
+        *self.requests.lock().unwrap().entry(endpoint).or_insert(0) += 1;
+    }
+
+    pub(super) fn cache(&self, hit: bool) {
+        let counter = if hit {
+            &self.cache_hits
+        } else {
+            &self.cache_misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a scrape, i.e. a request of a page
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed`:  Duration - How long the scrape took
+    pub(super) fn scrape(&self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        for (bucket, bound) in self.latency_buckets.iter().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.latency_count.fetch_add(1, Ordering::Relaxed);
+        self.latency_sum_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Records a failed lookup, classified by its error
+    ///
+    /// # Arguments
+    ///
+    /// * `error`:  &dyn Error - The error of the lookup
+    pub(super) fn failure(&self, error: &(dyn Error + 'static)) {
+        *self
+            .failures
+            .lock()
+            .unwrap()
+            .entry(error_kind(error))
+            .or_insert(0) += 1;
+    }
+
+    /// Renders every metric in the Prometheus text exposition format
+    ///
+    /// returns: String
+    pub(super) fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# HELP hltb_requests_total Requests received, by endpoint.\n");
+        out.push_str("# TYPE hltb_requests_total counter\n");
+        for (endpoint, count) in self.requests.lock().unwrap().iter() {
+            let _ = writeln!(
+                out,
+                "hltb_requests_total{{endpoint=\"{}\"}} {}",
+                endpoint, count
+            );
+        }
+        out.push_str("# HELP hltb_cache_hits_total Games and searches served from the cache.\n");
+        out.push_str("# TYPE hltb_cache_hits_total counter\n");
+        let _ = writeln!(
+            out,
+            "hltb_cache_hits_total {}",
+            self.cache_hits.load(Ordering::Relaxed)
+        );
+        out.push_str(
+            "# HELP hltb_cache_misses_total Games scraped instead of served from the cache.\n",
+        );
+        out.push_str("# TYPE hltb_cache_misses_total counter\n");
+        let _ = writeln!(
+            out,
+            "hltb_cache_misses_total {}",
+            self.cache_misses.load(Ordering::Relaxed)
+        );
+        out.push_str("# HELP hltb_scrape_failures_total Failed lookups, by error type.\n");
+        out.push_str("# TYPE hltb_scrape_failures_total counter\n");
+        for (kind, count) in self.failures.lock().unwrap().iter() {
+            let _ = writeln!(
+                out,
+                "hltb_scrape_failures_total{{kind=\"{}\"}} {}",
+                kind, count
+            );
+        }
+        out.push_str("# HELP hltb_scrape_duration_seconds Duration of scrapes.\n");
+        out.push_str("# TYPE hltb_scrape_duration_seconds histogram\n");
+        for (bucket, bound) in self.latency_buckets.iter().zip(LATENCY_BUCKETS) {
+            let _ = writeln!(
+                out,
+                "hltb_scrape_duration_seconds_bucket{{le=\"{}\"}} {}",
+                bound,
+                bucket.load(Ordering::Relaxed)
+            );
+        }
+        let count = self.latency_count.load(Ordering::Relaxed);
+        let sum = self.latency_sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        let _ = writeln!(
+            out,
+            "hltb_scrape_duration_seconds_bucket{{le=\"+Inf\"}} {}",
+            count
+        );
+        let _ = writeln!(out, "hltb_scrape_duration_seconds_sum {}", sum);
+        let _ = writeln!(out, "hltb_scrape_duration_seconds_count {}", count);
+        out
+    }
+}
+
+/// Reports the cache hits and the scrapes of the client of the service to its metrics
+pub(super) struct MetricsObserver(pub(super) Arc<Metrics>);
+
+impl Observer for MetricsObserver {
+    fn on_request_end(&self, url: &str, elapsed: Duration, error: Option<&str>) {
+        self.0.scrape(elapsed);
+        // A game is only requested when it isn't cached
+        if error.is_none() && game_id_from_href(url).is_some() {
+            self.0.cache(false);
+        }
+    }
+
+    fn on_cache_hit(&self, _hltb_id: u32) {
+        self.0.cache(true);
+    }
+}
+
+/// Classifies a lookup error into a small set of metric labels
+///
+/// # Arguments
+///
+/// * `error`:  &dyn Error - The error of the lookup
+///
+/// returns: &'static str
+fn error_kind(error: &(dyn Error + 'static)) -> &'static str {
+    match error.downcast_ref::<HltbError>() {
+        Some(
+            HltbError::NoMatch(_)
+            | HltbError::NotOnPlatform { .. }
+            | HltbError::ElementNotFound(_)
+            | HltbError::MissingData(_),
+        ) => "not_found",
+        Some(HltbError::WaitTimedOut(_)) => "timeout",
+        Some(HltbError::BackendUnavailable(_)) => "browser",
+        _ => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics::default();
+        metrics.request("search");
+        metrics.cache(false);
+        metrics.scrape(Duration::from_millis(1500));
+        metrics.scrape(Duration::from_secs(3));
+        metrics.failure(&HltbError::ElementNotFound("h1".to_string()));
+        let text = metrics.render();
+        assert!(text.contains("hltb_requests_total{endpoint=\"search\"} 1\n"));
+        assert!(text.contains("hltb_cache_misses_total 1\n"));
+        assert!(text.contains("hltb_scrape_failures_total{kind=\"not_found\"} 1\n"));
+        assert!(text.contains("hltb_scrape_duration_seconds_bucket{le=\"1\"} 0\n"));
+        assert!(text.contains("hltb_scrape_duration_seconds_bucket{le=\"2\"} 1\n"));
+        assert!(text.contains("hltb_scrape_duration_seconds_bucket{le=\"+Inf\"} 2\n"));
+        assert!(text.contains("hltb_scrape_duration_seconds_sum 4.5\n"));
+    }
+
+    #[test]
+    fn test_error_kind() {
+        let timed_out: Box<dyn Error> = HltbError::WaitTimedOut("the results".to_string()).into();
+        assert_eq!(error_kind(timed_out.as_ref()), "timeout");
+        assert_eq!(
+            error_kind(&HltbError::BackendUnavailable(Vec::new())),
+            "browser"
+        );
+        // Only the variant counts, not the message
+        let message: Box<dyn Error> = "Element not found: h1".into();
+        assert_eq!(error_kind(message.as_ref()), "other");
+    }
+}
@@ -0,0 +1,183 @@
+//! Featured and spotlight games of the home page.
+
+use crate::parse::{game_cards, game_id_from_href, GAME_LINK_SELECTOR};
+use crate::{fetch_page_content, BASE_URL};
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+use serde::Serialize;
+use std::error::Error;
+
+/// A module of the home page showcasing games
+#[derive(Deserialize, Debug, PartialEq, Eq, Serialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum HomeModule {
+    /// The featured games, usually at the top of the page
+    Featured,
+    /// The spotlight, games put forward by the staff
+    Spotlight,
+}
+
+impl HomeModule {
+    /// Returns the module a heading introduces, if any
+    fn from_heading(heading: &str) -> Option<HomeModule> {
+        let heading = heading.to_lowercase();
+        if heading.contains("featured") {
+            Some(HomeModule::Featured)
+        } else if heading.contains("spotlight") {
+            Some(HomeModule::Spotlight)
+        } else {
+            None
+        }
+    }
+}
+
+/// A game of a featured or spotlight module of the home page
+#[derive(Deserialize, Debug, PartialEq, Eq, Serialize, Clone)]
+pub struct SpotlightGame {
+    /// The module listing the game
+    pub module: HomeModule,
+    pub hltb_id: u32,
+    pub title: String,
+    /// The text the site shows along with the game, if any
+    pub blurb: Option<String>,
+}
+
+/// Fetches the featured and spotlight games of the home page, in the order of the site
+///
+/// # Arguments
+///
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+///
+/// returns: Result<Vec<SpotlightGame>, Box<dyn Error, Global>>
+pub async fn get_spotlight_with_sandbox(
+    sandbox: bool,
+) -> Result<Vec<SpotlightGame>, Box<dyn Error>> {
+    let content = fetch_page_content(BASE_URL, "a[href*='/game/']", sandbox)?;
+    Ok(parse_spotlight(&content))
+}
+
+/// Fetches the featured and spotlight games of the home page (with sandbox enabled by default)
+///
+/// returns: Result<Vec<SpotlightGame>, Box<dyn Error, Global>>
+pub async fn get_spotlight() -> Result<Vec<SpotlightGame>, Box<dyn Error>> {
+    get_spotlight_with_sandbox(true).await
+}
+
+/// Parses the featured and spotlight games of the home page
+///
+/// A module is the section whose heading mentions "Featured" or "Spotlight"; its games are
+/// its links to details pages, each game being listed once per module. The blurb of a game
+/// is the first paragraph of its card, the largest element of the section holding only
+/// links to that game.
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the home page
+///
+/// returns: Vec<SpotlightGame>
+fn parse_spotlight(content: &str) -> Vec<SpotlightGame> {
+    let document = Html::parse_document(content);
+    let heading_selector = Selector::parse("h1, h2, h3, h4").unwrap();
+    let link_selector = Selector::parse(GAME_LINK_SELECTOR).unwrap();
+    let paragraph_selector = Selector::parse("p").unwrap();
+
+    let mut games: Vec<SpotlightGame> = Vec::new();
+    for heading in document.select(&heading_selector) {
+        let Some(module) = HomeModule::from_heading(&heading.text().collect::<String>()) else {
+            continue;
+        };
+        let Some(section) = heading.parent().and_then(ElementRef::wrap) else {
+            continue;
+        };
+        for card in game_cards(section, &link_selector) {
+            if games
+                .iter()
+                .any(|game| game.module == module && game.hltb_id == card.hltb_id)
+            {
+                continue;
+            }
+            let blurb = card_element(card.link, section, card.hltb_id, &link_selector).and_then(
+                |element| {
+                    element
+                        .select(&paragraph_selector)
+                        .map(|paragraph| paragraph.text().collect::<String>().trim().to_string())
+                        .find(|text| !text.is_empty())
+                },
+            );
+            games.push(SpotlightGame {
+                module,
+                hltb_id: card.hltb_id,
+                title: card.title,
+                blurb,
+            });
+        }
+    }
+    games
+}
+
+/// Returns the card of a game: the largest ancestor of its link within the section that only
+/// links to that game
+fn card_element<'a>(
+    link: ElementRef<'a>,
+    section: ElementRef<'a>,
+    hltb_id: u32,
+    link_selector: &Selector,
+) -> Option<ElementRef<'a>> {
+    link.ancestors()
+        .map_while(ElementRef::wrap)
+        .take_while(|ancestor| ancestor.id() != section.id())
+        .take_while(|ancestor| {
+            ancestor.select(link_selector).all(|other| {
+                other.value().attr("href").and_then(game_id_from_href) == Some(hltb_id)
+            })
+        })
+        .last()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spotlight() {
+        let content = r#"<html><body><main>
+            <div><h2>Trending Games</h2><a href="/game/1">Trending</a></div>
+            <section>
+                <h2>Featured Games</h2>
+                <div class="card">
+                    <a href="/game/129232"><img></a>
+                    <div><a href="/game/129232">Helldivers 2</a><p>Spread managed democracy.</p></div>
+                </div>
+                <div class="card"><a href="/game/5900" title="Metal Gear"><img></a></div>
+            </section>
+            <section>
+                <h3>Staff Spotlight</h3>
+                <div><a href="/game/5900">Metal Gear</a><p> A classic. </p></div>
+            </section>
+        </main></body></html>"#;
+        assert_eq!(
+            parse_spotlight(content),
+            vec![
+                SpotlightGame {
+                    module: HomeModule::Featured,
+                    hltb_id: 129232,
+                    title: "Helldivers 2".to_string(),
+                    blurb: Some("Spread managed democracy.".to_string()),
+                },
+                SpotlightGame {
+                    module: HomeModule::Featured,
+                    hltb_id: 5900,
+                    title: "Metal Gear".to_string(),
+                    blurb: None,
+                },
+                SpotlightGame {
+                    module: HomeModule::Spotlight,
+                    hltb_id: 5900,
+                    title: "Metal Gear".to_string(),
+                    blurb: Some("A classic.".to_string()),
+                },
+            ]
+        );
+        assert!(parse_spotlight("<html></html>").is_empty());
+    }
+}
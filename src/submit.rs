@@ -0,0 +1,266 @@
+//! Authenticated write operations on the user's account.
+//!
+//! The site has no public API for these, so each operation fills in and submits
+//! the corresponding form in the browser, logged in with a [`Session`].
+
+use crate::auth::Session;
+use crate::client::host_slot_blocking;
+use crate::guard::BrowserGuard;
+use crate::user::GameList;
+use crate::{launch_tab, HltbError, Platform, PlayStyle, BASE_URL};
+use headless_chrome::Tab;
+use std::error::Error;
+use std::time::Duration;
+
+/// Builds a script setting the value of a form field the way a user would, so the page's
+/// own handlers see the change
+///
+/// # Arguments
+///
+/// * `selector`:  &str - The selector of the field
+/// * `value`:  &str - The value to set
+///
+/// returns: String
+fn set_field_script(selector: &str, value: &str) -> String {
+    format!(
+        r#"(() => {{
+            const el = document.querySelector({selector});
+            if (!el) return false;
+            const proto = el.tagName === 'SELECT' ? HTMLSelectElement.prototype : HTMLInputElement.prototype;
+            Object.getOwnPropertyDescriptor(proto, 'value').set.call(el, {value});
+            el.dispatchEvent(new Event('input', {{ bubbles: true }}));
+            el.dispatchEvent(new Event('change', {{ bubbles: true }}));
+            return true;
+        }})()"#,
+        selector = serde_json::to_string(selector).unwrap(),
+        value = serde_json::to_string(value).unwrap(),
+    )
+}
+
+/// Builds a script ticking a checkbox by clicking it, so the page's own handlers see the change
+///
+/// # Arguments
+///
+/// * `selector`:  &str - The selector of the checkbox
+///
+/// returns: String
+fn check_box_script(selector: &str) -> String {
+    format!(
+        r#"(() => {{
+            const el = document.querySelector({selector});
+            if (!el) return false;
+            if (!el.checked) el.click();
+            return true;
+        }})()"#,
+        selector = serde_json::to_string(selector).unwrap(),
+    )
+}
+
+/// Returns the name of the checkbox of a game list in the submit form
+///
+/// # Arguments
+///
+/// * `list`:  GameList - The game list
+///
+/// returns: &'static str
+fn list_field(list: GameList) -> &'static str {
+    match list {
+        GameList::Playing => "list_p",
+        GameList::Backlog => "list_b",
+        GameList::Replays => "list_r",
+        GameList::Custom => "list_c",
+        GameList::Completed => "list_cp",
+        GameList::Retired => "list_rt",
+    }
+}
+
+/// Opens the submit form of a game, logged in with a session
+///
+/// The browser shuts down when the returned guard is dropped.
+///
+/// # Arguments
+///
+/// * `session`:  &Session - The logged-in session
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser
+///
+/// returns: Result<BrowserGuard, Box<dyn Error, Global>>
+fn open_submit_form(
+    session: &Session,
+    hltb_id: u32,
+    sandbox: bool,
+) -> Result<BrowserGuard, Box<dyn Error>> {
+    let tab = launch_tab(sandbox)?;
+    session.apply(&tab)?;
+    let _slot = host_slot_blocking(BASE_URL, None)?;
+    tab.navigate_to(&format!("{}submit?gid={}", BASE_URL, hltb_id))?;
+    tab.wait_until_navigated()?;
+    tab.wait_for_element("form select[name='platform']")?;
+    Ok(tab)
+}
+
+/// Runs a form script and fails if the field it targets doesn't exist
+///
+/// # Arguments
+///
+/// * `tab`:  &Tab - The tab showing the form
+/// * `script`:  String - The script to run
+/// * `selector`:  &str - The selector of the field, for the error message
+///
+/// returns: Result<(), Box<dyn Error, Global>>
+fn run_form_script(tab: &Tab, script: String, selector: &str) -> Result<(), Box<dyn Error>> {
+    let result = tab.evaluate(&script, false)?;
+    if result.value != Some(serde_json::Value::Bool(true)) {
+        return Err(format!("Submit form field not found: {}", selector).into());
+    }
+    Ok(())
+}
+
+/// Submits the form shown in a tab
+///
+/// # Arguments
+///
+/// * `tab`:  &Tab - The tab showing the form
+///
+/// returns: Result<(), Box<dyn Error, Global>>
+fn send_form(tab: &Tab) -> Result<(), Box<dyn Error>> {
+    tab.wait_for_element("form button[type='submit']")?
+        .click()?;
+    tab.wait_until_navigated()?;
+    if tab.get_url().contains("/login") {
+        return Err("The session has expired, log in again".into());
+    }
+    Ok(())
+}
+
+/// Returns the prefix of the time fields of a play style in the submit form
+///
+/// # Arguments
+///
+/// * `style`:  PlayStyle - The play style
+///
+/// returns: Result<&'static str, Box<dyn Error, Global>>
+fn time_field_prefix(style: PlayStyle) -> Result<&'static str, Box<dyn Error>> {
+    match style {
+        PlayStyle::MainStory => Ok("c_main"),
+        PlayStyle::MainExtra => Ok("c_plus"),
+        PlayStyle::Completionist => Ok("c_100"),
+        PlayStyle::AllStyles => {
+            Err(HltbError::Unsupported("Times are submitted for a single play style").into())
+        }
+    }
+}
+
+/// Submits a completion time for a game on the account of a session
+///
+/// # Arguments
+///
+/// * `session`:  &Session - The logged-in session
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+/// * `platform`:  impl Into<Platform> - The platform the game was played on, or its name (e.g. "PC")
+/// * `style`:  PlayStyle - The play style the time was achieved in
+/// * `duration`:  Duration - The time it took to finish the game
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+///
+/// returns: Result<(), Box<dyn Error, Global>>
+pub async fn submit_time(
+    session: &Session,
+    hltb_id: u32,
+    platform: impl Into<Platform>,
+    style: PlayStyle,
+    duration: Duration,
+    sandbox: bool,
+) -> Result<(), Box<dyn Error>> {
+    let prefix = time_field_prefix(style)?;
+    let tab = open_submit_form(session, hltb_id, sandbox)?;
+
+    let seconds = duration.as_secs();
+    let fields = [
+        (
+            "select[name='platform']".to_string(),
+            platform.into().to_string(),
+        ),
+        (
+            format!("input[name='{}_h']", prefix),
+            (seconds / 3600).to_string(),
+        ),
+        (
+            format!("input[name='{}_m']", prefix),
+            (seconds % 3600 / 60).to_string(),
+        ),
+        (
+            format!("input[name='{}_s']", prefix),
+            (seconds % 60).to_string(),
+        ),
+    ];
+    for (selector, value) in &fields {
+        run_form_script(&tab, set_field_script(selector, value), selector)?;
+    }
+    send_form(&tab)
+}
+
+/// Adds a game to one of the lists of the account of a session, or moves it there
+///
+/// # Arguments
+///
+/// * `session`:  &Session - The logged-in session
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+/// * `list`:  GameList - The list to put the game in
+/// * `platform`:  Option<&str> - The name of the platform to record, if any (e.g. "PC" or "PS4")
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+///
+/// returns: Result<(), Box<dyn Error, Global>>
+pub async fn set_game_list(
+    session: &Session,
+    hltb_id: u32,
+    list: GameList,
+    platform: Option<&str>,
+    sandbox: bool,
+) -> Result<(), Box<dyn Error>> {
+    let tab = open_submit_form(session, hltb_id, sandbox)?;
+    if let Some(platform) = platform.map(Platform::from) {
+        let selector = "select[name='platform']";
+        run_form_script(&tab, set_field_script(selector, platform.name()), selector)?;
+    }
+    let selector = format!("input[name='{}']", list_field(list));
+    run_form_script(&tab, check_box_script(&selector), &selector)?;
+    send_form(&tab)
+}
+
+/// Adds a game to the backlog of the account of a session
+///
+/// # Arguments
+///
+/// * `session`:  &Session - The logged-in session
+/// * `hltb_id`:  u32 - The ID of the game on How Long to Beat
+/// * `platform`:  Option<&str> - The name of the platform to record, if any (e.g. "PC" or "PS4")
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+///
+/// returns: Result<(), Box<dyn Error, Global>>
+pub async fn add_to_backlog(
+    session: &Session,
+    hltb_id: u32,
+    platform: Option<&str>,
+    sandbox: bool,
+) -> Result<(), Box<dyn Error>> {
+    set_game_list(session, hltb_id, GameList::Backlog, platform, sandbox).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_box_script() {
+        let script = check_box_script(&format!("input[name='{}']", list_field(GameList::Retired)));
+        assert!(script.contains(r#"document.querySelector("input[name='list_rt']")"#));
+        assert!(script.contains("if (!el.checked) el.click();"));
+    }
+
+    #[test]
+    fn test_set_field_script_escapes_values() {
+        let script = set_field_script("input[name='c_main_h']", "4\"; alert(1); \"");
+        assert!(script.contains(r#"document.querySelector("input[name='c_main_h']")"#));
+        assert!(script.contains(r#"set.call(el, "4\"; alert(1); \"")"#));
+    }
+}
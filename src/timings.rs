@@ -0,0 +1,50 @@
+//! Timing breakdown of lookups.
+//!
+//! A lookup sent with `send_with_timings` comes back with the [`Timings`] of its
+//! phases, summed over every page it fetched, so slow lookups can be told apart:
+//! a browser slow to launch, a site slow to answer or to render, or a parser
+//! regression.
+
+use crate::Game;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How long the phases of a lookup took, summed over its pages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct Timings {
+    /// Launching the browser and opening its tabs
+    pub launch: Duration,
+    /// Loading the pages in the browser, or requesting them with the other backends
+    pub navigation: Duration,
+    /// Waiting for the pages to be rendered in the browser and reading them
+    pub wait: Duration,
+    /// Parsing the pages
+    pub parse: Duration,
+    /// The whole lookup, including the waits of the rate limit and the retries
+    pub total: Duration,
+    /// How many pages were requested, none when the game came from the cache
+    pub requests: u32,
+}
+
+impl Timings {
+    /// Adds the timings of another lookup or page to these
+    ///
+    /// # Arguments
+    ///
+    /// * `other`:  &Timings - The other timings
+    pub fn add(&mut self, other: &Timings) {
+        self.launch += other.launch;
+        self.navigation += other.navigation;
+        self.wait += other.wait;
+        self.parse += other.parse;
+        self.total += other.total;
+        self.requests += other.requests;
+    }
+}
+
+/// A game along with the timings of its lookup
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct TimedGame {
+    pub game: Game,
+    pub timings: Timings,
+}
@@ -0,0 +1,108 @@
+//! Trending games of the home page.
+
+use crate::parse::{game_cards, GAME_LINK_SELECTOR};
+use crate::{fetch_page_content, BASE_URL};
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+use serde::Serialize;
+use std::error::Error;
+
+/// A game of the trending list of the home page
+#[derive(Deserialize, Debug, PartialEq, Eq, Serialize, Clone)]
+pub struct TrendingGame {
+    /// The position of the game in the list, starting at 1
+    pub rank: usize,
+    pub hltb_id: u32,
+    pub title: String,
+}
+
+/// Fetches the trending games of the home page, in the order of the site
+///
+/// # Arguments
+///
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+///
+/// returns: Result<Vec<TrendingGame>, Box<dyn Error, Global>>
+pub async fn get_trending_with_sandbox(sandbox: bool) -> Result<Vec<TrendingGame>, Box<dyn Error>> {
+    let content = fetch_page_content(BASE_URL, "a[href*='/game/']", sandbox)?;
+    Ok(parse_trending(&content))
+}
+
+/// Fetches the trending games of the home page (with sandbox enabled by default)
+///
+/// returns: Result<Vec<TrendingGame>, Box<dyn Error, Global>>
+pub async fn get_trending() -> Result<Vec<TrendingGame>, Box<dyn Error>> {
+    get_trending_with_sandbox(true).await
+}
+
+/// Parses the trending games of the home page
+///
+/// The list is the section whose heading mentions "Trending" or "Popular"; its games are
+/// its links to details pages, each game being listed once.
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the home page
+///
+/// returns: Vec<TrendingGame>
+fn parse_trending(content: &str) -> Vec<TrendingGame> {
+    let document = Html::parse_document(content);
+    let heading_selector = Selector::parse("h1, h2, h3, h4").unwrap();
+    let link_selector = Selector::parse(GAME_LINK_SELECTOR).unwrap();
+    let Some(section) = document
+        .select(&heading_selector)
+        .find(|heading| {
+            let text = heading.text().collect::<String>().to_lowercase();
+            text.contains("trending") || text.contains("popular")
+        })
+        .and_then(|heading| heading.parent().and_then(ElementRef::wrap))
+    else {
+        return Vec::new();
+    };
+
+    game_cards(section, &link_selector)
+        .into_iter()
+        .enumerate()
+        .map(|(index, card)| TrendingGame {
+            rank: index + 1,
+            hltb_id: card.hltb_id,
+            title: card.title,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_trending() {
+        let content = r#"<html><body><main>
+            <div><h2>Recently Updated</h2><a href="/game/1">Old news</a></div>
+            <div class="HomeTrending_section">
+                <h2>Trending Games</h2>
+                <ul>
+                    <li><a href="/game/129232"><img></a><a href="/game/129232">Helldivers 2</a></li>
+                    <li><a href="/game/5900" title="Metal Gear"><img></a></li>
+                    <li><a href="/forum">Forum</a></li>
+                </ul>
+            </div>
+        </main></body></html>"#;
+        assert_eq!(
+            parse_trending(content),
+            vec![
+                TrendingGame {
+                    rank: 1,
+                    hltb_id: 129232,
+                    title: "Helldivers 2".to_string()
+                },
+                TrendingGame {
+                    rank: 2,
+                    hltb_id: 5900,
+                    title: "Metal Gear".to_string()
+                },
+            ]
+        );
+        assert!(parse_trending("<html></html>").is_empty());
+    }
+}
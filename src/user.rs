@@ -0,0 +1,498 @@
+//! Public user profiles and game lists.
+
+use crate::auth::Session;
+use crate::parse::{game_id_from_href, GAME_LINK_SELECTOR};
+use crate::{
+    convert_hours_minutes_to_sec_opt, fetch_page_content_with_session, Platform, BASE_URL,
+};
+use scraper::{Html, Selector};
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use urlencoding::encode;
+
+/// The maximum number of pages read from a single list, as a guard against endless pagination
+const MAX_LIST_PAGES: u32 = 500;
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone, Copy)]
+pub enum GameList {
+    Playing,
+    Backlog,
+    Replays,
+    Custom,
+    Completed,
+    Retired,
+}
+
+impl GameList {
+    /// Returns the path segment of the list on the profile pages
+    ///
+    /// returns: &'static str
+    fn slug(&self) -> &'static str {
+        match self {
+            GameList::Playing => "playing",
+            GameList::Backlog => "backlog",
+            GameList::Replays => "replays",
+            GameList::Custom => "custom",
+            GameList::Completed => "completed",
+            GameList::Retired => "retired",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone, Default)]
+pub struct ListEntry {
+    pub hltb_id: Option<u32>,
+    pub title: String,
+    pub platform: Option<Platform>,
+    /// The time logged so far, in seconds
+    pub progress: Option<f32>,
+    /// The time logged by the user for the main story, in seconds
+    pub main_story: Option<f32>,
+    /// The time logged by the user for the main story and extras, in seconds
+    pub main_extra: Option<f32>,
+    /// The time logged by the user to complete the game 100%, in seconds
+    pub completionist: Option<f32>,
+    /// The date the user finished the game, as `YYYY-MM-DD`
+    pub finished_on: Option<String>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+pub struct Completion {
+    pub hltb_id: Option<u32>,
+    pub title: String,
+    pub platform: Option<Platform>,
+    /// The date the user finished the game, as `YYYY-MM-DD`
+    pub finished_on: Option<String>,
+    /// The time the user took to finish the game, in seconds
+    pub time: Option<f32>,
+}
+
+impl From<ListEntry> for Completion {
+    fn from(entry: ListEntry) -> Completion {
+        Completion {
+            time: entry
+                .progress
+                .or(entry.completionist)
+                .or(entry.main_extra)
+                .or(entry.main_story),
+            hltb_id: entry.hltb_id,
+            title: entry.title,
+            platform: entry.platform,
+            finished_on: entry.finished_on,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone, Default)]
+pub struct UserProfile {
+    pub username: String,
+    pub playing: Option<u32>,
+    pub backlog: Option<u32>,
+    pub replays: Option<u32>,
+    pub custom: Option<u32>,
+    pub completed: Option<u32>,
+    pub retired: Option<u32>,
+    /// The number of games on the profile, across all lists
+    pub games_played: Option<u32>,
+    /// The total playtime logged on the profile, in seconds
+    pub total_playtime: Option<f32>,
+    /// Every statistic shown on the profile, keyed by its label
+    pub stats: BTreeMap<String, String>,
+}
+
+/// Fetches the summary statistics of a public user profile
+///
+/// # Arguments
+///
+/// * `username`:  &str - The name of the user on How Long to Beat
+///
+/// returns: Result<UserProfile, Box<dyn Error, Global>>
+pub async fn get_user(username: &str) -> Result<UserProfile, Box<dyn Error>> {
+    get_user_with_sandbox(username, true).await
+}
+
+/// Fetches the summary statistics of a public user profile with custom sandbox setting
+///
+/// # Arguments
+///
+/// * `username`:  &str - The name of the user on How Long to Beat
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+///
+/// returns: Result<UserProfile, Box<dyn Error, Global>>
+pub async fn get_user_with_sandbox(
+    username: &str,
+    sandbox: bool,
+) -> Result<UserProfile, Box<dyn Error>> {
+    get_user_with_session(username, sandbox, None).await
+}
+
+/// Fetches the summary statistics of a user profile, logged in to access private profiles
+///
+/// # Arguments
+///
+/// * `username`:  &str - The name of the user on How Long to Beat
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+/// * `session`:  Option<&Session> - The session to browse with, if any
+///
+/// returns: Result<UserProfile, Box<dyn Error, Global>>
+pub async fn get_user_with_session(
+    username: &str,
+    sandbox: bool,
+    session: Option<&Session>,
+) -> Result<UserProfile, Box<dyn Error>> {
+    let url = BASE_URL.to_owned() + "user/" + &encode(username);
+    let content =
+        fetch_page_content_with_session(&url, "div[class*='_profile_stats']", sandbox, session)?;
+    parse_user_profile(&content, username)
+}
+
+/// Fetches every entry of one of the game lists of a user, following pagination
+///
+/// # Arguments
+///
+/// * `username`:  &str - The name of the user on How Long to Beat
+/// * `list`:  GameList - The list to fetch
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+///
+/// returns: Result<Vec<ListEntry>, Box<dyn Error, Global>>
+pub async fn get_user_list_with_sandbox(
+    username: &str,
+    list: GameList,
+    sandbox: bool,
+) -> Result<Vec<ListEntry>, Box<dyn Error>> {
+    get_user_list_with_session(username, list, sandbox, None).await
+}
+
+/// Fetches every entry of one of the game lists of a user, logged in to access private lists
+///
+/// # Arguments
+///
+/// * `username`:  &str - The name of the user on How Long to Beat
+/// * `list`:  GameList - The list to fetch
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+/// * `session`:  Option<&Session> - The session to browse with, if any
+///
+/// returns: Result<Vec<ListEntry>, Box<dyn Error, Global>>
+pub async fn get_user_list_with_session(
+    username: &str,
+    list: GameList,
+    sandbox: bool,
+    session: Option<&Session>,
+) -> Result<Vec<ListEntry>, Box<dyn Error>> {
+    let mut entries: Vec<ListEntry> = Vec::new();
+    for page in 1..=MAX_LIST_PAGES {
+        let url = format!(
+            "{}user/{}/games/{}/{}",
+            BASE_URL,
+            encode(username),
+            list.slug(),
+            page
+        );
+        let content = match fetch_page_content_with_session(&url, "main table", sandbox, session) {
+            Ok(content) => content,
+            Err(e) if page == 1 => return Err(e),
+            Err(_) => break,
+        };
+        let page_entries = parse_user_list(&content);
+        // Past the last page the site keeps serving the last one
+        if page_entries.is_empty() || entries.ends_with(&page_entries) {
+            break;
+        }
+        entries.extend(page_entries);
+    }
+    Ok(entries)
+}
+
+/// Fetches every entry of one of the game lists of a user, following pagination
+///
+/// # Arguments
+///
+/// * `username`:  &str - The name of the user on How Long to Beat
+/// * `list`:  GameList - The list to fetch
+///
+/// returns: Result<Vec<ListEntry>, Box<dyn Error, Global>>
+pub async fn get_user_list(
+    username: &str,
+    list: GameList,
+) -> Result<Vec<ListEntry>, Box<dyn Error>> {
+    get_user_list_with_sandbox(username, list, true).await
+}
+
+/// Fetches the completion history of a user, oldest first
+///
+/// Completions without a known date are listed last.
+///
+/// # Arguments
+///
+/// * `username`:  &str - The name of the user on How Long to Beat
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+///
+/// returns: Result<Vec<Completion>, Box<dyn Error, Global>>
+pub async fn get_user_completions_with_sandbox(
+    username: &str,
+    sandbox: bool,
+) -> Result<Vec<Completion>, Box<dyn Error>> {
+    get_user_completions_with_session(username, sandbox, None).await
+}
+
+/// Fetches the completion history of a user, logged in to access private profiles
+///
+/// # Arguments
+///
+/// * `username`:  &str - The name of the user on How Long to Beat
+/// * `sandbox`:  bool - Whether to enable sandbox mode for the browser (set to false for Docker/CI environments)
+/// * `session`:  Option<&Session> - The session to browse with, if any
+///
+/// returns: Result<Vec<Completion>, Box<dyn Error, Global>>
+pub async fn get_user_completions_with_session(
+    username: &str,
+    sandbox: bool,
+    session: Option<&Session>,
+) -> Result<Vec<Completion>, Box<dyn Error>> {
+    let entries =
+        get_user_list_with_session(username, GameList::Completed, sandbox, session).await?;
+    let mut completions: Vec<Completion> = entries.into_iter().map(Completion::from).collect();
+    completions.sort_by(|a, b| match (&a.finished_on, &b.finished_on) {
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    Ok(completions)
+}
+
+/// Fetches the completion history of a user, oldest first
+///
+/// # Arguments
+///
+/// * `username`:  &str - The name of the user on How Long to Beat
+///
+/// returns: Result<Vec<Completion>, Box<dyn Error, Global>>
+pub async fn get_user_completions(username: &str) -> Result<Vec<Completion>, Box<dyn Error>> {
+    get_user_completions_with_sandbox(username, true).await
+}
+
+/// Converts a date as displayed on the site (e.g. "Jan 5, 2024", "January 5th 2024" or
+/// "2024-01-05") to `YYYY-MM-DD`
+///
+/// # Arguments
+///
+/// * `text`:  &str - The date to convert
+///
+/// returns: Option<String>
+fn parse_date(text: &str) -> Option<String> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+    let parts: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || c == ',' || c == '-' || c == '/')
+        .filter(|p| !p.is_empty())
+        .collect();
+    let (year, month, day) = match parts.as_slice() {
+        [year, month, day] if year.len() == 4 => (
+            year.parse::<u32>().ok()?,
+            month.parse::<u32>().ok()?,
+            day.parse::<u32>().ok()?,
+        ),
+        [month, day, year] => {
+            let month = month.to_lowercase();
+            let month = MONTHS.iter().position(|m| month.starts_with(m))? as u32 + 1;
+            let day = day.trim_end_matches(|c: char| c.is_alphabetic());
+            (year.parse::<u32>().ok()?, month, day.parse::<u32>().ok()?)
+        }
+        _ => return None,
+    };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some(format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+/// Parses the entries of a game list page
+///
+/// Columns are identified by their header, so lists showing different columns share the parser.
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the list page
+///
+/// returns: Vec<ListEntry>
+fn parse_user_list(content: &str) -> Vec<ListEntry> {
+    let document = Html::parse_document(content);
+    let header_selector = Selector::parse("main table thead th").unwrap();
+    let row_selector = Selector::parse("main table tbody tr").unwrap();
+    let cell_selector = Selector::parse("td").unwrap();
+    let link_selector = Selector::parse(GAME_LINK_SELECTOR).unwrap();
+
+    let headers: Vec<String> = document
+        .select(&header_selector)
+        .map(|th| th.text().collect::<String>().trim().to_lowercase())
+        .collect();
+
+    let mut entries = Vec::new();
+    for row in document.select(&row_selector) {
+        let mut entry = ListEntry::default();
+        for (header, cell) in headers.iter().zip(row.select(&cell_selector)) {
+            let text = cell.text().collect::<String>().trim().to_string();
+            match header.as_str() {
+                "title" | "game" => {
+                    entry.title = text;
+                    entry.hltb_id = cell
+                        .select(&link_selector)
+                        .next()
+                        .and_then(|a| a.value().attr("href"))
+                        .and_then(game_id_from_href);
+                }
+                "platform" => {
+                    entry.platform = Some(text).filter(|t| !t.is_empty()).map(Platform::from)
+                }
+                "progress" => entry.progress = convert_hours_minutes_to_sec_opt(&text),
+                "main story" | "main" => entry.main_story = convert_hours_minutes_to_sec_opt(&text),
+                "main + extra" | "main + extras" => {
+                    entry.main_extra = convert_hours_minutes_to_sec_opt(&text)
+                }
+                "completionist" | "100%" => {
+                    entry.completionist = convert_hours_minutes_to_sec_opt(&text)
+                }
+                "completed" | "finished" | "date" => entry.finished_on = parse_date(&text),
+                _ => {}
+            }
+        }
+        if !entry.title.is_empty() {
+            entries.push(entry);
+        }
+    }
+    entries
+}
+
+/// Parses the statistics of a user profile page
+///
+/// Every statistic is a block holding a value and a label (e.g. "1,254" and "Completed").
+///
+/// # Arguments
+///
+/// * `content`:  &str - The HTML of the profile page
+/// * `username`:  &str - The name of the user
+///
+/// returns: Result<UserProfile, Box<dyn Error, Global>>
+fn parse_user_profile(content: &str, username: &str) -> Result<UserProfile, Box<dyn Error>> {
+    let document = Html::parse_document(content);
+    let selector = Selector::parse("div[class*='_profile_stats'] > *").unwrap();
+
+    let mut profile = UserProfile {
+        username: username.to_string(),
+        ..Default::default()
+    };
+    for element in document.select(&selector) {
+        let parts: Vec<String> = element
+            .text()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+        if parts.len() < 2 {
+            continue;
+        }
+        let (value, label) = (parts[0].clone(), parts[1..].join(" "));
+        let count = value.replace(',', "").parse::<u32>().ok();
+        match label.to_lowercase().as_str() {
+            "playing" => profile.playing = count,
+            "backlog" => profile.backlog = count,
+            "replays" => profile.replays = count,
+            "custom" => profile.custom = count,
+            "completed" => profile.completed = count,
+            "retired" => profile.retired = count,
+            "games" | "total games" | "games played" => profile.games_played = count,
+            "playtime" | "total playtime" | "time played" => {
+                profile.total_playtime = convert_hours_minutes_to_sec_opt(&value)
+            }
+            _ => {}
+        }
+        profile.stats.insert(label, value);
+    }
+
+    if profile.stats.is_empty() {
+        return Err(format!("No statistics found for user {}", username).into());
+    }
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILE: &str = r#"<html><body>
+        <div class="UserProfile_profile_stats__x1Y2z">
+            <div><h4>3</h4><h5>Playing</h5></div>
+            <div><h4>1,254</h4><h5>Backlog</h5></div>
+            <div><h4>412</h4><h5>Completed</h5></div>
+            <div><h4>17</h4><h5>Retired</h5></div>
+            <div><h4>1,686</h4><h5>Games</h5></div>
+            <div><h4>3021h 15m</h4><h5>Playtime</h5></div>
+        </div>
+    </body></html>"#;
+
+    #[test]
+    fn test_parse_user_profile() {
+        let profile = parse_user_profile(PROFILE, "someone").unwrap();
+        assert_eq!(profile.username, "someone");
+        assert_eq!(profile.playing, Some(3));
+        assert_eq!(profile.backlog, Some(1254));
+        assert_eq!(profile.completed, Some(412));
+        assert_eq!(profile.retired, Some(17));
+        assert_eq!(profile.games_played, Some(1686));
+        assert_eq!(profile.replays, None);
+        assert_eq!(profile.total_playtime, Some(3021.0 * 3600.0 + 15.0 * 60.0));
+        assert_eq!(profile.stats.get("Backlog"), Some(&"1,254".to_string()));
+    }
+
+    #[test]
+    fn test_parse_user_list() {
+        let content = r#"<html><body><main><table>
+            <thead><tr><th>Title</th><th>Platform</th><th>Progress</th><th>Main Story</th></tr></thead>
+            <tbody>
+                <tr><td><a href="/game/5900">Metal Gear</a></td><td>NES</td><td>2h 30m</td><td>--</td></tr>
+                <tr><td><a href="/game/129232">Helldivers 2</a></td><td>PC</td><td>--</td><td>31h</td></tr>
+            </tbody>
+        </table></main></body></html>"#;
+        let entries = parse_user_list(content);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].hltb_id, Some(5900));
+        assert_eq!(entries[0].platform, Some(Platform::Nes));
+        assert_eq!(entries[0].progress, Some(9000.0));
+        assert_eq!(entries[0].main_story, None);
+        assert_eq!(entries[1].title, "Helldivers 2");
+        assert_eq!(entries[1].main_story, Some(31.0 * 3600.0));
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("Jan 5, 2024"), Some("2024-01-05".to_string()));
+        assert_eq!(
+            parse_date("September 21st 2023"),
+            Some("2023-09-21".to_string())
+        );
+        assert_eq!(parse_date("2022-11-30"), Some("2022-11-30".to_string()));
+        assert_eq!(parse_date("--"), None);
+    }
+
+    #[test]
+    fn test_completion_from_entry() {
+        let entry = ListEntry {
+            hltb_id: Some(5900),
+            title: "Metal Gear".to_string(),
+            main_story: Some(15000.0),
+            finished_on: Some("2024-01-05".to_string()),
+            ..Default::default()
+        };
+        let completion = Completion::from(entry);
+        assert_eq!(completion.time, Some(15000.0));
+        assert_eq!(completion.finished_on, Some("2024-01-05".to_string()));
+    }
+
+    #[test]
+    fn test_parse_user_profile_without_stats() {
+        assert!(parse_user_profile("<html></html>", "nobody").is_err());
+    }
+}
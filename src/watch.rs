@@ -0,0 +1,218 @@
+//! Watch mode: periodically re-fetches tracked games and reports time changes.
+//!
+//! When a webhook is configured, every change beyond the threshold is POSTed as
+//! JSON. The payload carries both a `content` (Discord) and a `text` (Slack)
+//! message so it can be pointed straight at either service.
+
+pub use crate::delta::TimeChange;
+use crate::{search_by_id_with_sandbox, Game};
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::time::Duration;
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+pub struct WebhookConfig {
+    /// The URL the payload is POSTed to
+    pub url: String,
+    /// The relative change (e.g. 0.05 for 5%) a time must move by to be reported
+    pub threshold: f32,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+pub struct GameChange {
+    pub hltb_id: u32,
+    pub title: String,
+    pub changes: Vec<TimeChange>,
+}
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    content: &'a str,
+    text: &'a str,
+    changes: &'a [GameChange],
+}
+
+pub struct Watcher {
+    ids: Vec<u32>,
+    sandbox: bool,
+    interval: Duration,
+    threshold: f32,
+    webhook: Option<WebhookConfig>,
+    last: HashMap<u32, Game>,
+}
+
+impl Watcher {
+    /// Creates a new Watcher tracking a list of games, checked every hour by default
+    ///
+    /// # Arguments
+    ///
+    /// * `ids`:  Vec<u32> - The How Long to Beat IDs of the tracked games
+    /// * `sandbox`:  bool - Whether to enable sandbox mode for the browser
+    ///
+    /// returns: Watcher
+    pub fn new(ids: Vec<u32>, sandbox: bool) -> Watcher {
+        Watcher {
+            ids,
+            sandbox,
+            interval: Duration::from_secs(3600),
+            threshold: 0.0,
+            webhook: None,
+            last: HashMap::new(),
+        }
+    }
+
+    /// Sets the delay between two checks
+    pub fn interval(mut self, interval: Duration) -> Watcher {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets the webhook notified of changes; its threshold also applies to the reported changes
+    pub fn webhook(mut self, webhook: WebhookConfig) -> Watcher {
+        self.threshold = webhook.threshold;
+        self.webhook = Some(webhook);
+        self
+    }
+
+    /// Fetches every tracked game once and returns the changes since the previous check
+    ///
+    /// The first check only records the current times. Games that fail to load are skipped
+    /// and checked again next time. When the webhook can't be notified, the times aren't
+    /// recorded either, so the changes are reported again by the next check.
+    ///
+    /// returns: Result<Vec<GameChange>, Box<dyn Error, Global>>
+    pub async fn check(&mut self) -> Result<Vec<GameChange>, Box<dyn Error>> {
+        let mut report = Vec::new();
+        let mut fetched = Vec::new();
+        for &hltb_id in &self.ids {
+            let game = match search_by_id_with_sandbox(hltb_id, self.sandbox).await {
+                Ok(game) => game,
+                Err(_) => continue,
+            };
+            if let Some(old) = self.last.get(&hltb_id) {
+                let changes = changed_times(old, &game, self.threshold);
+                if !changes.is_empty() {
+                    report.push(GameChange {
+                        hltb_id,
+                        title: game.title.clone(),
+                        changes,
+                    });
+                }
+            }
+            fetched.push((hltb_id, game));
+        }
+        if let Some(webhook) = &self.webhook {
+            if !report.is_empty() {
+                notify(webhook, &report).await?;
+            }
+        }
+        self.last.extend(fetched);
+        Ok(report)
+    }
+
+    /// Checks the tracked games forever, waiting for the interval between checks
+    ///
+    /// A failed check is logged to stderr and the next one runs on schedule.
+    ///
+    /// returns: Result<(), Box<dyn Error, Global>>
+    pub async fn run(&mut self) -> Result<(), Box<dyn Error>> {
+        loop {
+            if let Err(e) = self.check().await {
+                eprintln!("Failed to check the watched games: {}", e);
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+}
+
+/// POSTs a list of changes to a webhook
+///
+/// # Arguments
+///
+/// * `webhook`:  &WebhookConfig - The webhook to notify
+/// * `changes`:  &[GameChange] - The changes to report
+///
+/// returns: Result<(), Box<dyn Error, Global>>
+pub async fn notify(webhook: &WebhookConfig, changes: &[GameChange]) -> Result<(), Box<dyn Error>> {
+    let message = changes
+        .iter()
+        .map(|c| format!("{} times changed ({} values)", c.title, c.changes.len()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let payload = WebhookPayload {
+        content: &message,
+        text: &message,
+        changes,
+    };
+    reqwest::Client::new()
+        .post(&webhook.url)
+        .json(&payload)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// Lists the times that moved by more than a relative threshold between two fetches of a game
+///
+/// A time that appears or disappears is always reported.
+///
+/// # Arguments
+///
+/// * `old`:  &Game - The previous fetch
+/// * `new`:  &Game - The current fetch
+/// * `threshold`:  f32 - The minimum relative change to report
+///
+/// returns: Vec<TimeChange>
+pub fn changed_times(old: &Game, new: &Game, threshold: f32) -> Vec<TimeChange> {
+    new.diff(old)
+        .times
+        .into_iter()
+        .filter(|change| change.exceeds(threshold))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Styles;
+
+    fn game(main_average: f32) -> Game {
+        Game::new(
+            "Metal Gear".to_string(),
+            5900,
+            Some(Styles::new(Some(main_average), Some(14400.0), None, None)),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_changed_times_threshold() {
+        assert!(changed_times(&game(15000.0), &game(15300.0), 0.05).is_empty());
+        let changes = changed_times(&game(15000.0), &game(16500.0), 0.05);
+        assert_eq!(
+            changes,
+            vec![TimeChange {
+                field: "main_story.average".to_string(),
+                old: Some(15000.0),
+                new: Some(16500.0),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_changed_times_new_style() {
+        let mut new = game(15000.0);
+        new.co_op = Some(Styles::new(Some(3600.0), None, None, None));
+        let changes = changed_times(&game(15000.0), &new, 0.5);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "co_op.average");
+        assert_eq!(changes[0].old, None);
+    }
+}